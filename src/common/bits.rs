@@ -96,10 +96,8 @@ impl<'a> Bits<'a> {
             .0
             .iter()
             .zip(other.0.iter())
-            .try_fold(Vec::new(), |mut acc, (a, b)| {
-                acc.push(*a ^ *b);
-                Ok(acc)
-            })?)
+            .map(|(a, b)| *a ^ *b)
+            .collect())
     }
 }
 
@@ -0,0 +1,187 @@
+// Parser for the NIST CAVP response files (`.rsp`)
+use thiserror::Error;
+
+use super::hex::Hex;
+
+/// A single test case of a CAVP response file
+///
+/// A file is made of sections of bracketed parameters followed by records:
+///
+/// ```text
+/// # comment
+/// [SHA-256]
+/// [PredictionResistance = False]
+///
+/// COUNT = 0
+/// EntropyInput = ca851911...
+/// AdditionalInput =
+/// AdditionalInput =
+/// ```
+///
+/// Every record inherits the parameters of its section. Field names may
+/// repeat (e.g. `AdditionalInput` once per generate call), so the fields are
+/// kept in file order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CavpVector {
+    pub count: usize,
+    params: Vec<(String, String)>,
+    fields: Vec<(String, String)>,
+}
+
+impl CavpVector {
+    /// Value of a section parameter, `Some("")` for a flag such as `[AES-256 use df]`
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// First value of a field
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Every value of a repeated field, in file order
+    pub fn get_all(&self, name: &str) -> Vec<&str> {
+        self.fields
+            .iter()
+            .filter(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    /// First value of a field decoded from hex
+    pub fn bytes(&self, name: &str) -> Result<Vec<u8>, CavpError> {
+        let value = self
+            .get(name)
+            .ok_or_else(|| CavpError::MissingField(name.to_string()))?;
+        decode_hex(value)
+    }
+
+    /// Every value of a repeated field decoded from hex
+    pub fn all_bytes(&self, name: &str) -> Result<Vec<Vec<u8>>, CavpError> {
+        self.get_all(name).into_iter().map(decode_hex).collect()
+    }
+}
+
+/// Parse the test cases of a response file
+pub fn parse(input: &str) -> Result<Vec<CavpVector>, CavpError> {
+    let mut vectors: Vec<CavpVector> = Vec::new();
+    let mut params: Vec<(String, String)> = Vec::new();
+    // a bracket line after a record starts a new section
+    let mut in_header = false;
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(param) = line.strip_prefix('[').and_then(|p| p.strip_suffix(']')) {
+            if !in_header {
+                params.clear();
+                in_header = true;
+            }
+            let (key, value) = split_pair(param).unwrap_or((param.trim(), ""));
+            params.push((key.to_string(), value.to_string()));
+            continue;
+        }
+        in_header = false;
+
+        let (key, value) = split_pair(line).ok_or(CavpError::MalformedLine(index + 1))?;
+        if key == "COUNT" {
+            let count = value
+                .parse()
+                .map_err(|_| CavpError::MalformedLine(index + 1))?;
+            vectors.push(CavpVector {
+                count,
+                params: params.clone(),
+                fields: Vec::new(),
+            });
+        } else {
+            let vector = vectors
+                .last_mut()
+                .ok_or(CavpError::FieldBeforeCount(index + 1))?;
+            vector.fields.push((key.to_string(), value.to_string()));
+        }
+    }
+
+    Ok(vectors)
+}
+
+/// Split `key = value`, the value may be empty
+fn split_pair(line: &str) -> Option<(&str, &str)> {
+    line.split_once('=')
+        .map(|(key, value)| (key.trim(), value.trim()))
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, CavpError> {
+    if !value.len().is_multiple_of(2) {
+        return Err(CavpError::InvalidHex(value.to_string()));
+    }
+    Hex::hex_to_bytes(value.as_bytes()).map_err(|_| CavpError::InvalidHex(value.to_string()))
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum CavpError {
+    #[error("Malformed line {0}")]
+    MalformedLine(usize),
+
+    #[error("Field before the first COUNT on line {0}")]
+    FieldBeforeCount(usize),
+
+    #[error("Missing field {0}")]
+    MissingField(String),
+
+    #[error("Invalid hex value {0}")]
+    InvalidHex(String),
+}
+
+#[test]
+fn test_parse() {
+    let input = "
+# comment
+[SHA-256]
+[PredictionResistance = False]
+
+COUNT = 0
+EntropyInput = 00ff
+AdditionalInput =
+AdditionalInput = 0102
+
+[SHA-256]
+[PredictionResistance = True]
+
+COUNT = 0
+EntropyInput = 10
+";
+    let vectors = parse(input).unwrap();
+    assert_eq!(vectors.len(), 2);
+
+    assert_eq!(vectors[0].param("SHA-256"), Some(""));
+    assert_eq!(vectors[0].param("PredictionResistance"), Some("False"));
+    assert_eq!(vectors[0].bytes("EntropyInput").unwrap(), vec![0x00, 0xff]);
+    assert_eq!(
+        vectors[0].all_bytes("AdditionalInput").unwrap(),
+        vec![vec![], vec![0x01, 0x02]]
+    );
+    assert_eq!(vectors[1].param("PredictionResistance"), Some("True"));
+    assert_eq!(vectors[1].get("Nonce"), None);
+
+    assert_eq!(
+        parse("Key = 00").unwrap_err(),
+        CavpError::FieldBeforeCount(1)
+    );
+    assert_eq!(
+        parse("COUNT = 0\nno value").unwrap_err(),
+        CavpError::MalformedLine(2)
+    );
+    assert_eq!(
+        vectors[1].bytes("Nonce").unwrap_err(),
+        CavpError::MissingField("Nonce".to_string())
+    );
+}
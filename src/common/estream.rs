@@ -0,0 +1,315 @@
+// Parser for the eSTREAM stream cipher test vector files
+use thiserror::Error;
+
+use super::hex::Hex;
+
+/// A test vector of an eSTREAM file
+///
+/// Each vector gives the key, the IV, chunks of the keystream (the
+/// encryption of zero bytes) and the XOR digest of the whole keystream:
+///
+/// ```text
+/// Set 1, vector#  0:
+///                          key = 80000000000000000000
+///                           IV = 00000000000000000000
+///                stream[0..63] = 38EB86FF730D7A9CAF8DF13A4420540D
+///                                ...
+///             stream[192..255] = ...
+///                   xor-digest = ...
+/// ```
+///
+/// Long values continue on the following lines, and an empty line ends the
+/// vector. The lines outside the vectors (title, set descriptions) are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EstreamVector {
+    pub set: usize,
+    pub vector: usize,
+    pub key: Vec<u8>,
+    pub iv: Vec<u8>,
+    /// Keystream chunks, as their start offset and bytes
+    pub stream: Vec<(usize, Vec<u8>)>,
+    pub xor_digest: Vec<u8>,
+}
+
+impl EstreamVector {
+    /// Number of keystream bytes covered by the vector, up to the end of the last chunk
+    pub fn stream_len(&self) -> usize {
+        self.stream
+            .iter()
+            .map(|(start, bytes)| start + bytes.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Check `keystream`, at least [`EstreamVector::stream_len`] bytes, against the chunks and the digest
+    pub fn check(&self, keystream: &[u8]) -> Result<(), EstreamError> {
+        let len = self.stream_len();
+        if keystream.len() < len {
+            return Err(EstreamError::StreamTooShort(keystream.len()));
+        }
+
+        for (start, bytes) in &self.stream {
+            if &keystream[*start..start + bytes.len()] != bytes {
+                return Err(EstreamError::StreamMismatch(*start));
+            }
+        }
+        if xor_digest(&keystream[..len], self.xor_digest.len()) != self.xor_digest {
+            return Err(EstreamError::DigestMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// XOR of the blocks of `size` bytes of `stream`, the last one padded with zeros
+pub fn xor_digest(stream: &[u8], size: usize) -> Vec<u8> {
+    let mut digest = vec![0; size];
+    for block in stream.chunks(size.max(1)) {
+        for (d, b) in digest.iter_mut().zip(block) {
+            *d ^= b;
+        }
+    }
+    digest
+}
+
+/// Field of a vector, to which a line holding only hex digits is appended
+#[derive(Clone, Copy)]
+enum Field {
+    Key,
+    Iv,
+    /// Keystream chunk, up to the offset `end` included
+    Stream {
+        end: usize,
+    },
+    XorDigest,
+}
+
+/// Check the length of the chunk filled by `field`, the last one of `vector`,
+/// once its last line is read
+fn close_field(field: Option<Field>, vector: Option<&EstreamVector>) -> Result<(), EstreamError> {
+    if let (Some(Field::Stream { end }), Some(vector)) = (field, vector) {
+        // stream[a..b] holds the bytes a to b included
+        let (start, bytes) = vector.stream.last().unwrap();
+        if bytes.len() != end - start + 1 {
+            return Err(EstreamError::ChunkLength {
+                set: vector.set,
+                vector: vector.vector,
+                start: *start,
+                end,
+                len: bytes.len(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Parse the test vectors of a file
+pub fn parse(input: &str) -> Result<Vec<EstreamVector>, EstreamError> {
+    let mut vectors: Vec<EstreamVector> = Vec::new();
+    let mut last_field: Option<Field> = None;
+    // a vector goes from its header to the next empty line
+    let mut in_vector = false;
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line.trim();
+        let malformed = || EstreamError::MalformedLine(index + 1);
+
+        if let Some(header) = line.strip_prefix("Set ") {
+            let (set, vector) = header
+                .strip_suffix(':')
+                .and_then(|h| h.split_once(", vector#"))
+                .ok_or_else(malformed)?;
+            close_field(last_field.take(), vectors.last())?;
+            vectors.push(EstreamVector {
+                set: set.trim().parse().map_err(|_| malformed())?,
+                vector: vector.trim().parse().map_err(|_| malformed())?,
+                key: vec![],
+                iv: vec![],
+                stream: vec![],
+                xor_digest: vec![],
+            });
+            in_vector = true;
+            continue;
+        }
+
+        let Some(vector) = vectors.last_mut().filter(|_| in_vector && !line.is_empty()) else {
+            close_field(last_field.take(), vectors.last())?;
+            in_vector = false;
+            continue;
+        };
+        let (field, value) = if let Some((name, value)) = line.split_once('=') {
+            close_field(last_field.take(), Some(vector))?;
+            let field = match name.trim() {
+                "key" => Field::Key,
+                "IV" => Field::Iv,
+                "xor-digest" => Field::XorDigest,
+                name => {
+                    let (start, end) = name
+                        .strip_prefix("stream[")
+                        .and_then(|r| r.strip_suffix(']'))
+                        .and_then(|r| r.split_once(".."))
+                        .ok_or_else(malformed)?;
+                    let start: usize = start.parse().map_err(|_| malformed())?;
+                    let end: usize = end.parse().map_err(|_| malformed())?;
+                    if end < start {
+                        return Err(malformed());
+                    }
+                    vector.stream.push((start, vec![]));
+                    Field::Stream { end }
+                }
+            };
+            (field, value.trim())
+        } else if line.bytes().all(|c| c.is_ascii_hexdigit()) {
+            (last_field.ok_or_else(malformed)?, line)
+        } else {
+            return Err(malformed());
+        };
+
+        let bytes = decode_hex(value, index)?;
+        match field {
+            Field::Key => vector.key.extend(bytes),
+            Field::Iv => vector.iv.extend(bytes),
+            Field::Stream { .. } => vector.stream.last_mut().unwrap().1.extend(bytes),
+            Field::XorDigest => vector.xor_digest.extend(bytes),
+        }
+        last_field = Some(field);
+    }
+    close_field(last_field, vectors.last())?;
+
+    for vector in &vectors {
+        if vector.key.is_empty() || vector.stream.is_empty() || vector.xor_digest.is_empty() {
+            return Err(EstreamError::IncompleteVector(vector.set, vector.vector));
+        }
+    }
+
+    Ok(vectors)
+}
+
+fn decode_hex(value: &str, index: usize) -> Result<Vec<u8>, EstreamError> {
+    if !value.len().is_multiple_of(2) {
+        return Err(EstreamError::InvalidHex(index + 1));
+    }
+    Hex::hex_to_bytes(value.as_bytes()).map_err(|_| EstreamError::InvalidHex(index + 1))
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum EstreamError {
+    #[error("Malformed line {0}")]
+    MalformedLine(usize),
+
+    #[error("Invalid hex value on line {0}")]
+    InvalidHex(usize),
+
+    #[error("Set {0}, vector {1} has no key, keystream or digest")]
+    IncompleteVector(usize, usize),
+
+    #[error("Set {set}, vector {vector}: stream[{start}..{end}] has {len} bytes")]
+    ChunkLength {
+        set: usize,
+        vector: usize,
+        start: usize,
+        end: usize,
+        len: usize,
+    },
+
+    #[error("Keystream of {0} bytes shorter than the vector")]
+    StreamTooShort(usize),
+
+    #[error("Keystream differs from the chunk at offset {0}")]
+    StreamMismatch(usize),
+
+    #[error("Keystream differs from the XOR digest")]
+    DigestMismatch,
+}
+
+#[test]
+fn test_parse() {
+    let input = "
+Primitive Name: Test
+====================
+
+Test vectors -- set 1
+=====================
+
+(stream is generated by encrypting 8 zero bytes)
+
+Set 1, vector#  0:
+                         key = 0001
+                          IV = 02
+               stream[0..3] = 0102
+                              0304
+               stream[6..7] = 0708
+                  xor-digest = 00
+                               08
+
+Set 2, vector# 10:
+                         key = FF
+                          IV =
+               stream[0..0] = AA
+                  xor-digest = AA
+
+End of test vectors
+";
+    let vectors = parse(input).unwrap();
+    assert_eq!(vectors.len(), 2);
+
+    let vector = &vectors[0];
+    assert_eq!((vector.set, vector.vector), (1, 0));
+    assert_eq!(vector.key, vec![0x00, 0x01]);
+    assert_eq!(vector.iv, vec![0x02]);
+    assert_eq!(vector.stream, vec![(0, vec![1, 2, 3, 4]), (6, vec![7, 8])]);
+    assert_eq!(vector.stream_len(), 8);
+    assert_eq!(vector.xor_digest, vec![0x00, 0x08]);
+    assert_eq!((vectors[1].set, vectors[1].vector), (2, 10));
+    assert!(vectors[1].iv.is_empty());
+
+    // the digest XORs blocks of its own length: 0102 ^ 0304 ^ 0506 ^ 0708
+    assert_eq!(vector.check(&[1, 2, 3, 4, 5, 6, 7, 8]), Ok(()));
+    assert_eq!(
+        vector.check(&[1, 2, 3, 4, 5, 6, 7, 9]),
+        Err(EstreamError::StreamMismatch(6))
+    );
+    assert_eq!(
+        vector.check(&[1, 2, 3, 4, 0, 6, 7, 8]),
+        Err(EstreamError::DigestMismatch)
+    );
+    assert_eq!(
+        vector.check(&[1, 2, 3, 4]),
+        Err(EstreamError::StreamTooShort(4))
+    );
+
+    assert_eq!(
+        parse("Set 1, vector# 0:\nkey = 0G").unwrap_err(),
+        EstreamError::InvalidHex(2)
+    );
+    assert_eq!(
+        parse("Set 1, vector# 0:\nkey: 00").unwrap_err(),
+        EstreamError::MalformedLine(2)
+    );
+    assert_eq!(
+        parse("Set 1, vector# 0:\nkey = 00").unwrap_err(),
+        EstreamError::IncompleteVector(1, 0)
+    );
+    assert_eq!(
+        parse("Set 1, vector# 0:\nkey = 00\nstream[0..3] = 010203\nxor-digest = 00").unwrap_err(),
+        EstreamError::ChunkLength {
+            set: 1,
+            vector: 0,
+            start: 0,
+            end: 3,
+            len: 3
+        }
+    );
+    // a chunk is checked when the next field, the end of the vector or of the file closes it
+    assert!(matches!(
+        parse("Set 1, vector# 0:\nkey = 00\nstream[0..1] = 00\nstream[2..2] = 00\n"),
+        Err(EstreamError::ChunkLength { start: 0, .. })
+    ));
+    assert!(matches!(
+        parse("Set 1, vector# 0:\nkey = 00\nxor-digest = 00\nstream[4..5] = 00\n0011"),
+        Err(EstreamError::ChunkLength { len: 3, .. })
+    ));
+    assert_eq!(
+        parse("Set 1, vector# 0:\nkey = 00\nstream[3..0] = 00").unwrap_err(),
+        EstreamError::MalformedLine(3)
+    );
+}
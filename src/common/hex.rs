@@ -26,7 +26,7 @@ impl<'a> Hex<'a> {
 
 impl fmt::Display for Hex<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for byte in &*self.0 {
+        for byte in self.0 {
             // format as uppercase hex
             write!(f, "{:X}", byte)?;
         }
@@ -36,7 +36,7 @@ impl fmt::Display for Hex<'_> {
 
 impl Hex<'_> {
     pub fn as_slice(&self) -> &[u8] {
-        &self.0
+        self.0
     }
 
     /// convert hex to bits in msb-first order
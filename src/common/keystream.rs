@@ -0,0 +1,89 @@
+// Buffer of keystream bits for the stream ciphers producing several bits per step
+
+/// Output bits of a keystream generator not used yet
+///
+/// Hardware-oriented stream ciphers (Trivium, Grain, A5/1) are fastest when
+/// several clocks are computed at once, but their keystream is consumed a
+/// few bits at a time. The bits of a step wait here, the first one in the
+/// most significant position, until they are used.
+#[derive(Debug, Clone, Default)]
+pub struct KeystreamBuffer {
+    bits: u64,
+    len: u32,
+}
+
+impl KeystreamBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of bits waiting in the buffer
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The next `n` bits, $1 \le n \le 64$, the first one in bit `n - 1`
+    ///
+    /// When the buffer runs out, `step` is called for more bits: it returns
+    /// between 1 and 64 bits and their number, the first one in the most
+    /// significant position as well.
+    pub fn next_bits(&mut self, n: u32, mut step: impl FnMut() -> (u64, u32)) -> u64 {
+        assert!((1..=64).contains(&n), "between 1 and 64 bits at a time");
+        let low_bits = |x: u64, n: u32| x & (u64::MAX >> (64 - n));
+
+        let mut bits = 0;
+        let mut needed = n;
+        while needed > 0 {
+            if self.len == 0 {
+                (self.bits, self.len) = step();
+            }
+            let taken = needed.min(self.len);
+            self.len -= taken;
+            let chunk = low_bits(self.bits >> self.len, taken);
+            bits = if taken == 64 {
+                chunk
+            } else {
+                bits << taken | chunk
+            };
+            needed -= taken;
+        }
+        bits
+    }
+
+    /// The next 8 bits as a byte, the first one in its most significant bit
+    pub fn next_byte(&mut self, step: impl FnMut() -> (u64, u32)) -> u8 {
+        self.next_bits(8, step) as u8
+    }
+}
+
+#[test]
+fn test_keystream_buffer() {
+    // a counter producing 5 bits per step: 00000 00001 00010 ...
+    let mut counter = 0;
+    let mut step = || {
+        counter += 1;
+        (counter - 1, 5)
+    };
+
+    let mut buffer = KeystreamBuffer::new();
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.next_bits(3, &mut step), 0b000);
+    assert_eq!(buffer.len(), 2);
+    // 00 00001 00010
+    assert_eq!(buffer.next_bits(12, &mut step), 0b0000_0010_0010);
+    // 00011 001
+    assert_eq!(buffer.next_byte(&mut step), 0b0001_1001);
+    assert_eq!(buffer.len(), 2);
+
+    // 64 bits across steps of 64 bits
+    let mut words = [0xFEDC_BA98_7654_3210u64, 0x0123_4567_89AB_CDEF].into_iter();
+    let mut buffer = KeystreamBuffer::new();
+    let mut step = || (words.next().unwrap(), 64);
+    assert_eq!(buffer.next_bits(4, &mut step), 0xF);
+    assert_eq!(buffer.next_bits(64, &mut step), 0xEDCB_A987_6543_2100);
+    assert_eq!(buffer.len(), 60);
+}
@@ -1,2 +1,5 @@
 pub mod bits;
+pub mod cavp;
+pub mod estream;
 pub mod hex;
+pub mod keystream;
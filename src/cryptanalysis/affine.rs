@@ -0,0 +1,115 @@
+// Exhaustive key search and known-plaintext attack on the affine cipher
+use super::{
+    exhaustive,
+    scoring::{Candidate, Scorer},
+};
+use crate::{
+    math::modular_arithmetic::mod_inverse::mod_inverse,
+    symmetric_encryption::{
+        affine::affine::Affine,
+        traits::ClassicalCipher,
+        utils::{alphabet_size_i32, default_alphabet, get_char_index, PassThrough},
+    },
+};
+
+/// The 312 valid keys $(a, b)$: $\gcd(a, 26) = 1$ leaves 12 values of $a$
+pub fn keys() -> impl Iterator<Item = (i32, i32)> {
+    Affine::default().keys().collect::<Vec<_>>().into_iter()
+}
+
+/// Try the 312 keys and return the candidates, most English-like first
+pub fn brute_force(cipher: &str, scorer: Scorer) -> Vec<Candidate<(i32, i32)>> {
+    let affine = Affine::new(default_alphabet().clone(), PassThrough::Preserve);
+    exhaustive::brute_force(&affine, cipher, scorer)
+}
+
+/// Most likely key and plaintext
+pub fn crack(cipher: &str, scorer: Scorer) -> Candidate<(i32, i32)> {
+    brute_force(cipher, scorer).swap_remove(0)
+}
+
+/// Recover the key from a plaintext and its ciphertext
+///
+/// Two pairs $(x_1, y_1)$, $(x_2, y_2)$ give
+/// $y_1 - y_2 \equiv a (x_1 - x_2) \pmod{26}$, so
+/// $a = (y_1 - y_2)(x_1 - x_2)^{-1}$ as soon as $x_1 - x_2$ is invertible,
+/// and $b = y_1 - a x_1$. The key is then checked against every pair.
+/// Characters outside the alphabet are skipped, case is ignored.
+pub fn known_plaintext(plaintext: &str, cipher: &str) -> Result<(i32, i32), String> {
+    let pairs = letter_pairs(plaintext, cipher)?;
+    let n = alphabet_size_i32();
+
+    let key = pairs
+        .iter()
+        .flat_map(|p1| pairs.iter().map(move |p2| (p1, p2)))
+        .find_map(|(&(x1, y1), &(x2, y2))| {
+            let inverse = mod_inverse((x1 - x2).rem_euclid(n), n).ok()?;
+            let a = ((y1 - y2) * inverse).rem_euclid(n);
+            Some((a, (y1 - a * x1).rem_euclid(n)))
+        })
+        .ok_or("Not enough distinct plaintext characters to recover the key".to_string())?;
+
+    let (a, b) = key;
+    if pairs.iter().any(|&(x, y)| (a * x + b).rem_euclid(n) != y) {
+        return Err("Plaintext and ciphertext are not related by an affine key".to_string());
+    }
+
+    Ok(key)
+}
+
+/// Indices of the matching letters of the plaintext and the ciphertext
+fn letter_pairs(plaintext: &str, cipher: &str) -> Result<Vec<(i32, i32)>, String> {
+    let letters = |text: &str| -> Vec<i32> {
+        text.chars()
+            .filter_map(|c| get_char_index(c.to_ascii_lowercase()).ok())
+            .collect()
+    };
+
+    let (x, y) = (letters(plaintext), letters(cipher));
+    if x.len() != y.len() {
+        return Err("Plaintext and ciphertext have different lengths".to_string());
+    }
+
+    Ok(x.into_iter().zip(y).collect())
+}
+
+#[test]
+fn test_brute_force_affine() {
+    use crate::symmetric_encryption::affine::affine::encrypt_with;
+
+    assert_eq!(keys().count(), 312);
+    assert!(keys().all(|(a, _)| a % 2 == 1 && a != 13));
+
+    let text = "The quick brown fox jumps over the lazy dog, then runs back into the forest.";
+    for (a, b) in [(3, 7), (5, 0), (25, 12), (17, 3)] {
+        let cipher = encrypt_with(text, a, b, default_alphabet(), PassThrough::Preserve).unwrap();
+
+        for scorer in [Scorer::ChiSquared, Scorer::Bigram, Scorer::Quadgram] {
+            let candidate = crack(&cipher, scorer);
+            assert_eq!(candidate.key, (a, b));
+            assert_eq!(candidate.plaintext, text);
+        }
+    }
+}
+
+#[test]
+fn test_known_plaintext_affine() {
+    use crate::symmetric_encryption::affine::affine::encrypt;
+
+    assert_eq!(known_plaintext("hello", "ctoox"), Ok((3, 7)));
+    assert_eq!(
+        known_plaintext("If", &encrypt("if", 11, 4).unwrap()),
+        Ok((11, 4))
+    );
+
+    // e - a = 4 is not invertible, but e - h = 23 is
+    assert_eq!(
+        known_plaintext("ahe", &encrypt("ahe", 19, 2).unwrap()),
+        Ok((19, 2))
+    );
+
+    // "a" and "c" differ by 2: a and a + 13 both fit
+    assert!(known_plaintext("ac", "ha").is_err());
+    assert!(known_plaintext("hello", "ctoo").is_err());
+    assert!(known_plaintext("hello", "abcde").is_err());
+}
@@ -0,0 +1,51 @@
+// Exhaustive key search on the Caesar cipher
+use super::{
+    exhaustive,
+    scoring::{Candidate, Scorer},
+};
+use crate::symmetric_encryption::{
+    caesar::caesar::Caesar,
+    utils::{default_alphabet, PassThrough},
+};
+
+/// Try the 26 shifts and return the candidates, most English-like first
+///
+/// Characters outside the alphabet are preserved, so spacing and
+/// punctuation of the ciphertext carry over to the candidates.
+pub fn brute_force(cipher: &str, scorer: Scorer) -> Vec<Candidate<i32>> {
+    let caesar = Caesar::new(default_alphabet().clone(), PassThrough::Preserve);
+    exhaustive::brute_force(&caesar, cipher, scorer)
+}
+
+/// Most likely shift and plaintext
+pub fn crack(cipher: &str, scorer: Scorer) -> Candidate<i32> {
+    brute_force(cipher, scorer).swap_remove(0)
+}
+
+#[test]
+fn test_brute_force_caesar() {
+    use crate::symmetric_encryption::caesar::caesar::encrypt_with;
+
+    let text = "It was the best of times, it was the worst of times, it was the age of wisdom.";
+    for shift in [1, 7, 13, 25] {
+        let cipher = encrypt_with(text, shift, default_alphabet(), PassThrough::Preserve).unwrap();
+
+        for scorer in [
+            Scorer::ChiSquared,
+            Scorer::Monogram,
+            Scorer::Bigram,
+            Scorer::Quadgram,
+        ] {
+            let candidates = brute_force(&cipher, scorer);
+            assert_eq!(candidates.len(), 26);
+            assert_eq!(candidates[0].key, shift);
+            assert_eq!(candidates[0].plaintext, text);
+            assert!(candidates[0].score >= candidates[1].score);
+        }
+    }
+
+    // quadgrams still work on a short text
+    let candidate = crack("Pmttw, Ewztl", Scorer::Quadgram);
+    assert_eq!(candidate.key, 8);
+    assert_eq!(candidate.plaintext, "Hello, World");
+}
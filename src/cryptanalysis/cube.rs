@@ -0,0 +1,564 @@
+// Cube attack (Dinur and Shamir) on ciphers whose output bit has a low degree in the key and IV
+use crate::math::linear_algebra::gf2::Gf2System;
+use crate::rng::traits::Rng;
+use crate::symmetric_encryption::trivium::trivium::Trivium;
+
+/// Number of random key pairs of the linearity test in [`find_maxterms`]
+const LINEARITY_TESTS: usize = 16;
+
+/// Keystream of a cipher as a function of the key and IV bits, one bit per byte
+pub trait BlackBox {
+    fn key_len(&self) -> usize;
+
+    fn iv_len(&self) -> usize;
+
+    /// Keystream bit number `bit`, from 0
+    fn output(&self, key: &[u8], iv: &[u8], bit: usize) -> u8;
+
+    /// Sum over GF(2) of keystream bit `bit` for the $2^d$ values of the
+    /// cube bits, the other IV bits being 0
+    ///
+    /// Writing the output as $t_C \cdot p(k, v) \oplus q(k, v)$, where $t_C$
+    /// is the product of the cube bits and no term of $q$ contains all of
+    /// them, the sum is the superpoly $p$: every term of $q$ misses a cube
+    /// bit and is counted an even number of times.
+    fn cube_sum(&self, key: &[u8], cube: &[usize], bit: usize) -> u8 {
+        cube_sum_with(|iv| self.output(key, iv, bit), self.iv_len(), cube)
+    }
+}
+
+/// Keystream of Trivium with `rounds` initialization clocks
+///
+/// Bit $j$ of the keystream is also the first bit with $\text{rounds} + j$
+/// initialization clocks: the following bits give superpolys of more rounds,
+/// but in other key bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReducedTrivium {
+    pub rounds: u64,
+}
+
+impl BlackBox for ReducedTrivium {
+    fn key_len(&self) -> usize {
+        80
+    }
+
+    fn iv_len(&self) -> usize {
+        80
+    }
+
+    fn output(&self, key: &[u8], iv: &[u8], bit: usize) -> u8 {
+        // the last bit comes first
+        Trivium::with_rounds(key, iv, self.rounds)
+            .unwrap()
+            .key_stream(bit + 1)
+            .get_bit(0)
+    }
+
+    /// Bit-sliced sum: 64 values of the cube bits at once
+    ///
+    /// Each bit of a `u64` is a separate instance of the cipher, the first
+    /// 6 cube bits taking every value across the 64 instances. The registers
+    /// are kept as the sequences of their bits: with $a_t$ the bit entering
+    /// the first register at clock $t$, $s_i = a_{t - i}$ and a clock only
+    /// appends one word to each sequence.
+    fn cube_sum(&self, key: &[u8], cube: &[usize], bit: usize) -> u8 {
+        const LANES: [u64; 6] = [
+            0xaaaa_aaaa_aaaa_aaaa,
+            0xcccc_cccc_cccc_cccc,
+            0xf0f0_f0f0_f0f0_f0f0,
+            0xff00_ff00_ff00_ff00,
+            0xffff_0000_ffff_0000,
+            0xffff_ffff_0000_0000,
+        ];
+        let spread = |bit: u8| 0u64.wrapping_sub(bit as u64);
+        let (sliced, outer) = cube.split_at(cube.len().min(LANES.len()));
+        // the instances repeated when there are less than 6 cube bits are ignored
+        let lanes = u64::MAX >> (64 - (1 << sliced.len()));
+        let rounds = self.rounds as usize + bit;
+
+        let mut sum = 0;
+        for assignment in 0..1u64 << outer.len() {
+            let mut iv = [0u64; 80];
+            for (&i, &lane) in sliced.iter().zip(&LANES) {
+                iv[i] = lane;
+            }
+            for (j, &i) in outer.iter().enumerate() {
+                iv[i] = spread((assignment >> j) as u8 & 1);
+            }
+
+            // a[k] is s_{93-k}, b[k] is s_{177-k} and c[k] is s_{288-k} before the first clock
+            let mut a = vec![0; 93 + rounds + 1];
+            let mut b = vec![0; 84 + rounds + 1];
+            let mut c = vec![0; 111 + rounds + 1];
+            for i in 0..80 {
+                a[92 - i] = spread(key[i]);
+                b[83 - i] = iv[i];
+            }
+            c[..3].fill(u64::MAX);
+
+            for t in 0..rounds {
+                let t1 = a[t + 27] ^ a[t] ^ (a[t + 2] & a[t + 1]) ^ b[t + 6];
+                let t2 = b[t + 15] ^ b[t] ^ (b[t + 2] & b[t + 1]) ^ c[t + 24];
+                let t3 = c[t + 45] ^ c[t] ^ (c[t + 2] & c[t + 1]) ^ a[t + 24];
+                a[t + 93] = t3;
+                b[t + 84] = t1;
+                c[t + 111] = t2;
+            }
+            let t = rounds;
+            let z = a[t + 27] ^ a[t] ^ b[t + 15] ^ b[t] ^ c[t + 45] ^ c[t];
+            sum ^= (z & lanes).count_ones() as u8 & 1;
+        }
+        sum
+    }
+}
+
+/// A cube of IV bits whose superpoly is linear in the key bits
+///
+/// The superpoly is $c \oplus \bigoplus_{i \in I} k_i$, with $c$ the
+/// `constant` and $I$ the `key_bits`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Maxterm {
+    pub cube: Vec<usize>,
+    /// Keystream bit summed over the cube
+    pub output_bit: usize,
+    pub constant: u8,
+    pub key_bits: Vec<usize>,
+}
+
+/// Degree of a superpoly, as far as the linearity test can tell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Degree {
+    Constant,
+    Linear,
+    Nonlinear,
+}
+
+/// Blum-Luby-Rubinfeld linearity test of the superpoly of `cube`
+///
+/// An affine function satisfies $p(0) \oplus p(x) \oplus p(y) = p(x \oplus y)$
+/// for all keys $x, y$. The test tries `tests` random pairs: a nonlinear
+/// superpoly fails one of them with high probability. A superpoly taking
+/// the same value on every key tried is reported constant.
+pub fn superpoly_degree<B, R>(
+    black_box: &B,
+    cube: &[usize],
+    bit: usize,
+    tests: usize,
+    rng: &mut R,
+) -> Degree
+where
+    B: BlackBox,
+    R: Rng,
+{
+    let key_len = black_box.key_len();
+    let zero = black_box.cube_sum(&vec![0; key_len], cube, bit);
+    let mut constant = true;
+    for _ in 0..tests {
+        let x = random_bits(key_len, rng);
+        let y = random_bits(key_len, rng);
+        let xy: Vec<u8> = x.iter().zip(&y).map(|(a, b)| a ^ b).collect();
+        let px = black_box.cube_sum(&x, cube, bit);
+        let py = black_box.cube_sum(&y, cube, bit);
+        if zero ^ px ^ py != black_box.cube_sum(&xy, cube, bit) {
+            return Degree::Nonlinear;
+        }
+        constant &= px == zero && py == zero;
+    }
+
+    if constant {
+        Degree::Constant
+    } else {
+        Degree::Linear
+    }
+}
+
+/// Superpoly of `cube`, assumed linear: the constant is its value on the
+/// zero key, and key bit $i$ appears if flipping it alone changes the value
+pub fn superpoly<B: BlackBox>(black_box: &B, cube: &[usize], bit: usize) -> Maxterm {
+    let mut key = vec![0; black_box.key_len()];
+    let constant = black_box.cube_sum(&key, cube, bit);
+    let key_bits = (0..key.len())
+        .filter(|&i| {
+            key[i] = 1;
+            let sum = black_box.cube_sum(&key, cube, bit);
+            key[i] = 0;
+            sum != constant
+        })
+        .collect();
+
+    Maxterm {
+        cube: cube.to_vec(),
+        output_bit: bit,
+        constant,
+        key_bits,
+    }
+}
+
+/// Preprocessing: search for cubes with linearly independent linear superpolys
+///
+/// Each of the `attempts` random walks, on keystream bits 0 to
+/// `output_bits - 1` in turn, starts from a random cube of `dimension` IV
+/// bits. A nonlinear superpoly means the cube is too small and a random bit
+/// is added, a constant one that it is too large and a random bit is
+/// removed, until the superpoly is linear or the cube exceeds
+/// `max_dimension` bits. The linear superpolys which predict the cube sums
+/// of other random keys, and are independent of the ones already found, are
+/// kept.
+pub fn find_maxterms<B, R>(
+    black_box: &B,
+    output_bits: usize,
+    dimension: usize,
+    max_dimension: usize,
+    attempts: usize,
+    rng: &mut R,
+) -> Vec<Maxterm>
+where
+    B: BlackBox,
+    R: Rng,
+{
+    let (key_len, iv_len) = (black_box.key_len(), black_box.iv_len());
+    let mut maxterms = vec![];
+    let mut system = Gf2System::new(key_len);
+
+    for attempt in 0..attempts {
+        let bit = attempt % output_bits.max(1);
+        let mut cube = vec![];
+        while cube.len() < dimension.min(iv_len) {
+            add_random_bit(&mut cube, iv_len, rng);
+        }
+
+        while !cube.is_empty() && cube.len() <= max_dimension {
+            match superpoly_degree(black_box, &cube, bit, LINEARITY_TESTS, rng) {
+                Degree::Nonlinear if cube.len() == iv_len => break,
+                Degree::Nonlinear => add_random_bit(&mut cube, iv_len, rng),
+                Degree::Constant => {
+                    cube.swap_remove(rng.next_below(cube.len() as u64) as usize);
+                }
+                Degree::Linear => {
+                    cube.sort_unstable();
+                    let maxterm = superpoly(black_box, &cube, bit);
+                    if !holds(black_box, &maxterm, LINEARITY_TESTS, rng) {
+                        break;
+                    }
+                    let mut extended = system.clone();
+                    extended.add_equation(&coefficients(&maxterm.key_bits, key_len), 0);
+                    if extended.rank() > system.rank() {
+                        system = extended;
+                        maxterms.push(maxterm);
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    maxterms
+}
+
+/// Online phase: recover the key from the maxterms and an oracle returning
+/// keystream bits of the unknown key for a chosen IV
+///
+/// Each cube sum computed with the oracle gives the value of a linear
+/// superpoly, i.e. a linear equation in the key bits. The key bits left
+/// free by the equations, at most `max_guessed`, are guessed and every
+/// candidate key is tested with `verify`, e.g. against some known keystream.
+pub fn recover_key<O, V>(
+    oracle: O,
+    maxterms: &[Maxterm],
+    key_len: usize,
+    iv_len: usize,
+    max_guessed: usize,
+    verify: V,
+) -> Result<Vec<u8>, String>
+where
+    O: Fn(&[u8], usize) -> u8,
+    V: Fn(&[u8]) -> bool,
+{
+    let mut system = Gf2System::new(key_len);
+    for maxterm in maxterms {
+        let sum = cube_sum_with(|iv| oracle(iv, maxterm.output_bit), iv_len, &maxterm.cube);
+        system.add_equation(
+            &coefficients(&maxterm.key_bits, key_len),
+            sum ^ maxterm.constant,
+        );
+    }
+
+    let free = system.free_variables();
+    if free.len() > max_guessed {
+        return Err(format!(
+            "{} key bits are not determined by the superpolys",
+            free.len()
+        ));
+    }
+
+    for guess in 0..1u64 << free.len() {
+        let mut candidate = system.clone();
+        for (j, &i) in free.iter().enumerate() {
+            candidate.add_equation(&coefficients(&[i], key_len), (guess >> j) as u8);
+        }
+        let key = candidate.solve().map_err(|e| e.to_string())?;
+        if verify(&key) {
+            return Ok(key);
+        }
+    }
+
+    Err("No key is consistent with the cube sums".to_string())
+}
+
+/// Whether the cube sums of `tests` random keys are the values of the superpoly
+///
+/// A superpoly with a nonlinear term which is rarely 1 can pass the
+/// linearity test, but then it is unlikely to predict every sum.
+fn holds<B: BlackBox, R: Rng>(black_box: &B, maxterm: &Maxterm, tests: usize, rng: &mut R) -> bool {
+    (0..tests).all(|_| {
+        let key = random_bits(black_box.key_len(), rng);
+        let value = maxterm
+            .key_bits
+            .iter()
+            .fold(maxterm.constant, |value, &i| value ^ key[i]);
+        black_box.cube_sum(&key, &maxterm.cube, maxterm.output_bit) == value
+    })
+}
+
+/// Sum of `output` over the values of the cube bits, the other IV bits being 0
+fn cube_sum_with<F: FnMut(&[u8]) -> u8>(mut output: F, iv_len: usize, cube: &[usize]) -> u8 {
+    let mut iv = vec![0; iv_len];
+    let mut sum = 0;
+    for assignment in 0..1u64 << cube.len() {
+        for (j, &i) in cube.iter().enumerate() {
+            iv[i] = (assignment >> j) as u8 & 1;
+        }
+        sum ^= output(&iv);
+    }
+    sum
+}
+
+fn add_random_bit<R: Rng>(cube: &mut Vec<usize>, iv_len: usize, rng: &mut R) {
+    loop {
+        let i = rng.next_below(iv_len as u64) as usize;
+        if !cube.contains(&i) {
+            cube.push(i);
+            return;
+        }
+    }
+}
+
+fn random_bits<R: Rng>(len: usize, rng: &mut R) -> Vec<u8> {
+    (0..len).map(|_| rng.next_below(2) as u8).collect()
+}
+
+/// Coefficient words of $\bigoplus_{i \in I} k_i$
+fn coefficients(key_bits: &[usize], key_len: usize) -> Vec<u64> {
+    let mut words = vec![0; key_len.div_ceil(64)];
+    for &i in key_bits {
+        words[i / 64] |= 1 << (i % 64);
+    }
+    words
+}
+
+/// Maxterms of Trivium reduced to 576 rounds on keystream bits 0 to 31, found
+/// with [`find_maxterms`] from cubes of 5 to 12 bits
+#[cfg(test)]
+const MAXTERMS_576: [(&[usize], usize, u8, &[usize]); 69] = [
+    (&[11, 31, 35, 43, 44, 66, 74], 0, 0, &[61]),
+    (&[3, 7, 19, 33, 40, 65, 76], 1, 0, &[18]),
+    (&[5, 7, 40, 68, 71, 72, 77], 2, 0, &[64]),
+    (&[0, 15, 16, 42, 44, 45, 53, 71], 3, 0, &[58]),
+    (&[4, 17, 32, 38, 54, 57, 76], 4, 0, &[63]),
+    (&[19, 37, 49, 52, 61, 66, 69], 5, 0, &[65]),
+    (&[6, 20, 21, 31, 39, 41, 50, 55], 6, 0, &[20]),
+    (&[0, 5, 6, 29, 31, 47, 48, 77, 78, 79], 7, 0, &[55, 61]),
+    (&[11, 15, 17, 25, 49, 68, 73, 76], 8, 0, &[62]),
+    (&[12, 13, 24, 38, 62, 64, 65, 78], 10, 0, &[9]),
+    (&[14, 29, 34, 42, 48, 49, 56, 62, 72], 11, 0, &[31]),
+    (&[2, 8, 9, 22, 53, 71, 73, 78], 12, 0, &[56, 62]),
+    (&[0, 24, 25, 42, 47, 56, 58, 60, 67, 72, 75], 15, 0, &[0]),
+    (&[9, 17, 22, 37, 43, 56, 78], 19, 1, &[41]),
+    (&[6, 10, 33, 34, 36, 49, 65, 66, 67], 20, 0, &[38]),
+    (&[6, 12, 35, 59, 60, 61, 72, 75, 78], 21, 0, &[48]),
+    (&[16, 21, 27, 34, 36, 52, 75, 78], 22, 0, &[36]),
+    (&[4, 23, 36, 46, 51, 53, 58, 67], 24, 0, &[17]),
+    (&[2, 5, 8, 12, 36, 48, 52, 68], 25, 0, &[57]),
+    (&[13, 16, 34, 47, 54, 62, 68, 71, 74], 26, 0, &[15]),
+    (&[11, 18, 27, 28, 32, 38, 40, 46, 62], 27, 1, &[15, 30, 42]),
+    (&[4, 13, 14, 19, 20, 36, 37, 55, 56, 77], 29, 0, &[25]),
+    (&[8, 16, 17, 30, 48, 50, 56, 57, 73, 75], 31, 1, &[52]),
+    (&[1, 10, 38, 49, 58, 60], 0, 0, &[6]),
+    (&[17, 30, 36, 42, 52, 56, 58], 1, 0, &[19]),
+    (&[4, 11, 20, 24, 52, 53, 77, 78], 3, 0, &[68]),
+    (&[16, 17, 19, 20, 23, 38, 64, 77, 78], 5, 0, &[10]),
+    (&[7, 9, 21, 22, 40, 49, 76, 79], 6, 0, &[53]),
+    (&[10, 12, 13, 14, 29, 44, 56, 70, 72], 12, 1, &[59]),
+    (&[16, 18, 25, 30, 35, 42, 48, 52, 76], 22, 1, &[24]),
+    (&[1, 6, 18, 32, 47, 61, 71, 77], 23, 0, &[60]),
+    (&[16, 21, 38, 54, 55, 56, 67, 69, 70, 74], 0, 0, &[54]),
+    (&[12, 13, 14, 24, 28, 41, 53, 61], 2, 0, &[28]),
+    (&[6, 7, 8, 11, 35, 55, 75], 3, 0, &[37]),
+    (&[21, 29, 32, 47, 52, 57, 60, 73], 4, 0, &[51]),
+    (&[13, 25, 28, 48, 56, 63, 73, 74], 7, 0, &[67]),
+    (&[26, 38, 42, 62, 64, 68, 70], 8, 1, &[60, 64, 66]),
+    (&[11, 22, 32, 47, 48, 50, 67], 10, 0, &[11]),
+    (&[4, 14, 23, 34, 47, 59, 60, 65], 13, 1, &[5]),
+    (&[13, 16, 29, 38, 58, 62, 67, 77], 14, 0, &[33]),
+    (&[7, 16, 32, 35, 43, 48, 52, 54, 64, 68, 78], 20, 0, &[39]),
+    (&[26, 31, 33, 35, 45, 51, 62, 63, 72, 77], 25, 1, &[3]),
+    (&[5, 20, 23, 42, 57, 58, 64, 65, 71], 30, 0, &[12]),
+    (&[8, 9, 21, 34, 37, 40, 58, 67], 31, 0, &[29]),
+    (&[0, 3, 28, 41, 47, 49, 59, 60, 63], 1, 0, &[4]),
+    (&[2, 11, 16, 19, 62, 70, 78], 5, 0, &[21, 66]),
+    (&[5, 6, 13, 37, 42, 49, 53, 63], 11, 1, &[7]),
+    (&[9, 15, 37, 45, 48, 56, 74], 14, 1, &[26]),
+    (&[4, 16, 36, 43, 54, 61, 75, 79], 28, 0, &[45]),
+    (&[2, 8, 27, 33, 44, 53, 54], 4, 0, &[35]),
+    (&[12, 23, 38, 55, 59, 61, 62, 66], 19, 0, &[22]),
+    (
+        &[1, 26, 30, 40, 42, 45, 46, 48, 54],
+        31,
+        0,
+        &[19, 31, 44, 46],
+    ),
+    (&[10, 14, 24, 46, 56, 72, 76], 5, 1, &[14]),
+    (&[10, 14, 25, 30, 37, 53, 54, 67], 6, 1, &[34, 64]),
+    (&[2, 11, 34, 43, 56, 69], 7, 1, &[13]),
+    (&[14, 21, 22, 44, 52, 53, 63, 64, 68], 12, 0, &[8]),
+    (&[4, 5, 20, 29, 37, 46, 66, 74], 14, 1, &[1, 55, 61]),
+    (&[0, 5, 7, 8, 14, 40, 49], 16, 0, &[44]),
+    (&[7, 19, 40, 45, 51, 60, 78, 79], 17, 0, &[42]),
+    (&[8, 10, 12, 50, 71, 74, 79], 19, 0, &[23]),
+    (&[4, 11, 13, 25, 41, 47, 48, 78], 27, 0, &[43]),
+    (
+        &[17, 26, 37, 40, 41, 44, 45, 47, 54, 62, 76],
+        20,
+        1,
+        &[2, 65],
+    ),
+    (&[3, 24, 29, 35, 46, 66, 76], 22, 0, &[5, 50]),
+    (&[10, 29, 36, 59, 63, 72, 77], 31, 0, &[40]),
+    (&[4, 26, 27, 30, 33, 40, 62, 79], 12, 0, &[32]),
+    (&[0, 10, 14, 25, 27, 58, 63], 13, 0, &[27]),
+    (&[4, 18, 31, 39, 44, 55, 56, 57, 59], 3, 1, &[16]),
+    (
+        &[10, 21, 31, 32, 42, 45, 47, 52, 54, 61, 62],
+        27,
+        0,
+        &[47, 56],
+    ),
+    (&[5, 20, 30, 55, 59, 62, 73, 75], 9, 0, &[49]),
+];
+
+/// $p = v_0 v_1 (k_0 \oplus k_2 \oplus k_4 v_2) \oplus v_0 k_1 k_3 \oplus v_2 \oplus 1$, on 5 key and 3 IV bits
+#[cfg(test)]
+struct Polynomial;
+
+#[cfg(test)]
+impl BlackBox for Polynomial {
+    fn key_len(&self) -> usize {
+        5
+    }
+
+    fn iv_len(&self) -> usize {
+        3
+    }
+
+    fn output(&self, k: &[u8], v: &[u8], _: usize) -> u8 {
+        (v[0] & v[1] & (k[0] ^ k[2] ^ (k[4] & v[2]))) ^ (v[0] & k[1] & k[3]) ^ v[2] ^ 1
+    }
+}
+
+#[test]
+fn test_superpoly() {
+    use crate::rng::splitmix64::SplitMix64;
+
+    let mut rng = SplitMix64::new(5);
+    let degree =
+        |cube: &[usize], rng: &mut SplitMix64| superpoly_degree(&Polynomial, cube, 0, 16, rng);
+    assert_eq!(degree(&[0, 1], &mut rng), Degree::Linear);
+    assert_eq!(degree(&[0], &mut rng), Degree::Nonlinear);
+    assert_eq!(degree(&[0, 1, 2], &mut rng), Degree::Linear);
+    assert_eq!(degree(&[1, 2], &mut rng), Degree::Constant);
+
+    let maxterm = superpoly(&Polynomial, &[0, 1], 0);
+    assert_eq!((maxterm.constant, maxterm.key_bits), (0, vec![0, 2]));
+    assert_eq!(superpoly(&Polynomial, &[0, 1, 2], 0).key_bits, vec![4]);
+    // the empty cube is the polynomial itself, with a constant term
+    assert_eq!(superpoly(&Polynomial, &[], 0).constant, 1);
+
+    // the walks stop at the first linear superpoly, k0 ^ k2
+    let mut maxterms = find_maxterms(&Polynomial, 1, 1, 3, 20, &mut rng);
+    assert_eq!(maxterms.len(), 1);
+    assert_eq!(maxterms[0].key_bits, vec![0, 2]);
+    maxterms.push(superpoly(&Polynomial, &[0, 1, 2], 0));
+
+    // the superpolys determine k0 ^ k2 and k4, the 3 other bits are guessed
+    let key = [1, 0, 1, 1, 1];
+    let oracle = |iv: &[u8], bit: usize| Polynomial.output(&key, iv, bit);
+    let verify = |candidate: &[u8]| {
+        (0..8u8).all(|v| {
+            let iv = [v & 1, v >> 1 & 1, v >> 2];
+            Polynomial.output(candidate, &iv, 0) == oracle(&iv, 0)
+        })
+    };
+    let recovered = recover_key(oracle, &maxterms, 5, 3, 3, verify).unwrap();
+    assert!(verify(&recovered));
+    assert_eq!(recovered[4], 1);
+    assert_eq!(recovered[0] ^ recovered[2], 0);
+    assert!(recover_key(oracle, &maxterms, 5, 3, 2, verify).is_err());
+}
+
+#[test]
+fn test_cube_attack_trivium() {
+    use crate::rng::{splitmix64::SplitMix64, traits::Rng};
+
+    // the bit-sliced sums agree with the cipher
+    struct Reference(ReducedTrivium);
+    impl BlackBox for Reference {
+        fn key_len(&self) -> usize {
+            80
+        }
+        fn iv_len(&self) -> usize {
+            80
+        }
+        fn output(&self, key: &[u8], iv: &[u8], bit: usize) -> u8 {
+            self.0.output(key, iv, bit)
+        }
+    }
+    let mut rng = SplitMix64::new(576);
+    let key: Vec<u8> = (0..80).map(|_| rng.next_below(2) as u8).collect();
+    let trivium = ReducedTrivium { rounds: 576 };
+    for (cube, bit) in [
+        (vec![], 0),
+        (vec![7], 3),
+        (vec![0, 13, 18, 32, 64, 68, 72, 79], 9),
+    ] {
+        assert_eq!(
+            trivium.cube_sum(&key, &cube, bit),
+            Reference(trivium).cube_sum(&key, &cube, bit)
+        );
+    }
+
+    let maxterms: Vec<Maxterm> = MAXTERMS_576
+        .iter()
+        .map(|&(cube, output_bit, constant, key_bits)| Maxterm {
+            cube: cube.to_vec(),
+            output_bit,
+            constant,
+            key_bits: key_bits.to_vec(),
+        })
+        .collect();
+    assert_eq!(superpoly(&trivium, &maxterms[0].cube, 0), maxterms[0]);
+
+    // the attacker chooses IVs and observes the keystream, 69 equations leave 11 bits to guess
+    let oracle = |iv: &[u8], bit: usize| trivium.output(&key, iv, bit);
+    let known = Trivium::with_rounds(&key, &[0; 80], 576)
+        .unwrap()
+        .key_stream(64)
+        .reverse();
+    let verify = |candidate: &[u8]| {
+        Trivium::with_rounds(candidate, &[0; 80], 576)
+            .unwrap()
+            .key_stream(64)
+            .reverse()
+            == known
+    };
+    assert_eq!(recover_key(oracle, &maxterms, 80, 80, 11, verify), Ok(key));
+}
@@ -0,0 +1,69 @@
+// Exhaustive key search on any classical cipher with a small key space
+use super::scoring::{rank, Candidate, Scorer};
+use crate::symmetric_encryption::traits::ClassicalCipher;
+
+/// Decrypt with every key of `cipher` and return the candidates, most English-like first
+///
+/// Keys for which decryption fails are skipped.
+pub fn brute_force<C: ClassicalCipher>(
+    cipher: &C,
+    text: &str,
+    scorer: Scorer,
+) -> Vec<Candidate<C::Key>> {
+    let mut candidates: Vec<Candidate<C::Key>> = cipher
+        .keys()
+        .filter_map(|key| {
+            let plaintext = cipher.decrypt(text, &key).ok()?;
+            Some(Candidate {
+                key,
+                score: scorer.score(&plaintext),
+                plaintext,
+            })
+        })
+        .collect();
+
+    rank(&mut candidates);
+    candidates
+}
+
+/// Most likely key and plaintext, `None` if no key decrypts `text`
+pub fn crack<C: ClassicalCipher>(
+    cipher: &C,
+    text: &str,
+    scorer: Scorer,
+) -> Option<Candidate<C::Key>> {
+    let mut candidates = brute_force(cipher, text, scorer);
+    (!candidates.is_empty()).then(|| candidates.swap_remove(0))
+}
+
+#[test]
+fn test_exhaustive_search() {
+    use crate::symmetric_encryption::{
+        affine::affine::Affine,
+        caesar::caesar::Caesar,
+        utils::{Alphabet, PassThrough},
+    };
+
+    let text = "Meet me by the old oak tree at midnight";
+    let caesar = Caesar::new(Alphabet::lowercase(), PassThrough::Preserve);
+    let cipher = caesar.encrypt(text, &11).unwrap();
+    let candidate = crack(&caesar, &cipher, Scorer::Quadgram).unwrap();
+    assert_eq!((candidate.key, candidate.plaintext.as_str()), (11, text));
+
+    let affine = Affine::new(Alphabet::lowercase(), PassThrough::Preserve);
+    let cipher = affine.encrypt(text, &(17, 4)).unwrap();
+    let candidates = brute_force(&affine, &cipher, Scorer::Quadgram);
+    assert_eq!(candidates.len(), 312);
+    assert_eq!(candidates[0].key, (17, 4));
+    assert_eq!(candidates[0].plaintext, text);
+
+    // a Caesar shift is an affine key with a = 1
+    let cipher = caesar.encrypt(text, &5).unwrap();
+    assert_eq!(
+        crack(&affine, &cipher, Scorer::Quadgram).unwrap().key,
+        (1, 5)
+    );
+
+    let strict = Caesar::default();
+    assert!(crack(&strict, "not: letters", Scorer::Quadgram).is_none());
+}
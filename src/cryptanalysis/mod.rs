@@ -0,0 +1,11 @@
+pub mod affine;
+pub mod caesar;
+pub mod cube;
+pub mod exhaustive;
+pub mod period;
+pub mod rc4;
+pub mod scoring;
+pub mod substitution;
+pub mod transposition;
+pub mod vigenere;
+pub mod xor;
@@ -0,0 +1,21 @@
+// Periods of the keys recovered by the attacks on repeating-key ciphers
+
+/// Shortest prefix of `key` whose repetition gives `key`, `"lemonlemon"` becomes `"lemon"`
+///
+/// A key length that is a multiple of the real one solves the same
+/// ciphertext, the recovered key is then the real key repeated.
+pub fn shortest_period<T: PartialEq + Clone>(key: &[T]) -> Vec<T> {
+    let period = (1..=key.len())
+        .find(|&p| key.len().is_multiple_of(p) && (p..key.len()).all(|i| key[i] == key[i - p]))
+        .unwrap_or(key.len());
+    key[..period].to_vec()
+}
+
+#[test]
+fn test_shortest_period() {
+    let lemon: Vec<char> = "lemonlemon".chars().collect();
+    assert_eq!(shortest_period(&lemon), "lemon".chars().collect::<Vec<_>>());
+    assert_eq!(shortest_period(b"aab"), b"aab");
+    assert_eq!(shortest_period(&[1, 2, 1, 2, 1, 2]), [1, 2]);
+    assert_eq!(shortest_period::<u8>(&[]), []);
+}
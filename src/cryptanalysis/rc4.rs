@@ -0,0 +1,275 @@
+// Attacks on RC4: the bias of the second keystream byte and the
+// Fluhrer-Mantin-Shamir key recovery on WEP
+use crate::rng::traits::Rng;
+use crate::symmetric_encryption::rc4::rc4::Rc4;
+
+/// Number of occurrences of each value of the keystream byte at `position`
+/// (0 for the first byte), over `keys` random keys of `key_len` bytes
+pub fn byte_frequencies<R: Rng>(
+    position: usize,
+    keys: usize,
+    key_len: usize,
+    rng: &mut R,
+) -> [u64; 256] {
+    let mut frequencies = [0; 256];
+    let mut key = vec![0; key_len];
+    let mut keystream = vec![0; position + 1];
+    for _ in 0..keys {
+        rng.fill_bytes(&mut key);
+        keystream.fill(0);
+        Rc4::new(&key).unwrap().apply_keystream(&mut keystream);
+        frequencies[keystream[position] as usize] += 1;
+    }
+    frequencies
+}
+
+/// Probability that the second keystream byte is 0, over `keys` random keys
+/// of `key_len` bytes, relative to the $1/256$ of a random byte
+///
+/// Mantin and Shamir showed that it is about $2/256$: the ratio is close to 2.
+pub fn second_byte_bias<R: Rng>(keys: usize, key_len: usize, rng: &mut R) -> f64 {
+    let frequencies = byte_frequencies(1, keys, key_len, rng);
+    frequencies[0] as f64 * 256.0 / keys as f64
+}
+
+/// Second byte of a message sent encrypted under many keys: as the second
+/// keystream byte is 0 twice as often as any other value, the most frequent
+/// second byte of the ciphertexts is the plaintext one
+pub fn recover_second_byte(cipher_texts: &[Vec<u8>]) -> Option<u8> {
+    let mut frequencies = [0usize; 256];
+    for cipher_text in cipher_texts {
+        frequencies[*cipher_text.get(1)? as usize] += 1;
+    }
+    (0..=255u8).max_by_key(|&byte| frequencies[byte as usize])
+}
+
+/// A WEP packet as seen by an eavesdropper: the 3-byte IV, sent in clear
+/// before the encrypted payload, and the first keystream byte, the first
+/// plaintext byte being the `0xAA` of the SNAP header
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WepSample {
+    pub iv: [u8; 3],
+    pub keystream: u8,
+}
+
+/// Longest WEP key, the RC4 key $IV \| K$ having at most 256 bytes
+pub const MAX_WEP_KEY_LEN: usize = 253;
+
+/// Samples of the packets sent with `ivs` by a station using the WEP `key`
+///
+/// WEP encrypts each packet with RC4 under the key $IV \| K$: the IV is
+/// public and changes at each packet, the secret part does not.
+pub fn wep_samples(
+    key: &[u8],
+    ivs: impl IntoIterator<Item = [u8; 3]>,
+) -> Result<Vec<WepSample>, String> {
+    check_key_len(key.len())?;
+    Ok(ivs.into_iter().map(|iv| wep_sample(key, iv)).collect())
+}
+
+/// Sample of the packet sent with `iv`, for a key of valid length
+fn wep_sample(key: &[u8], iv: [u8; 3]) -> WepSample {
+    let mut keystream = [0];
+    Rc4::new(&[&iv, key].concat())
+        .unwrap()
+        .apply_keystream(&mut keystream);
+    WepSample {
+        iv,
+        keystream: keystream[0],
+    }
+}
+
+/// The IVs $(A + 3, 255, X)$ used by Fluhrer, Mantin and Shamir for each
+/// byte $A$ of a key of `key_len` bytes, $X$ taking the 256 values
+pub fn weak_ivs(key_len: usize) -> Result<impl Iterator<Item = [u8; 3]>, String> {
+    check_key_len(key_len)?;
+    Ok((0..key_len).flat_map(|a| (0..=255).map(move |x| [a as u8 + 3, 255, x])))
+}
+
+/// Votes of the samples for the value of the next byte of the key, `known`
+/// holding the bytes already recovered
+///
+/// With $t = |IV| + |known|$, the first $t$ steps of the key scheduling only
+/// use known key bytes and are simulated. When $S[1] < t$ and
+/// $S[1] + S[S[1]] = t$ at this point, the sample is resolved: if the next
+/// steps do not touch $S[1]$, $S[S[1]]$ and $S[t]$, which happens with
+/// probability about $e^{-3} \approx 5\%$, the first output byte is the value
+/// swapped into $S[t]$ at step $t$, $S[j + S[t] + K[t]]$, which gives $K[t]$.
+/// The other resolved samples vote for random values.
+pub fn fms_votes(samples: &[WepSample], known: &[u8]) -> Result<[u32; 256], String> {
+    // the next byte must still be part of a key
+    check_key_len(known.len() + 1)?;
+
+    let t = 3 + known.len();
+    let mut votes = [0; 256];
+    for sample in samples {
+        let key = [&sample.iv, known].concat();
+        let mut s: Vec<u8> = (0..=255).collect();
+        let mut j = 0u8;
+        for (i, &k) in key.iter().enumerate() {
+            j = j.wrapping_add(s[i]).wrapping_add(k);
+            s.swap(i, j as usize);
+        }
+
+        let x = s[1] as usize;
+        if x >= t || (x + s[x] as usize) % 256 != t {
+            continue;
+        }
+        let inverse = s.iter().position(|&v| v == sample.keystream).unwrap() as u8;
+        let guess = inverse.wrapping_sub(j).wrapping_sub(s[t]);
+        votes[guess as usize] += 1;
+    }
+    Ok(votes)
+}
+
+/// Recover a WEP key of `key_len` bytes from the samples
+///
+/// The bytes are recovered one after the other with [`fms_votes`], an error
+/// on a byte spoiling the votes for the next ones: the search tries the
+/// `breadth` most voted values of each byte, depth first, and checks every
+/// complete key against the samples. With the 256 IVs of [`weak_ivs`] for
+/// each byte the most voted value is almost always right, with 128 of them
+/// about two keys in three are found, and with 64 hardly any.
+///
+/// The search tries up to `breadth` to the power `key_len` keys: it gives up
+/// with an error after [`MAX_CANDIDATE_KEYS`] of them.
+pub fn recover_wep_key(
+    samples: &[WepSample],
+    key_len: usize,
+    breadth: usize,
+) -> Result<Vec<u8>, String> {
+    check_key_len(key_len)?;
+    // without samples, any key would pass the final check
+    if samples.is_empty() {
+        return Err("No samples to recover the key from".to_string());
+    }
+
+    let mut key = Vec::with_capacity(key_len);
+    let mut checked = 0;
+    if search(samples, &mut key, key_len, breadth, &mut checked)? {
+        Ok(key)
+    } else {
+        Err(format!(
+            "No key found among the {breadth} best candidates of each byte"
+        ))
+    }
+}
+
+fn check_key_len(key_len: usize) -> Result<(), String> {
+    if !(1..=MAX_WEP_KEY_LEN).contains(&key_len) {
+        return Err(format!(
+            "Invalid WEP key length {key_len}: expected 1 to {MAX_WEP_KEY_LEN} bytes"
+        ));
+    }
+    Ok(())
+}
+
+/// Number of samples a recovered key is checked against
+const CHECKED_SAMPLES: usize = 32;
+
+/// Number of complete keys checked by [`recover_wep_key`] before it gives up
+pub const MAX_CANDIDATE_KEYS: usize = 1 << 16;
+
+/// Depth-first search of the key, `checked` counting the complete keys checked
+fn search(
+    samples: &[WepSample],
+    key: &mut Vec<u8>,
+    key_len: usize,
+    breadth: usize,
+    checked: &mut usize,
+) -> Result<bool, String> {
+    if key.len() == key_len {
+        if *checked == MAX_CANDIDATE_KEYS {
+            return Err(format!(
+                "No key found among the first {MAX_CANDIDATE_KEYS} candidates"
+            ));
+        }
+        *checked += 1;
+        return Ok(samples
+            .iter()
+            .take(CHECKED_SAMPLES)
+            .all(|sample| wep_sample(key, sample.iv) == *sample));
+    }
+
+    // key.len() < key_len <= MAX_WEP_KEY_LEN
+    let votes = fms_votes(samples, key).unwrap();
+    let mut candidates: Vec<u8> = (0..=255).collect();
+    candidates.sort_by_key(|&byte| std::cmp::Reverse(votes[byte as usize]));
+    for &candidate in candidates.iter().take(breadth) {
+        key.push(candidate);
+        if search(samples, key, key_len, breadth, checked)? {
+            return Ok(true);
+        }
+        key.pop();
+    }
+    Ok(false)
+}
+
+#[test]
+fn test_second_byte_bias() {
+    use crate::rng::splitmix64::SplitMix64;
+    let mut rng = SplitMix64::new(6229);
+
+    // Z_2 = 0 with probability 2/256, the other bytes are close to uniform
+    let bias = second_byte_bias(1 << 16, 16, &mut rng);
+    assert!((1.8..2.2).contains(&bias), "{bias}");
+    let third = byte_frequencies(2, 1 << 16, 16, &mut rng)[0] as f64 * 256.0 / (1 << 16) as f64;
+    assert!((0.8..1.2).contains(&third), "{third}");
+
+    // the same message under many keys
+    let message = b"Attack at dawn";
+    let cipher_texts: Vec<Vec<u8>> = (0..1 << 13)
+        .map(|_| {
+            let mut key = [0; 16];
+            rng.fill_bytes(&mut key);
+            let mut cipher_text = message.to_vec();
+            Rc4::new(&key).unwrap().apply_keystream(&mut cipher_text);
+            cipher_text
+        })
+        .collect();
+    assert_eq!(recover_second_byte(&cipher_texts), Some(b't'));
+    assert_eq!(recover_second_byte(&[vec![0]]), None);
+}
+
+#[test]
+fn test_fms_attack() {
+    use crate::rng::splitmix64::SplitMix64;
+    let mut rng = SplitMix64::new(2001);
+
+    // WEP-40 and WEP-104
+    for key_len in [5, 13] {
+        let mut key = vec![0; key_len];
+        rng.fill_bytes(&mut key);
+        let samples = wep_samples(&key, weak_ivs(key_len).unwrap()).unwrap();
+        assert_eq!(recover_wep_key(&samples, key_len, 2), Ok(key));
+    }
+
+    // without the weak IVs, a few samples are not enough
+    let key = [0x13, 0x37, 0xC0, 0xFF, 0xEE];
+    let ivs = (0..64).map(|i| [i as u8, 0, 1]);
+    let samples = wep_samples(&key, ivs).unwrap();
+    assert!(recover_wep_key(&samples, 5, 1).is_err());
+
+    // the search gives up instead of trying 256^13 keys
+    let samples = wep_samples(&[0; 13], (0..4).map(|i| [i, 0, 1])).unwrap();
+    assert_eq!(
+        recover_wep_key(&samples, 13, 256),
+        Err(format!(
+            "No key found among the first {MAX_CANDIDATE_KEYS} candidates"
+        ))
+    );
+
+    // nothing to recover from, or a key too long for RC4
+    assert!(recover_wep_key(&[], 5, 2).is_err());
+    assert!(weak_ivs(0).is_err());
+    assert_eq!(weak_ivs(MAX_WEP_KEY_LEN).unwrap().count(), 253 * 256);
+    assert!(weak_ivs(MAX_WEP_KEY_LEN + 1).is_err());
+    assert!(fms_votes(&[], &[0; MAX_WEP_KEY_LEN]).is_err());
+    let sample = WepSample {
+        iv: [3, 255, 0],
+        keystream: 0,
+    };
+    assert!(recover_wep_key(&[sample], 300, 1).is_err());
+    assert!(wep_samples(&[0; MAX_WEP_KEY_LEN + 1], [[0; 3]]).is_err());
+    assert!(wep_samples(&[], [[0; 3]]).is_err());
+}
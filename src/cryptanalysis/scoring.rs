@@ -0,0 +1,288 @@
+// Fitness of candidate plaintexts against English statistics
+use std::sync::OnceLock;
+
+/// Relative frequencies of the letters `a` to `z` in English text (Lewand)
+pub const ENGLISH_FREQUENCIES: [f64; 26] = [
+    0.08167, 0.01492, 0.02782, 0.04253, 0.12702, 0.02228, 0.02015, 0.06094, 0.06966, 0.00153,
+    0.00772, 0.04025, 0.02406, 0.06749, 0.07507, 0.01929, 0.00095, 0.05987, 0.06327, 0.09056,
+    0.02758, 0.00978, 0.02360, 0.00150, 0.01974, 0.00074,
+];
+
+/// Public domain English text the n-gram models are trained on
+/// (Alice's Adventures in Wonderland, from the Canterbury corpus)
+const CORPUS: &str = include_str!("data/alice29.txt");
+
+/// Indices of the ASCII letters of `text`, case-insensitive, other characters skipped
+pub fn letter_indices(text: &str) -> Vec<usize> {
+    text.chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| (c.to_ascii_lowercase() as u8 - b'a') as usize)
+        .collect()
+}
+
+/// Number of occurrences of each letter `a` to `z` in `text`
+pub fn letter_counts(text: &str) -> [usize; 26] {
+    let mut counts = [0; 26];
+    for index in letter_indices(text) {
+        counts[index] += 1;
+    }
+    counts
+}
+
+/// Chi-squared distance between the letter counts of `text` and English
+///
+/// $$\chi^2 = \sum_{i=0}^{25} \frac{(C_i - E_i)^2}{E_i}$$
+///
+/// with $C_i$ the count of the $i$-th letter and $E_i = N p_i$ its expected
+/// count in English. Lower is closer to English.
+pub fn chi_squared(text: &str) -> f64 {
+    chi_squared_counts(&letter_counts(text))
+}
+
+/// Chi-squared distance of letter counts, see [`chi_squared`]
+pub fn chi_squared_counts(counts: &[usize; 26]) -> f64 {
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return f64::INFINITY;
+    }
+
+    counts
+        .iter()
+        .zip(ENGLISH_FREQUENCIES)
+        .map(|(&count, p)| {
+            let expected = total as f64 * p;
+            (count as f64 - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+/// Index of coincidence of English text, against $1/26 \approx 0.0385$ for random letters
+pub const ENGLISH_IOC: f64 = 0.0667;
+
+/// Index of coincidence: probability that two letters drawn from `text` are equal
+///
+/// $$IC = \sum_{i=0}^{25} \frac{C_i (C_i - 1)}{N (N - 1)}$$
+///
+/// It does not change under a monoalphabetic substitution, so it tells
+/// English encrypted with a single alphabet from a polyalphabetic cipher.
+pub fn index_of_coincidence(text: &str) -> f64 {
+    index_of_coincidence_counts(&letter_counts(text))
+}
+
+/// Index of coincidence of letter counts, see [`index_of_coincidence`]
+pub fn index_of_coincidence_counts(counts: &[usize; 26]) -> f64 {
+    let total: usize = counts.iter().sum();
+    if total < 2 {
+        return 0.0;
+    }
+
+    let pairs: usize = counts.iter().map(|&c| c * c.saturating_sub(1)).sum();
+    pairs as f64 / (total * (total - 1)) as f64
+}
+
+/// Log-probabilities of the $26^n$ letter n-grams
+///
+/// The score of a text is the sum of $\log_{10} p$ over its overlapping
+/// n-grams, i.e. the log-likelihood of the text under a model where n-grams
+/// are independent. Spaces and punctuation are ignored, so the score also
+/// works on ciphertexts where word boundaries were removed. N-grams missing
+/// from the training text get the probability of $0.01$ occurrence.
+#[derive(Debug, Clone)]
+pub struct NgramModel {
+    n: usize,
+    log_probabilities: Vec<f64>,
+}
+
+impl NgramModel {
+    /// Count the n-grams of `corpus`, with $1 \le n \le 5$
+    pub fn from_corpus(corpus: &str, n: usize) -> Result<Self, String> {
+        if !(1..=5).contains(&n) {
+            return Err(format!("Unsupported n-gram length {n}"));
+        }
+
+        let mut counts = vec![0usize; 26usize.pow(n as u32)];
+        let letters = letter_indices(corpus);
+        for ngram in letters.windows(n) {
+            counts[Self::ngram_index(ngram)] += 1;
+        }
+
+        let total = letters.len().saturating_sub(n - 1).max(1) as f64;
+        let floor = (0.01 / total).log10();
+        let log_probabilities = counts
+            .into_iter()
+            .map(|count| {
+                if count == 0 {
+                    floor
+                } else {
+                    (count as f64 / total).log10()
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            n,
+            log_probabilities,
+        })
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Log-likelihood of `text`, higher is closer to English
+    pub fn score(&self, text: &str) -> f64 {
+        self.score_indices(&letter_indices(text))
+    }
+
+    /// Log-likelihood of a text given as letter indices (`a` = 0)
+    pub fn score_indices(&self, letters: &[usize]) -> f64 {
+        letters
+            .windows(self.n)
+            .map(|ngram| self.log_probabilities[Self::ngram_index(ngram)])
+            .sum()
+    }
+
+    fn ngram_index(ngram: &[usize]) -> usize {
+        ngram.iter().fold(0, |acc, &letter| acc * 26 + letter)
+    }
+}
+
+/// Single letter model trained on the bundled corpus
+pub fn monograms() -> &'static NgramModel {
+    static MODEL: OnceLock<NgramModel> = OnceLock::new();
+    MODEL.get_or_init(|| NgramModel::from_corpus(CORPUS, 1).unwrap())
+}
+
+/// Bigram model trained on the bundled corpus
+pub fn bigrams() -> &'static NgramModel {
+    static MODEL: OnceLock<NgramModel> = OnceLock::new();
+    MODEL.get_or_init(|| NgramModel::from_corpus(CORPUS, 2).unwrap())
+}
+
+/// Quadgram model trained on the bundled corpus, the most reliable on short texts
+pub fn quadgrams() -> &'static NgramModel {
+    static MODEL: OnceLock<NgramModel> = OnceLock::new();
+    MODEL.get_or_init(|| NgramModel::from_corpus(CORPUS, 4).unwrap())
+}
+
+/// $\log_{10}$ of the frequency of each byte in the bundled corpus
+///
+/// Unlike the n-gram models, spaces, punctuation and case count, and bytes
+/// which are not English text at all get the probability of $0.01$
+/// occurrence. Used to score the output of byte-oriented ciphers.
+pub fn byte_log_probabilities() -> &'static [f64; 256] {
+    static MODEL: OnceLock<[f64; 256]> = OnceLock::new();
+    MODEL.get_or_init(|| {
+        let mut counts = [0usize; 256];
+        for &byte in CORPUS.as_bytes() {
+            counts[byte as usize] += 1;
+        }
+        let total = CORPUS.len() as f64;
+        let floor = (0.01 / total).log10();
+        counts.map(|count| {
+            if count == 0 {
+                floor
+            } else {
+                (count as f64 / total).log10()
+            }
+        })
+    })
+}
+
+/// Log-likelihood of `bytes` as English text, higher is closer to English
+pub fn score_bytes(bytes: &[u8]) -> f64 {
+    let model = byte_log_probabilities();
+    bytes.iter().map(|&byte| model[byte as usize]).sum()
+}
+
+/// Fitness function used to rank candidate plaintexts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Scorer {
+    ChiSquared,
+    Monogram,
+    Bigram,
+    #[default]
+    Quadgram,
+}
+
+impl Scorer {
+    /// Fitness of `text`, higher is closer to English
+    ///
+    /// The chi-squared distance is negated so that every scorer ranks the
+    /// same way.
+    pub fn score(&self, text: &str) -> f64 {
+        match self {
+            Scorer::ChiSquared => -chi_squared(text),
+            Scorer::Monogram => monograms().score(text),
+            Scorer::Bigram => bigrams().score(text),
+            Scorer::Quadgram => quadgrams().score(text),
+        }
+    }
+}
+
+/// A decryption attempt: the key, the resulting plaintext and its fitness
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate<K> {
+    pub key: K,
+    pub plaintext: String,
+    pub score: f64,
+}
+
+/// Sort candidates from the most to the least English-like
+pub fn rank<K>(candidates: &mut [Candidate<K>]) {
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+}
+
+#[test]
+fn test_chi_squared() {
+    let english =
+        "the quick brown fox jumps over the lazy dog and keeps running through the forest";
+    let shifted =
+        "wkh txlfn eurzq ira mxpsv ryhu wkh odcb grj dqg nhhsv uxqqlqj wkurxjk wkh iruhvw";
+    assert!(chi_squared(english) < chi_squared(shifted));
+    assert_eq!(chi_squared("1234"), f64::INFINITY);
+    assert_eq!(letter_counts("Aa b!")[..3], [2, 1, 0]);
+}
+
+#[test]
+fn test_index_of_coincidence() {
+    let ioc = index_of_coincidence(CORPUS);
+    assert!((ioc - ENGLISH_IOC).abs() < 0.005, "{ioc}");
+
+    let uniform: String = ('a'..='z').cycle().take(26 * 40).collect();
+    assert!((index_of_coincidence(&uniform) - 1.0 / 26.0).abs() < 0.001);
+    assert_eq!(index_of_coincidence("aaaa"), 1.0);
+    assert_eq!(index_of_coincidence("a"), 0.0);
+}
+
+#[test]
+fn test_ngram_models() {
+    // "the" is the most common trigram in English text
+    let trigrams = NgramModel::from_corpus(CORPUS, 3).unwrap();
+    let best = (0..26 * 26 * 26)
+        .max_by(|&a, &b| trigrams.log_probabilities[a].total_cmp(&trigrams.log_probabilities[b]))
+        .unwrap();
+    assert_eq!(best, NgramModel::ngram_index(&[19, 7, 4]));
+
+    for model in [monograms(), bigrams(), quadgrams()] {
+        assert!(model.score("attack at dawn") > model.score("xqzzvj kw pqjx"));
+    }
+    assert_eq!(quadgrams().score("abc"), 0.0);
+    assert!(NgramModel::from_corpus(CORPUS, 6).is_err());
+
+    let text = "Defend the east wall of the castle";
+    for scorer in [
+        Scorer::ChiSquared,
+        Scorer::Monogram,
+        Scorer::Bigram,
+        Scorer::Quadgram,
+    ] {
+        assert!(scorer.score(text) > scorer.score("Qrsraq gur rnfg jnyy bs gur pnfgyr"));
+    }
+
+    // spaces and lowercase letters are the most likely bytes
+    assert!(score_bytes(b"the east wall") > score_bytes(b"THE_EAST_WALL"));
+    assert!(
+        score_bytes(b"THE EAST") > score_bytes(&[0x8f, 0x01, 0xfe, 0x7f, 0x00, 0x13, 0x9a, 0xc4])
+    );
+}
@@ -0,0 +1,202 @@
+// Hill climbing and simulated annealing on monoalphabetic substitution ciphers
+use super::scoring::{letter_counts, letter_indices, quadgrams, Candidate};
+use crate::{
+    rng::traits::Rng,
+    symmetric_encryption::{
+        substitution::substitution::{decrypt_with, invert},
+        utils::{default_alphabet, PassThrough},
+    },
+};
+
+/// English letters from the most to the least frequent
+const FREQUENCY_ORDER: &[u8; 26] = b"etaoinshrdlcumwfgypbvkjxqz";
+
+/// Parameters of the key search
+///
+/// With a temperature of 0 the search is a plain hill climbing: a swap of
+/// two key letters is kept only if it improves the score. Otherwise it is a
+/// simulated annealing: a worse key is accepted with probability
+/// $e^{\Delta / T}$, with the temperature $T$ decreasing linearly to 0, which
+/// lets the search escape local maxima early on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolverConfig {
+    /// Number of independent searches, from the frequency guess then random keys
+    pub restarts: usize,
+    /// Number of swaps tried in each search
+    pub iterations: usize,
+    /// Initial temperature, in quadgram log-likelihood units
+    pub temperature: f64,
+}
+
+impl SolverConfig {
+    pub fn hill_climbing() -> Self {
+        Self {
+            restarts: 8,
+            iterations: 4000,
+            temperature: 0.0,
+        }
+    }
+
+    pub fn simulated_annealing() -> Self {
+        Self {
+            restarts: 4,
+            iterations: 10000,
+            temperature: 10.0,
+        }
+    }
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self::hill_climbing()
+    }
+}
+
+/// Recover the key of a monoalphabetic substitution cipher
+///
+/// Searches for the decryption key maximizing the quadgram score of the
+/// plaintext. The Caesar and affine ciphers are substitutions too, so
+/// their ciphertexts are solved as well. Letters missing from the
+/// ciphertext cannot be recovered: their place in the key is arbitrary.
+/// Needs about 200 letters of ciphertext to be reliable.
+pub fn solve(cipher: &str, rng: &mut impl Rng, config: &SolverConfig) -> Candidate<String> {
+    let letters = letter_indices(cipher);
+    let model = quadgrams();
+
+    let mut best_key = frequency_guess(cipher);
+    let mut best_score = f64::NEG_INFINITY;
+    let mut plaintext = vec![0; letters.len()];
+
+    for restart in 0..config.restarts.max(1) {
+        let mut key = if restart == 0 {
+            frequency_guess(cipher)
+        } else {
+            random_permutation(rng)
+        };
+        decrypt_indices(&letters, &key, &mut plaintext);
+        let mut score = model.score_indices(&plaintext);
+
+        for iteration in 0..config.iterations {
+            let i = rng.next_below(26) as usize;
+            let j = rng.next_below(26) as usize;
+            if i == j {
+                continue;
+            }
+
+            key.swap(i, j);
+            decrypt_indices(&letters, &key, &mut plaintext);
+            let candidate = model.score_indices(&plaintext);
+
+            let temperature =
+                config.temperature * (1.0 - iteration as f64 / config.iterations as f64);
+            let accept = candidate > score
+                || (temperature > 0.0
+                    && rng.next_f64() < ((candidate - score) / temperature).exp());
+
+            if accept {
+                score = candidate;
+            } else {
+                key.swap(i, j);
+            }
+        }
+
+        if score > best_score {
+            best_score = score;
+            best_key = key;
+        }
+    }
+
+    // the search works on the decryption key, the cipher key is its inverse
+    let key: String = invert(&best_key)
+        .into_iter()
+        .map(|index| default_alphabet().char_at(index).unwrap())
+        .collect();
+    let plaintext = decrypt_with(cipher, &key, default_alphabet(), PassThrough::Preserve).unwrap();
+
+    Candidate {
+        key,
+        plaintext,
+        score: best_score,
+    }
+}
+
+/// Decryption key mapping the most frequent ciphertext letters to the most frequent English letters
+fn frequency_guess(cipher: &str) -> Vec<usize> {
+    let counts = letter_counts(cipher);
+    let mut by_frequency: Vec<usize> = (0..26).collect();
+    by_frequency.sort_by(|&a, &b| counts[b].cmp(&counts[a]));
+
+    let mut key = vec![0; 26];
+    for (&cipher_letter, &plain_letter) in by_frequency.iter().zip(FREQUENCY_ORDER) {
+        key[cipher_letter] = (plain_letter - b'a') as usize;
+    }
+    key
+}
+
+/// Uniform permutation of the 26 letters (Fisher-Yates shuffle)
+fn random_permutation(rng: &mut impl Rng) -> Vec<usize> {
+    let mut permutation: Vec<usize> = (0..26).collect();
+    for i in (1..26).rev() {
+        let j = rng.next_below(i as u64 + 1) as usize;
+        permutation.swap(i, j);
+    }
+    permutation
+}
+
+fn decrypt_indices(letters: &[usize], key: &[usize], plaintext: &mut [usize]) {
+    for (p, &c) in plaintext.iter_mut().zip(letters) {
+        *p = key[c];
+    }
+}
+
+#[cfg(test)]
+const TEXT: &str = "It is a truth universally acknowledged, that a single man in possession \
+    of a good fortune, must be in want of a wife. However little known the feelings or views \
+    of such a man may be on his first entering a neighbourhood, this truth is so well fixed in \
+    the minds of the surrounding families, that he is considered the rightful property of some \
+    one or other of their daughters.";
+
+#[test]
+fn test_solve_substitution() {
+    use crate::{
+        rng::splitmix64::SplitMix64,
+        symmetric_encryption::substitution::substitution::{encrypt_with, key_from_keyword},
+    };
+
+    let key = key_from_keyword("the quick brown fox").unwrap();
+    let cipher = encrypt_with(TEXT, &key, default_alphabet(), PassThrough::Preserve).unwrap();
+
+    for config in [
+        SolverConfig::hill_climbing(),
+        SolverConfig::simulated_annealing(),
+    ] {
+        let mut rng = SplitMix64::new(2024);
+        let candidate = solve(&cipher, &mut rng, &config);
+        assert_eq!(candidate.plaintext, TEXT);
+
+        // every letter of the plaintext is mapped correctly
+        let recovered = candidate.key.as_bytes();
+        for letter in letter_indices(TEXT) {
+            assert_eq!(recovered[letter], key.as_bytes()[letter]);
+        }
+    }
+}
+
+#[test]
+fn test_solve_affine() {
+    use crate::{
+        rng::splitmix64::SplitMix64,
+        symmetric_encryption::{affine::affine, substitution::substitution::affine_key},
+    };
+
+    let cipher =
+        affine::encrypt_with(TEXT, 7, 3, default_alphabet(), PassThrough::Preserve).unwrap();
+    let mut rng = SplitMix64::new(7);
+    let candidate = solve(&cipher, &mut rng, &SolverConfig::default());
+    assert_eq!(candidate.plaintext, TEXT);
+
+    let key = affine_key(7, 3).unwrap();
+    for letter in letter_indices(TEXT) {
+        assert_eq!(candidate.key.as_bytes()[letter], key.as_bytes()[letter]);
+    }
+}
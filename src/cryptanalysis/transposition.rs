@@ -0,0 +1,106 @@
+// Exhaustive search on rail fence and columnar transpositions
+use super::scoring::{rank, Candidate, Scorer};
+use crate::symmetric_encryption::transposition::{
+    columnar::decrypt_with_order, rail_fence, transposition::next_permutation,
+};
+
+/// Largest columnar key length searched exhaustively, $8! = 40320$ orders
+pub const MAX_COLUMNAR_KEY_LEN: usize = 8;
+
+/// Try every number of rails from 2 to `max_rails`, most English-like first
+pub fn rail_fence_brute_force(
+    cipher: &str,
+    max_rails: usize,
+    scorer: Scorer,
+) -> Vec<Candidate<usize>> {
+    let mut candidates: Vec<Candidate<usize>> = (2..=max_rails.max(2))
+        .map(|rails| {
+            let plaintext = rail_fence::decrypt(cipher, rails).unwrap();
+            Candidate {
+                key: rails,
+                score: scorer.score(&plaintext),
+                plaintext,
+            }
+        })
+        .collect();
+
+    rank(&mut candidates);
+    candidates
+}
+
+/// Anagram the columns: try every column order for key lengths 2 to `max_key_len`
+///
+/// The candidates are the best order of each key length, most English-like
+/// first. Transpositions keep the letter frequencies, so only n-gram scores
+/// can tell the right order: the quadgram scorer should be used.
+pub fn columnar_brute_force(
+    cipher: &str,
+    max_key_len: usize,
+    scorer: Scorer,
+) -> Result<Vec<Candidate<Vec<usize>>>, String> {
+    if max_key_len > MAX_COLUMNAR_KEY_LEN {
+        return Err(format!(
+            "Key length {max_key_len} is too large for an exhaustive search (at most {MAX_COLUMNAR_KEY_LEN})"
+        ));
+    }
+
+    let mut candidates: Vec<Candidate<Vec<usize>>> = (2..=max_key_len)
+        .map(|key_len| best_order(cipher, key_len, scorer))
+        .collect();
+
+    rank(&mut candidates);
+    Ok(candidates)
+}
+
+/// The best of the $n!$ column orders for a key of length $n$
+fn best_order(cipher: &str, key_len: usize, scorer: Scorer) -> Candidate<Vec<usize>> {
+    let mut order: Vec<usize> = (0..key_len).collect();
+    let mut best: Option<Candidate<Vec<usize>>> = None;
+
+    loop {
+        let plaintext = decrypt_with_order(cipher, &order).unwrap();
+        let score = scorer.score(&plaintext);
+        if best.as_ref().is_none_or(|best| score > best.score) {
+            best = Some(Candidate {
+                key: order.clone(),
+                plaintext,
+                score,
+            });
+        }
+
+        if !next_permutation(&mut order) {
+            return best.unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+const TEXT: &str = "the enemy will attack the northern bridge at dawn so move the reserve \
+    battalion across the river before midnight and hold the eastern bank";
+
+#[test]
+fn test_rail_fence_brute_force() {
+    for rails in [2, 3, 5, 8] {
+        let cipher = rail_fence::encrypt(TEXT, rails).unwrap();
+        let candidates = rail_fence_brute_force(&cipher, 10, Scorer::Quadgram);
+        assert_eq!(candidates.len(), 9);
+        assert_eq!(candidates[0].key, rails);
+        assert_eq!(candidates[0].plaintext, TEXT);
+    }
+}
+
+#[test]
+fn test_columnar_brute_force() {
+    use crate::symmetric_encryption::transposition::columnar::{column_order, encrypt};
+
+    let text: String = TEXT.split_whitespace().collect();
+    for key in ["fox", "ciph", "zebras"] {
+        let cipher = encrypt(&text, key).unwrap();
+        let candidates = columnar_brute_force(&cipher, 6, Scorer::Quadgram).unwrap();
+        assert_eq!(candidates.len(), 5);
+        assert_eq!(candidates[0].key, column_order(key).unwrap());
+        assert_eq!(candidates[0].plaintext, text);
+    }
+
+    assert!(columnar_brute_force(&text, 9, Scorer::Quadgram).is_err());
+}
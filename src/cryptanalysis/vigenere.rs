@@ -0,0 +1,226 @@
+// Kasiski examination and index of coincidence on periodic polyalphabetic ciphers
+use std::collections::HashMap;
+
+use super::period::shortest_period;
+use super::scoring::{
+    chi_squared_counts, index_of_coincidence_counts, letter_indices, rank, Candidate, Scorer,
+    ENGLISH_IOC,
+};
+use crate::symmetric_encryption::{
+    utils::{default_alphabet, PassThrough},
+    vigenere::vigenere::{decrypt_with, Tableau},
+};
+
+/// Number of candidate key lengths for which a key is solved in [`crack`]
+const KEY_LENGTH_CANDIDATES: usize = 3;
+
+/// Kasiski examination: support of each key length $2 \le m \le$ `max_key_len`
+///
+/// A trigram repeated in the ciphertext is most likely the same plaintext
+/// encrypted under the same key letters, so the distance between the two
+/// occurrences is a multiple of the key length. Returns, for each $m$, the
+/// number of distances between consecutive repeated trigrams divisible by $m$.
+pub fn kasiski(cipher: &str, max_key_len: usize) -> Vec<(usize, usize)> {
+    let letters = letter_indices(cipher);
+
+    let mut last_seen = HashMap::new();
+    let mut distances = Vec::new();
+    for (position, trigram) in letters.windows(3).enumerate() {
+        if let Some(previous) = last_seen.insert(trigram, position) {
+            distances.push(position - previous);
+        }
+    }
+
+    (2..=max_key_len)
+        .map(|m| (m, distances.iter().filter(|d| d.is_multiple_of(m)).count()))
+        .collect()
+}
+
+/// Average index of coincidence of the columns for each key length $1 \le m \le$ `max_key_len`
+///
+/// Splitting the ciphertext into $m$ columns, letter $i$ going to column
+/// $i \bmod m$, gives monoalphabetic ciphertexts when $m$ is a multiple of
+/// the key length: their index of coincidence is the one of English.
+pub fn column_coincidences(cipher: &str, max_key_len: usize) -> Vec<(usize, f64)> {
+    let letters = letter_indices(cipher);
+
+    (1..=max_key_len)
+        .map(|m| {
+            let total: f64 = columns(&letters, m)
+                .iter()
+                .map(index_of_coincidence_counts)
+                .sum();
+            (m, total / m as f64)
+        })
+        .collect()
+}
+
+/// Key lengths up to `max_key_len`, most likely first
+///
+/// Lengths whose columns look like English (index of coincidence closer to
+/// English than to random text) come first. The true length and its
+/// multiples all qualify, Kasiski examination then favors the true length,
+/// as fewer distances are divisible by its multiples.
+pub fn key_lengths(cipher: &str, max_key_len: usize) -> Vec<usize> {
+    let coincidences = column_coincidences(cipher, max_key_len);
+    let support: HashMap<usize, usize> = kasiski(cipher, max_key_len).into_iter().collect();
+    let threshold = (ENGLISH_IOC + 1.0 / 26.0) / 2.0;
+
+    let mut lengths = coincidences;
+    lengths.sort_by(|&(m1, ioc1), &(m2, ioc2)| {
+        (ioc2 >= threshold)
+            .cmp(&(ioc1 >= threshold))
+            .then(support.get(&m2).cmp(&support.get(&m1)))
+            .then(ioc2.total_cmp(&ioc1))
+    });
+
+    lengths.into_iter().map(|(m, _)| m).collect()
+}
+
+/// Recover a key of `key_len` letters, breaking each column as a Caesar cipher
+///
+/// For each column, the key letter is the one whose decryption has the
+/// letter frequencies closest to English (lowest chi-squared).
+pub fn solve_key(cipher: &str, key_len: usize, tableau: Tableau) -> String {
+    let letters = letter_indices(cipher);
+
+    (0..key_len)
+        .map(|column| {
+            let column: Vec<usize> = letters
+                .iter()
+                .skip(column)
+                .step_by(key_len)
+                .copied()
+                .collect();
+            let k = (0..26)
+                .min_by(|&k1, &k2| {
+                    let chi1 = chi_squared_counts(&decrypted_counts(&column, k1, tableau));
+                    let chi2 = chi_squared_counts(&decrypted_counts(&column, k2, tableau));
+                    chi1.total_cmp(&chi2)
+                })
+                .unwrap();
+            (b'a' + k as u8) as char
+        })
+        .collect()
+}
+
+/// Break a Vigenère, Beaufort or variant Beaufort ciphertext
+///
+/// The keys for the most likely lengths are solved and the resulting
+/// plaintexts ranked with quadgram statistics. Periods with a common
+/// divisor are reduced to the shortest key producing the same plaintext.
+pub fn crack(
+    cipher: &str,
+    tableau: Tableau,
+    max_key_len: usize,
+) -> Result<Candidate<String>, String> {
+    if max_key_len == 0 {
+        return Err("The maximum key length must be at least 1".to_string());
+    }
+    if letter_indices(cipher).len() < 2 * max_key_len {
+        return Err("Ciphertext too short for the maximum key length".to_string());
+    }
+
+    let mut candidates = key_lengths(cipher, max_key_len)
+        .into_iter()
+        .take(KEY_LENGTH_CANDIDATES)
+        .map(|key_len| {
+            let key: Vec<char> = solve_key(cipher, key_len, tableau).chars().collect();
+            let key: String = shortest_period(&key).into_iter().collect();
+            let plaintext = decrypt_with(
+                cipher,
+                &key,
+                tableau,
+                default_alphabet(),
+                PassThrough::Preserve,
+            )?;
+            Ok(Candidate {
+                key,
+                score: Scorer::Quadgram.score(&plaintext),
+                plaintext,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    rank(&mut candidates);
+    candidates
+        .into_iter()
+        .next()
+        .ok_or("No candidate key length".to_string())
+}
+
+/// Letter counts of the $m$ columns
+fn columns(letters: &[usize], m: usize) -> Vec<[usize; 26]> {
+    let mut counts = vec![[0; 26]; m];
+    for (i, &letter) in letters.iter().enumerate() {
+        counts[i % m][letter] += 1;
+    }
+    counts
+}
+
+fn decrypted_counts(column: &[usize], k: usize, tableau: Tableau) -> [usize; 26] {
+    let mut counts = [0; 26];
+    for &y in column {
+        counts[tableau.decrypt_index(y, k, 26)] += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+const TEXT: &str = "It is a truth universally acknowledged, that a single man in possession \
+    of a good fortune, must be in want of a wife. However little known the feelings or views \
+    of such a man may be on his first entering a neighbourhood, this truth is so well fixed in \
+    the minds of the surrounding families, that he is considered the rightful property of some \
+    one or other of their daughters. My dear Mr. Bennet, said his lady to him one day, have you \
+    heard that Netherfield Park is let at last? Mr. Bennet replied that he had not.";
+
+#[test]
+fn test_key_length() {
+    use crate::symmetric_encryption::vigenere::vigenere::encrypt;
+
+    let text: String = TEXT.chars().filter(|c| c.is_ascii_alphabetic()).collect();
+    let cipher = encrypt(&text.to_lowercase(), "crypto").unwrap();
+
+    // the divisors of 6 are supported as well, unrelated lengths are not
+    let support: HashMap<usize, usize> = kasiski(&cipher, 12).into_iter().collect();
+    assert!(support[&6] > 0);
+    assert!(support[&2] >= support[&6] && support[&3] >= support[&6]);
+    for m in [5, 7, 11] {
+        assert!(support[&m] < support[&6]);
+    }
+
+    let coincidences = column_coincidences(&cipher, 12);
+    assert!(coincidences[5].1 > 0.06);
+    assert!(coincidences[0].1 < 0.05);
+
+    assert_eq!(key_lengths(&cipher, 12)[0], 6);
+    assert_eq!(solve_key(&cipher, 6, Tableau::Vigenere), "crypto");
+}
+
+#[test]
+fn test_crack_vigenere() {
+    use crate::symmetric_encryption::vigenere::vigenere::encrypt_with;
+
+    for (key, tableau) in [
+        ("lemon", Tableau::Vigenere),
+        ("cipher", Tableau::Vigenere),
+        ("fortify", Tableau::Beaufort),
+        ("key", Tableau::VariantBeaufort),
+    ] {
+        let cipher = encrypt_with(
+            TEXT,
+            key,
+            tableau,
+            default_alphabet(),
+            PassThrough::Preserve,
+        )
+        .unwrap();
+        let candidate = crack(&cipher, tableau, 12).unwrap();
+        assert_eq!(candidate.key, key);
+        assert_eq!(candidate.plaintext, TEXT);
+    }
+
+    assert!(crack("short", Tableau::Vigenere, 12).is_err());
+    assert!(crack("short", Tableau::Vigenere, 0).is_err());
+    assert_eq!(solve_key("short", 0, Tableau::Vigenere), "");
+}
@@ -0,0 +1,189 @@
+// Key reuse attacks on XOR ciphers: repeating-key XOR and the two-time pad
+use super::period::shortest_period;
+use super::scoring::{rank, score_bytes, Candidate};
+use crate::symmetric_encryption::xor::xor::{repeating_key, single_byte, xor};
+
+/// Number of key sizes with the smallest distance that are solved by [`crack_repeating_key`]
+const KEY_SIZE_CANDIDATES: usize = 3;
+
+/// Number of differing bits between two byte strings of the same length
+pub fn hamming_distance(a: &[u8], b: &[u8]) -> u32 {
+    a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum()
+}
+
+/// Key sizes from 1 to `max_key_size`, from the most to the least likely
+///
+/// Two blocks of English text XORed with the same key keep the Hamming
+/// distance of the plaintext blocks, about 2 to 3 bits per byte, while
+/// blocks XORed with different key bytes look random, 4 bits per byte. The
+/// distance between consecutive blocks of $k$ bytes, divided by $k$ and
+/// averaged over the whole ciphertext, is lowest when $k$ is a multiple of
+/// the key size.
+pub fn key_sizes(cipher: &[u8], max_key_size: usize) -> Vec<(usize, f64)> {
+    let mut sizes: Vec<(usize, f64)> = (1..=max_key_size.min(cipher.len() / 2))
+        .map(|size| {
+            let blocks: Vec<&[u8]> = cipher.chunks_exact(size).collect();
+            let total: u32 = blocks
+                .windows(2)
+                .map(|pair| hamming_distance(pair[0], pair[1]))
+                .sum();
+            let distance = total as f64 / ((blocks.len() - 1) * size) as f64;
+            (size, distance)
+        })
+        .collect();
+
+    sizes.sort_by(|a, b| a.1.total_cmp(&b.1));
+    sizes
+}
+
+/// Try the 256 keys of a single-byte XOR, most English-like first
+pub fn single_byte_brute_force(cipher: &[u8]) -> Vec<Candidate<u8>> {
+    let mut candidates: Vec<Candidate<u8>> = (0..=255)
+        .map(|key| {
+            let plaintext = single_byte(cipher, key);
+            Candidate {
+                key,
+                score: score_bytes(&plaintext),
+                plaintext: String::from_utf8_lossy(&plaintext).into_owned(),
+            }
+        })
+        .collect();
+
+    rank(&mut candidates);
+    candidates
+}
+
+/// Break a repeating-key XOR ciphertext
+///
+/// For the most likely key sizes, the ciphertext is split into columns of
+/// bytes XORed with the same key byte, each one broken as a single-byte
+/// XOR. The plaintexts are ranked with the byte frequencies of English, and
+/// keys made of a repeated shorter key are reduced to it.
+pub fn crack_repeating_key(
+    cipher: &[u8],
+    max_key_size: usize,
+) -> Result<Candidate<Vec<u8>>, String> {
+    if max_key_size == 0 {
+        return Err("The maximum key size must be at least 1".to_string());
+    }
+    if cipher.len() < 2 * max_key_size {
+        return Err("Ciphertext too short for the maximum key size".to_string());
+    }
+
+    let mut candidates: Vec<Candidate<Vec<u8>>> = key_sizes(cipher, max_key_size)
+        .into_iter()
+        .take(KEY_SIZE_CANDIDATES)
+        .map(|(size, _)| {
+            let key: Vec<u8> = (0..size)
+                .map(|column| {
+                    let bytes: Vec<u8> =
+                        cipher.iter().skip(column).step_by(size).copied().collect();
+                    single_byte_brute_force(&bytes)[0].key
+                })
+                .collect();
+            let key = shortest_period(&key);
+            let plaintext = repeating_key(cipher, &key).unwrap();
+            Candidate {
+                key,
+                score: score_bytes(&plaintext),
+                plaintext: String::from_utf8_lossy(&plaintext).into_owned(),
+            }
+        })
+        .collect();
+
+    rank(&mut candidates);
+    candidates
+        .into_iter()
+        .next()
+        .ok_or("No candidate key size".to_string())
+}
+
+/// Crib-dragging on two messages encrypted with the same pad
+///
+/// $c_1 \oplus c_2 = m_1 \oplus m_2$: the pad cancels out. Sliding a word
+/// guessed to be in one of the messages, e.g. `" the "`, along
+/// $c_1 \oplus c_2$ reveals the other message at the offsets where the
+/// guess is right. The candidates are the revealed fragments at each
+/// offset, most English-like first.
+pub fn crib_drag(cipher1: &[u8], cipher2: &[u8], crib: &[u8]) -> Vec<Candidate<usize>> {
+    let combined = xor(cipher1, cipher2);
+    if crib.is_empty() || crib.len() > combined.len() {
+        return vec![];
+    }
+
+    let mut candidates: Vec<Candidate<usize>> = combined
+        .windows(crib.len())
+        .enumerate()
+        .map(|(offset, window)| {
+            let fragment = xor(window, crib);
+            Candidate {
+                key: offset,
+                score: score_bytes(&fragment),
+                plaintext: String::from_utf8_lossy(&fragment).into_owned(),
+            }
+        })
+        .collect();
+
+    rank(&mut candidates);
+    candidates
+}
+
+#[cfg(test)]
+const TEXT: &str = "It is a truth universally acknowledged, that a single man in possession \
+    of a good fortune, must be in want of a wife. However little known the feelings or views \
+    of such a man may be on his first entering a neighbourhood, this truth is so well fixed in \
+    the minds of the surrounding families, that he is considered the rightful property of some \
+    one or other of their daughters. My dear Mr. Bennet, said his lady to him one day, have you \
+    heard that Netherfield Park is let at last? Mr. Bennet replied that he had not. But it is, \
+    returned she; for Mrs. Long has just been here, and she told me all about it.";
+
+#[test]
+fn test_hamming_distance() {
+    assert_eq!(hamming_distance(b"this is a test", b"wokka wokka!!!"), 37);
+    assert_eq!(hamming_distance(b"abc", b"abc"), 0);
+}
+
+#[test]
+fn test_single_byte() {
+    let cipher = single_byte(b"Cooking MC's like a pound of bacon", 0x58);
+    let candidates = single_byte_brute_force(&cipher);
+    assert_eq!(candidates.len(), 256);
+    assert_eq!(candidates[0].key, 0x58);
+    assert_eq!(
+        candidates[0].plaintext,
+        "Cooking MC's like a pound of bacon"
+    );
+}
+
+#[test]
+fn test_crack_repeating_key() {
+    for key in [&b"ICE"[..], b"Trivium", b"one time pad reused"] {
+        let cipher = repeating_key(TEXT.as_bytes(), key).unwrap();
+        let candidate = crack_repeating_key(&cipher, 20).unwrap();
+        assert_eq!(candidate.key, key);
+        assert_eq!(candidate.plaintext, TEXT);
+    }
+    assert!(crack_repeating_key(b"short", 20).is_err());
+    assert!(crack_repeating_key(&[1, 2], 0).is_err());
+}
+
+#[test]
+fn test_crib_drag() {
+    use crate::symmetric_encryption::xor::xor::one_time_pad;
+
+    let pad: Vec<u8> = (0..64u32).map(|i| (i * 167 + 13) as u8 ^ 0xa5).collect();
+    let message1 = b"meet me at the north bridge at dawn";
+    let message2 = b"the password for the vault is orchid";
+    let cipher1 = one_time_pad(message1, &pad).unwrap();
+    let cipher2 = one_time_pad(message2, &pad).unwrap();
+
+    let candidates = crib_drag(&cipher1, &cipher2, b" the ");
+    assert_eq!(candidates.len(), cipher1.len() - 4);
+    // " the " is at offset 10 of the first message and 16 of the second one
+    let best: Vec<usize> = candidates[..2].iter().map(|c| c.key).collect();
+    assert!(best.contains(&10) && best.contains(&16));
+    let fragment = candidates.iter().find(|c| c.key == 10).unwrap();
+    assert_eq!(fragment.plaintext.as_bytes(), &message2[10..15]);
+
+    assert!(crib_drag(&cipher1, &cipher2, b"").is_empty());
+}
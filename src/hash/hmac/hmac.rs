@@ -0,0 +1,83 @@
+use crate::hash::sha256::sha256::Sha256;
+
+/// HMAC-SHA-256 (RFC 2104)
+///
+/// $$HMAC(K, m) = H((K' \oplus opad) \| H((K' \oplus ipad) \| m))$$
+///
+/// Where:
+/// - $K'$ is the key padded with zeros to the block size, or hashed first if
+///   it is longer than a block.
+/// - $ipad$ is the byte `0x36` and $opad$ the byte `0x5c` repeated to the block size.
+#[derive(Debug, Clone)]
+pub struct HmacSha256 {
+    inner: Sha256,
+    outer: Sha256,
+}
+
+impl HmacSha256 {
+    const IPAD: u8 = 0x36;
+    const OPAD: u8 = 0x5c;
+
+    pub fn new(key: &[u8]) -> Self {
+        let mut padded_key = [0u8; Sha256::BLOCK_SIZE];
+        if key.len() > Sha256::BLOCK_SIZE {
+            padded_key[..Sha256::OUTPUT_SIZE].copy_from_slice(&Sha256::digest(key));
+        } else {
+            padded_key[..key.len()].copy_from_slice(key);
+        }
+
+        let mut inner = Sha256::new();
+        inner.update(&padded_key.map(|byte| byte ^ Self::IPAD));
+        let mut outer = Sha256::new();
+        outer.update(&padded_key.map(|byte| byte ^ Self::OPAD));
+
+        Self { inner, outer }
+    }
+
+    /// One-shot MAC of `data`
+    pub fn mac(key: &[u8], data: &[u8]) -> [u8; 32] {
+        let mut hmac = Self::new(key);
+        hmac.update(data);
+        hmac.finalize()
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub fn finalize(mut self) -> [u8; 32] {
+        let inner_hash = self.inner.finalize();
+        self.outer.update(&inner_hash);
+        self.outer.finalize()
+    }
+}
+
+#[test]
+fn test_hmac_sha256() {
+    use crate::common::hex::Hex;
+
+    // RFC 4231 test cases 1, 2 and 6
+    assert_eq!(
+        Hex::hex_to_bytes(b"b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7")
+            .unwrap(),
+        HmacSha256::mac(&[0x0b; 20], b"Hi There")
+    );
+
+    let mut hmac = HmacSha256::new(b"Jefe");
+    hmac.update(b"what do ya want ");
+    hmac.update(b"for nothing?");
+    assert_eq!(
+        Hex::hex_to_bytes(b"5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+            .unwrap(),
+        hmac.finalize()
+    );
+
+    assert_eq!(
+        Hex::hex_to_bytes(b"60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54")
+            .unwrap(),
+        HmacSha256::mac(
+            &[0xaa; 131],
+            b"Test Using Larger Than Block-Size Key - Hash Key First"
+        )
+    );
+}
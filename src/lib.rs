@@ -1,4 +1,6 @@
 pub mod common;
+pub mod cryptanalysis;
+pub mod hash;
 pub mod math;
 pub mod rng;
 pub mod symmetric_encryption;
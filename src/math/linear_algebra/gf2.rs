@@ -0,0 +1,142 @@
+use thiserror::Error;
+
+/// System of linear equations over GF(2)
+///
+/// Every equation is $c_0 x_0 \oplus c_1 x_1 \oplus \dots \oplus c_{n-1} x_{n-1} = b$,
+/// with the coefficients packed 64 per word (bit $i \bmod 64$ of word $i / 64$).
+/// Solved by Gaussian elimination, where adding rows is a xor.
+#[derive(Debug, Clone)]
+pub struct Gf2System {
+    vars: usize,
+    rows: Vec<Vec<u64>>,
+    rhs: Vec<u8>,
+}
+
+impl Gf2System {
+    pub fn new(vars: usize) -> Self {
+        Self {
+            vars,
+            rows: Vec::new(),
+            rhs: Vec::new(),
+        }
+    }
+
+    pub fn vars(&self) -> usize {
+        self.vars
+    }
+
+    pub fn add_equation(&mut self, coefficients: &[u64], value: u8) {
+        let mut row = vec![0; self.vars.div_ceil(64)];
+        for (word, coefficient) in row.iter_mut().zip(coefficients.iter()) {
+            *word = *coefficient;
+        }
+        self.rows.push(row);
+        self.rhs.push(value & 1);
+    }
+
+    /// Reduce the system to row echelon form, return the pivot columns
+    fn eliminate(&mut self) -> Vec<usize> {
+        let mut pivots = Vec::new();
+        let mut rank = 0;
+
+        for column in 0..self.vars {
+            let (word, bit) = (column / 64, column % 64);
+            let Some(pivot) =
+                (rank..self.rows.len()).find(|&r| (self.rows[r][word] >> bit) & 1 == 1)
+            else {
+                continue;
+            };
+            self.rows.swap(rank, pivot);
+            self.rhs.swap(rank, pivot);
+
+            // clear the column in every other row (reduced echelon form)
+            let pivot_row = self.rows[rank].clone();
+            let pivot_rhs = self.rhs[rank];
+            for r in 0..self.rows.len() {
+                if r != rank && (self.rows[r][word] >> bit) & 1 == 1 {
+                    for (a, b) in self.rows[r].iter_mut().zip(pivot_row.iter()) {
+                        *a ^= b;
+                    }
+                    self.rhs[r] ^= pivot_rhs;
+                }
+            }
+
+            pivots.push(column);
+            rank += 1;
+        }
+
+        pivots
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().eliminate().len()
+    }
+
+    /// Variables which are not pivots of the reduced system: every value of
+    /// them extends to a solution if the system is consistent
+    pub fn free_variables(&self) -> Vec<usize> {
+        let pivots = self.clone().eliminate();
+        (0..self.vars).filter(|v| !pivots.contains(v)).collect()
+    }
+
+    /// Unique solution of the system, one bit per variable
+    pub fn solve(&self) -> Result<Vec<u8>, Gf2Error> {
+        let mut system = self.clone();
+        let pivots = system.eliminate();
+
+        // a zero row with a nonzero right-hand side is 0 = 1
+        if system.rhs[pivots.len()..].contains(&1) {
+            return Err(Gf2Error::Inconsistent);
+        }
+        if pivots.len() < self.vars {
+            return Err(Gf2Error::Underdetermined(pivots.len(), self.vars));
+        }
+
+        let mut solution = vec![0; self.vars];
+        for (row, &column) in pivots.iter().enumerate() {
+            solution[column] = system.rhs[row];
+        }
+        Ok(solution)
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum Gf2Error {
+    #[error("The system is inconsistent")]
+    Inconsistent,
+
+    #[error("The system has rank {0} for {1} variables, the solution is not unique")]
+    Underdetermined(usize, usize),
+}
+
+#[test]
+fn test_gf2_system() {
+    // x0 ^ x1 = 1, x1 ^ x2 = 0, x0 ^ x1 ^ x2 = 0
+    let mut system = Gf2System::new(3);
+    system.add_equation(&[0b011], 1);
+    system.add_equation(&[0b110], 0);
+    assert_eq!(system.solve(), Err(Gf2Error::Underdetermined(2, 3)));
+    assert_eq!(system.free_variables(), vec![2]);
+    system.add_equation(&[0b111], 0);
+    assert_eq!(system.rank(), 3);
+    assert_eq!(system.solve().unwrap(), vec![0, 1, 1]);
+
+    // redundant equations are fine, contradicting ones are not
+    system.add_equation(&[0b101], 1);
+    assert_eq!(system.solve().unwrap(), vec![0, 1, 1]);
+    system.add_equation(&[0b100], 0);
+    assert_eq!(system.solve(), Err(Gf2Error::Inconsistent));
+
+    // across word boundaries: x_i = parity of i for 130 variables
+    let mut system = Gf2System::new(130);
+    for i in 0..130 {
+        let mut row = [0u64; 3];
+        row[i / 64] |= 1 << (i % 64);
+        system.add_equation(&row, (i % 2) as u8);
+    }
+    let solution = system.solve().unwrap();
+    assert!(solution
+        .iter()
+        .enumerate()
+        .all(|(i, &x)| x == (i % 2) as u8));
+}
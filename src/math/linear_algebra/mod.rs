@@ -0,0 +1,2 @@
+pub mod gf2;
+pub mod zn;
@@ -0,0 +1,169 @@
+use crate::math::modular_arithmetic::mod_inverse::mod_inverse;
+
+/// Square matrix over $\mathbb{Z}_m$, stored row by row
+pub type Matrix = Vec<Vec<i32>>;
+
+/// Check that `matrix` is square and not empty
+pub fn check_square(matrix: &[Vec<i32>]) -> Result<usize, String> {
+    let n = matrix.len();
+    if n == 0 || matrix.iter().any(|row| row.len() != n) {
+        return Err("The matrix must be square".to_string());
+    }
+    Ok(n)
+}
+
+/// Determinant modulo `m`
+///
+/// $\mathbb{Z}_m$ is not a field when $m$ is composite, so a pivot cannot
+/// always be inverted. Instead, each column is cleared with the Euclidean
+/// algorithm on rows: subtract $q$ times the row with the smaller entry from
+/// the other one, swap, and repeat until one entry is 0. These operations
+/// leave the determinant unchanged, up to the sign of the swaps, and the
+/// triangular result has the determinant on its diagonal.
+pub fn determinant(matrix: &[Vec<i32>], m: i32) -> Result<i32, String> {
+    let n = check_square(matrix)?;
+    let m = m as i64;
+    let mut rows: Vec<Vec<i64>> = matrix
+        .iter()
+        .map(|row| row.iter().map(|&x| (x as i64).rem_euclid(m)).collect())
+        .collect();
+    let mut det = 1i64;
+
+    for column in 0..n {
+        for r in column + 1..n {
+            // Euclid on rows `column` and `r` until rows[r][column] == 0
+            while rows[r][column] != 0 {
+                let q = rows[column][column] / rows[r][column];
+                let other = rows[r].clone();
+                for (a, b) in rows[column][column..].iter_mut().zip(&other[column..]) {
+                    *a = (*a - q * b).rem_euclid(m);
+                }
+                rows.swap(column, r);
+                det = -det;
+            }
+        }
+        det = (det * rows[column][column]).rem_euclid(m);
+    }
+
+    Ok(det.rem_euclid(m) as i32)
+}
+
+/// Inverse modulo `m`, which exists if and only if $\gcd(\det, m) = 1$
+///
+/// $$A^{-1} = \det(A)^{-1} \operatorname{adj}(A)$$
+///
+/// where the adjugate is the transpose of the matrix of cofactors
+/// $C_{ij} = (-1)^{i+j} \det(A_{ij})$, $A_{ij}$ being $A$ without row $i$ and
+/// column $j$.
+pub fn inverse(matrix: &[Vec<i32>], m: i32) -> Result<Matrix, String> {
+    let n = check_square(matrix)?;
+    let det = determinant(matrix, m)?;
+    let det_inverse =
+        mod_inverse(det, m).map_err(|_| "The matrix is not invertible".to_string())? as i64;
+
+    if n == 1 {
+        return Ok(vec![vec![det_inverse as i32]]);
+    }
+
+    let cofactors = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    let minor: Matrix = (0..n)
+                        .filter(|&r| r != i)
+                        .map(|r| (0..n).filter(|&c| c != j).map(|c| matrix[r][c]).collect())
+                        .collect();
+                    let sign = if (i + j) % 2 == 0 { 1 } else { -1 };
+                    let cofactor = sign * determinant(&minor, m)? as i64;
+                    Ok((det_inverse * cofactor).rem_euclid(m as i64) as i32)
+                })
+                .collect::<Result<Vec<i32>, String>>()
+        })
+        .collect::<Result<Matrix, String>>()?;
+
+    // the adjugate is the transpose of the cofactor matrix
+    Ok(transpose(&cofactors))
+}
+
+/// Product of two matrices modulo `m`
+pub fn multiply(a: &[Vec<i32>], b: &[Vec<i32>], m: i32) -> Matrix {
+    let columns = b.first().map_or(0, |row| row.len());
+    a.iter()
+        .map(|row| {
+            (0..columns)
+                .map(|c| {
+                    let sum: i64 = row
+                        .iter()
+                        .zip(b.iter())
+                        .map(|(&x, b_row)| x as i64 * b_row[c] as i64)
+                        .sum();
+                    sum.rem_euclid(m as i64) as i32
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Product of a matrix and a column vector modulo `m`
+pub fn multiply_vector(matrix: &[Vec<i32>], vector: &[i32], m: i32) -> Vec<i32> {
+    matrix
+        .iter()
+        .map(|row| {
+            let sum: i64 = row
+                .iter()
+                .zip(vector.iter())
+                .map(|(&x, &v)| x as i64 * v as i64)
+                .sum();
+            sum.rem_euclid(m as i64) as i32
+        })
+        .collect()
+}
+
+/// Transpose of a matrix
+pub fn transpose(matrix: &[Vec<i32>]) -> Matrix {
+    let columns = matrix.first().map_or(0, |row| row.len());
+    (0..columns)
+        .map(|c| matrix.iter().map(|row| row[c]).collect())
+        .collect()
+}
+
+#[test]
+fn test_determinant() {
+    assert_eq!(determinant(&[vec![3, 3], vec![2, 5]], 26), Ok(9));
+    assert_eq!(
+        determinant(&[vec![6, 24, 1], vec![13, 16, 10], vec![20, 17, 15]], 26),
+        Ok(25)
+    );
+    // no entry of the first column is invertible modulo 26
+    assert_eq!(determinant(&[vec![2, 13], vec![13, 2]], 26), Ok(17));
+    assert_eq!(determinant(&[vec![1, 2], vec![2, 4]], 26), Ok(0));
+    assert!(determinant(&[vec![1, 2]], 26).is_err());
+}
+
+#[test]
+fn test_inverse() {
+    let key = vec![vec![6, 24, 1], vec![13, 16, 10], vec![20, 17, 15]];
+    let inv = inverse(&key, 26).unwrap();
+    assert_eq!(inv, vec![vec![8, 5, 10], vec![21, 8, 21], vec![21, 12, 8]]);
+    assert_eq!(
+        multiply(&key, &inv, 26),
+        vec![vec![1, 0, 0], vec![0, 1, 0], vec![0, 0, 1]]
+    );
+
+    let key = vec![vec![2, 13], vec![13, 2]];
+    let inv = inverse(&key, 26).unwrap();
+    assert_eq!(multiply(&inv, &key, 26), vec![vec![1, 0], vec![0, 1]]);
+
+    assert_eq!(
+        inverse(&[vec![2, 4], vec![1, 3]], 26).unwrap_err(),
+        "The matrix is not invertible"
+    );
+    assert_eq!(
+        multiply_vector(&[vec![3, 3], vec![2, 5]], &[7, 4], 26),
+        vec![7, 8]
+    );
+    assert_eq!(
+        transpose(&[vec![1, 2], vec![3, 4]]),
+        vec![vec![1, 3], vec![2, 4]]
+    );
+}
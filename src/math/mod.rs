@@ -1 +1,2 @@
+pub mod linear_algebra;
 pub mod modular_arithmetic;
@@ -41,6 +41,19 @@ pub fn gcdi(mut a: i32, mut b: i32) -> i32 {
     a.abs()
 }
 
+/// Iterative gcd over unsigned 64-bit integers
+///
+/// Same algorithm as [`gcdi`], used where the operands (e.g. RSA or Blum
+/// Blum Shub moduli) do not fit in an `i32`.
+pub fn gcd_u64(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let carry = b;
+        b = a % b;
+        a = carry;
+    }
+    a
+}
+
 ///  Recursive Extended Euclidean Algorithm
 ///
 /// #### Overview
@@ -152,7 +165,7 @@ pub fn egcd(a: i32, b: i32) -> (i32, i32, i32) {
 
     let y_p = x - a / b * y;
 
-    return (gcd, x_p, y_p);
+    (gcd, x_p, y_p)
 }
 
 /// Iterative Extended Euclidean algorithm
@@ -206,7 +219,7 @@ pub fn egcdi(mut a: i32, mut b: i32) -> (i32, i32, i32) {
         b = new_b;
     }
 
-    return (a, x_p, y_p);
+    (a, x_p, y_p)
 }
 
 #[test]
@@ -220,6 +233,10 @@ fn test_gcd() {
     assert_eq!(gcdi(216, 111), 3);
     assert_eq!(gcdi(216, -111), 3);
     assert_eq!(gcdi(-216, -111), 3);
+
+    assert_eq!(gcd_u64(216, 111), 3);
+    assert_eq!(gcd_u64(0, 7), 7);
+    assert_eq!(gcd_u64(4_294_967_311 * 3, 4_294_967_311 * 5), 4_294_967_311);
 }
 
 #[test]
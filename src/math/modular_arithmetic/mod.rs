@@ -1,2 +1,4 @@
 pub mod gcd;
 pub mod mod_inverse;
+pub mod mod_pow;
+pub mod primality;
@@ -0,0 +1,50 @@
+/// Modular multiplication $a \times b \bmod m$
+///
+/// The product is computed in 128 bits so it cannot overflow for any
+/// 64-bit modulus.
+pub fn mod_mul(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+/// Square-and-multiply modular exponentiation
+///
+/// Computes $base^{exp} \bmod m$ by scanning the exponent from the least
+/// significant bit:
+///
+/// - if the current bit of the exponent is 1, multiply the result by the base
+/// - square the base for the next bit
+///
+/// This needs $\log_2(exp)$ squarings instead of $exp$ multiplications.
+///
+/// # See also
+/// - [Modular exponentiation](https://en.wikipedia.org/wiki/Modular_exponentiation)
+pub fn mod_pow(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    if m == 1 {
+        return 0;
+    }
+
+    let mut result = 1;
+    base %= m;
+
+    while exp > 0 {
+        // the current bit is set, multiply it into the result
+        if exp & 1 == 1 {
+            result = mod_mul(result, base, m);
+        }
+        // move on to the next bit
+        base = mod_mul(base, base, m);
+        exp >>= 1;
+    }
+
+    result
+}
+
+#[test]
+fn test_mod_pow() {
+    assert_eq!(mod_pow(4, 13, 497), 445);
+    assert_eq!(mod_pow(2, 0, 7), 1);
+    assert_eq!(mod_pow(5, 3, 1), 0);
+    // Fermat's little theorem: a^(p-1) = 1 mod p
+    assert_eq!(mod_pow(3, 4_294_967_310, 4_294_967_311), 1);
+    assert_eq!(mod_mul(u64::MAX, u64::MAX, 1_000_000_007), 114_944_269);
+}
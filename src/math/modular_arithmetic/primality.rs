@@ -0,0 +1,105 @@
+use super::mod_pow::{mod_mul, mod_pow};
+
+/// Deterministic Miller-Rabin primality test for 64-bit integers
+///
+/// Write $n - 1 = 2^s \cdot d$ with $d$ odd. For a base $a$, $n$ passes the
+/// round if
+///
+/// $$a^d \equiv 1 \pmod n \quad \text{or} \quad a^{2^r d} \equiv -1 \pmod n$$
+///
+/// for some $0 \le r < s$. A composite $n$ passes only for a small fraction of
+/// the bases, and testing the first 12 primes as bases is known to be
+/// sufficient for every $n < 2^{64}$, so the result is exact.
+///
+/// # See also
+/// - [Miller-Rabin primality test](https://en.wikipedia.org/wiki/Miller%E2%80%93Rabin_primality_test)
+pub fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if n < 2 {
+        return false;
+    }
+    // small primes and their multiples
+    for p in BASES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    // n - 1 = 2^s * d
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    'bases: for a in BASES {
+        let mut x = mod_pow(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mod_mul(x, x, n);
+            if x == n - 1 {
+                continue 'bases;
+            }
+        }
+        // a is a witness that n is composite
+        return false;
+    }
+
+    true
+}
+
+/// Distinct prime factors of `n` in increasing order, by trial division
+///
+/// Only meant for the small moduli used in the educational generators, the
+/// running time is $O(\sqrt{n})$.
+pub fn prime_factors(mut n: u64) -> Vec<u64> {
+    let mut factors = Vec::new();
+    let mut f = 2;
+
+    // f * f overflows for the factors above 2^32
+    while f <= n / f {
+        if n.is_multiple_of(f) {
+            factors.push(f);
+            while n.is_multiple_of(f) {
+                n /= f;
+            }
+        }
+        f += if f == 2 { 1 } else { 2 };
+    }
+    // whatever is left is a prime larger than sqrt(n)
+    if n > 1 {
+        factors.push(n);
+    }
+
+    factors
+}
+
+#[test]
+fn test_is_prime() {
+    let primes: Vec<u64> = (0..50).filter(|&n| is_prime(n)).collect();
+    assert_eq!(
+        primes,
+        vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47]
+    );
+
+    assert!(is_prime(4_294_967_311));
+    assert!(is_prime(18_446_744_073_709_551_557));
+    // Carmichael number
+    assert!(!is_prime(561));
+    // strong pseudoprime to bases 2, 3, 5 and 7
+    assert!(!is_prime(3_215_031_751));
+    assert!(!is_prime(4_294_967_311 * 3));
+}
+
+#[test]
+fn test_prime_factors() {
+    assert_eq!(prime_factors(1), vec![]);
+    assert_eq!(prime_factors(2), vec![2]);
+    assert_eq!(prime_factors(360), vec![2, 3, 5]);
+    assert_eq!(prime_factors(4_294_967_310), vec![2, 3, 5, 131, 364_289]);
+    assert_eq!(
+        prime_factors(u64::MAX),
+        vec![3, 5, 17, 257, 641, 65_537, 6_700_417]
+    );
+    assert_eq!(prime_factors(1 << 63), vec![2]);
+}
@@ -0,0 +1,185 @@
+// Blum Blum Shub
+use thiserror::Error;
+
+use super::traits::Rng;
+use crate::math::modular_arithmetic::{gcd::gcd_u64, mod_pow::mod_mul, primality::is_prime};
+
+/// Blum Blum Shub cryptographically secure pseudorandom bit generator
+///
+/// With $n = p \cdot q$ for two distinct Blum primes ($p \equiv q \equiv 3 \pmod 4$)
+/// and a seed $s$ coprime to $n$:
+///
+/// $$
+/// \begin{align}
+/// &x_0 = s^2 \bmod n \newline
+/// &x_{i+1} = x_i^2 \bmod n \newline
+/// &z_{i+1} = \text{the } k \text{ least significant bits of } x_{i+1}
+/// \end{align}
+/// $$
+///
+/// Predicting the next bit is as hard as factoring $n$ as long as
+/// $k \le \log_2 \log_2 n$, see [`BlumBlumShub::max_secure_bits`].
+///
+/// # See also
+/// - Menezes, van Oorschot and Vanstone. Handbook of Applied Cryptography, Algorithm 5.40.
+#[derive(Debug)]
+pub struct BlumBlumShub {
+    n: u64,
+    state: u64,
+    bits_per_step: u32,
+    buffer: u64,
+    buffered: u32,
+}
+
+impl BlumBlumShub {
+    pub fn new(p: u64, q: u64, seed: u64, bits_per_step: u32) -> Result<Self, BbsError> {
+        for prime in [p, q] {
+            if !is_blum_prime(prime) {
+                return Err(BbsError::NotBlumPrime(prime));
+            }
+        }
+
+        if p == q {
+            return Err(BbsError::EqualPrimes);
+        }
+
+        let n = p.checked_mul(q).ok_or(BbsError::ModulusOverflow(p, q))?;
+
+        // the seed must be a unit of Z_n, otherwise the sequence leaks a factor of n
+        if seed < 2 || seed >= n || gcd_u64(seed, n) != 1 {
+            return Err(BbsError::InvalidSeed(seed));
+        }
+
+        let max_bits = max_secure_bits(n);
+        if bits_per_step == 0 || bits_per_step > max_bits {
+            return Err(BbsError::InvalidBitsPerStep(bits_per_step, max_bits));
+        }
+
+        Ok(Self {
+            n,
+            state: mod_mul(seed, seed, n),
+            bits_per_step,
+            buffer: 0,
+            buffered: 0,
+        })
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.n
+    }
+
+    /// Largest number of bits that can be extracted per squaring, $\lfloor \log_2 \log_2 n \rfloor$
+    pub fn max_secure_bits(&self) -> u32 {
+        max_secure_bits(self.n)
+    }
+
+    /// Square the state once and return its `bits_per_step` least significant bits
+    pub fn next_bits(&mut self) -> u64 {
+        self.state = mod_mul(self.state, self.state, self.n);
+        self.state & ((1 << self.bits_per_step) - 1)
+    }
+
+    /// Next single bit, the bits of each step are returned msb-first
+    pub fn next_bit(&mut self) -> u8 {
+        if self.buffered == 0 {
+            self.buffer = self.next_bits();
+            self.buffered = self.bits_per_step;
+        }
+        self.buffered -= 1;
+        ((self.buffer >> self.buffered) & 1) as u8
+    }
+}
+
+impl Rng for BlumBlumShub {
+    fn next_u64(&mut self) -> u64 {
+        (0..64).fold(0, |acc, _| (acc << 1) | self.next_bit() as u64)
+    }
+}
+
+/// A Blum prime is a prime congruent to 3 modulo 4
+pub fn is_blum_prime(p: u64) -> bool {
+    p % 4 == 3 && is_prime(p)
+}
+
+/// $\lfloor \log_2 \log_2 n \rfloor$, at least 1
+pub(crate) fn max_secure_bits(n: u64) -> u32 {
+    // floor(log2(n)) is the index of the highest set bit
+    let log_n = 63 - n.leading_zeros();
+    (31 - log_n.leading_zeros()).max(1)
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum BbsError {
+    #[error("{0} is not a Blum prime (prime congruent to 3 mod 4)")]
+    NotBlumPrime(u64),
+
+    #[error("p and q must be distinct")]
+    EqualPrimes,
+
+    #[error("Modulus {0} * {1} does not fit in 64 bits")]
+    ModulusOverflow(u64, u64),
+
+    #[error("Invalid seed {0}: must be in [2, n - 1] and coprime to n")]
+    InvalidSeed(u64),
+
+    #[error("Invalid bits per step {0}: must be in [1, {1}]")]
+    InvalidBitsPerStep(u32, u32),
+}
+
+#[test]
+fn test_bbs() {
+    // p = 11, q = 23, n = 253, seed = 3
+    // x_i: 81, 236, 36, 31, 202, 71, 234, 108
+    let mut bbs = BlumBlumShub::new(11, 23, 3, 1).unwrap();
+    assert_eq!(bbs.modulus(), 253);
+    assert_eq!(bbs.max_secure_bits(), 2);
+
+    let bits: Vec<u8> = (0..8).map(|_| bbs.next_bit()).collect();
+    assert_eq!(bits, vec![1, 0, 0, 1, 0, 1, 0, 0]);
+
+    // two bits per step: 81 -> 01, 236 -> 00, 36 -> 00, 31 -> 11
+    let mut bbs = BlumBlumShub::new(11, 23, 3, 2).unwrap();
+    let steps: Vec<u64> = (0..4).map(|_| bbs.next_bits()).collect();
+    assert_eq!(steps, vec![0b01, 0b00, 0b00, 0b11]);
+
+    let mut bbs = BlumBlumShub::new(11, 23, 3, 2).unwrap();
+    let bits: Vec<u8> = (0..8).map(|_| bbs.next_bit()).collect();
+    assert_eq!(bits, vec![0, 1, 0, 0, 0, 0, 1, 1]);
+
+    // large Blum primes
+    let mut bbs = BlumBlumShub::new(4_294_967_291, 4_294_967_279, 123_456_789, 5).unwrap();
+    assert_eq!(bbs.max_secure_bits(), 5);
+    assert_ne!(bbs.next_u64(), bbs.next_u64());
+}
+
+#[test]
+fn test_bbs_errors() {
+    assert_eq!(
+        BlumBlumShub::new(13, 23, 3, 1).unwrap_err(),
+        BbsError::NotBlumPrime(13)
+    );
+    assert_eq!(
+        BlumBlumShub::new(11, 15, 3, 1).unwrap_err(),
+        BbsError::NotBlumPrime(15)
+    );
+    assert_eq!(
+        BlumBlumShub::new(11, 11, 3, 1).unwrap_err(),
+        BbsError::EqualPrimes
+    );
+    assert_eq!(
+        BlumBlumShub::new(18_446_744_073_709_551_427, 11, 3, 1).unwrap_err(),
+        BbsError::ModulusOverflow(18_446_744_073_709_551_427, 11)
+    );
+    assert_eq!(
+        BlumBlumShub::new(11, 23, 22, 1).unwrap_err(),
+        BbsError::InvalidSeed(22)
+    );
+    assert_eq!(
+        BlumBlumShub::new(11, 23, 253, 1).unwrap_err(),
+        BbsError::InvalidSeed(253)
+    );
+    assert_eq!(
+        BlumBlumShub::new(11, 23, 3, 3).unwrap_err(),
+        BbsError::InvalidBitsPerStep(3, 2)
+    );
+}
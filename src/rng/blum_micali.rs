@@ -0,0 +1,169 @@
+// Blum-Micali
+use thiserror::Error;
+
+use super::{bbs::max_secure_bits, traits::Rng};
+use crate::math::modular_arithmetic::{
+    mod_pow::mod_pow,
+    primality::{is_prime, prime_factors},
+};
+
+/// Blum-Micali cryptographically secure pseudorandom bit generator
+///
+/// With a prime $p$, a generator $g$ of $\mathbb{Z}_p^*$ and a seed $x_0 \in [1, p - 1]$:
+///
+/// $$
+/// \begin{align}
+/// &x_{i+1} = g^{x_i} \bmod p \newline
+/// &z_{i+1} = \left\lfloor \frac{(x_{i+1} - 1) \cdot 2^k}{p - 1} \right\rfloor
+/// \end{align}
+/// $$
+///
+/// i.e. the output is the index of the $2^k$-th part of $[1, p - 1]$ that
+/// contains $x_{i+1}$. For $k = 1$ this is the classic predicate
+/// "is $x_{i+1}$ in the upper half", and predicting it is as hard as
+/// computing discrete logarithms modulo $p$.
+///
+/// # See also
+/// - Blum, Manuel, and Silvio Micali. "How to Generate Cryptographically Strong Sequences of Pseudo-Random Bits."
+#[derive(Debug)]
+pub struct BlumMicali {
+    p: u64,
+    g: u64,
+    state: u64,
+    bits_per_step: u32,
+    buffer: u64,
+    buffered: u32,
+}
+
+impl BlumMicali {
+    pub fn new(p: u64, g: u64, seed: u64, bits_per_step: u32) -> Result<Self, BlumMicaliError> {
+        if p < 3 || !is_prime(p) {
+            return Err(BlumMicaliError::NotPrime(p));
+        }
+
+        if !is_generator(g, p) {
+            return Err(BlumMicaliError::NotGenerator(g, p));
+        }
+
+        if seed == 0 || seed >= p {
+            return Err(BlumMicaliError::InvalidSeed(seed));
+        }
+
+        let max_bits = max_secure_bits(p);
+        if bits_per_step == 0 || bits_per_step > max_bits {
+            return Err(BlumMicaliError::InvalidBitsPerStep(bits_per_step, max_bits));
+        }
+
+        Ok(Self {
+            p,
+            g,
+            state: seed,
+            bits_per_step,
+            buffer: 0,
+            buffered: 0,
+        })
+    }
+
+    /// Largest number of bits that can be extracted per step, $\lfloor \log_2 \log_2 p \rfloor$
+    pub fn max_secure_bits(&self) -> u32 {
+        max_secure_bits(self.p)
+    }
+
+    /// Exponentiate the state once and return the `bits_per_step` bits of its interval index
+    pub fn next_bits(&mut self) -> u64 {
+        self.state = mod_pow(self.g, self.state, self.p);
+        // (p - 1) * 2^k may not fit in 64 bits
+        ((((self.state - 1) as u128) << self.bits_per_step) / (self.p - 1) as u128) as u64
+    }
+
+    /// Next single bit, the bits of each step are returned msb-first
+    pub fn next_bit(&mut self) -> u8 {
+        if self.buffered == 0 {
+            self.buffer = self.next_bits();
+            self.buffered = self.bits_per_step;
+        }
+        self.buffered -= 1;
+        ((self.buffer >> self.buffered) & 1) as u8
+    }
+}
+
+impl Rng for BlumMicali {
+    fn next_u64(&mut self) -> u64 {
+        (0..64).fold(0, |acc, _| (acc << 1) | self.next_bit() as u64)
+    }
+}
+
+/// Check that `g` generates $\mathbb{Z}_p^*$ for a prime `p`
+///
+/// The order of $g$ divides $p - 1$, so $g$ is a generator iff
+/// $g^{(p-1)/f} \not\equiv 1 \pmod p$ for every prime factor $f$ of $p - 1$.
+pub fn is_generator(g: u64, p: u64) -> bool {
+    if g < 2 || g >= p {
+        return false;
+    }
+
+    prime_factors(p - 1)
+        .into_iter()
+        .all(|f| mod_pow(g, (p - 1) / f, p) != 1)
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum BlumMicaliError {
+    #[error("{0} is not an odd prime")]
+    NotPrime(u64),
+
+    #[error("{0} is not a generator of Z_{1}^*")]
+    NotGenerator(u64, u64),
+
+    #[error("Invalid seed {0}: must be in [1, p - 1]")]
+    InvalidSeed(u64),
+
+    #[error("Invalid bits per step {0}: must be in [1, {1}]")]
+    InvalidBitsPerStep(u32, u32),
+}
+
+#[test]
+fn test_blum_micali() {
+    // p = 1019, g = 2, seed = 5
+    // x_i: 32, 500, 611, 865, 804, 187, 986, 858
+    let mut bm = BlumMicali::new(1019, 2, 5, 1).unwrap();
+    assert_eq!(bm.max_secure_bits(), 3);
+
+    let bits: Vec<u8> = (0..8).map(|_| bm.next_bit()).collect();
+    assert_eq!(bits, vec![0, 0, 1, 1, 1, 0, 1, 1]);
+
+    let mut bm = BlumMicali::new(1019, 2, 5, 3).unwrap();
+    let steps: Vec<u64> = (0..8).map(|_| bm.next_bits()).collect();
+    assert_eq!(steps, vec![0, 3, 4, 6, 6, 1, 7, 6]);
+
+    // p > 2^60 with k = 5: (x - 1) * 2^k overflows 64 bits
+    let p = 1_152_921_504_677_177_237;
+    let mut bm = BlumMicali::new(p, 2, 5, 5).unwrap();
+    assert_eq!(bm.max_secure_bits(), 5);
+    let steps: Vec<u64> = (0..8).map(|_| bm.next_bits()).collect();
+    assert_eq!(steps, vec![0, 0, 25, 10, 11, 24, 5, 29]);
+
+    assert!(is_generator(2, 1019));
+    assert!(!is_generator(3, 1019));
+    assert!(!is_generator(1019, 1019));
+}
+
+#[test]
+fn test_blum_micali_errors() {
+    assert_eq!(
+        BlumMicali::new(1020, 2, 5, 1).unwrap_err(),
+        BlumMicaliError::NotPrime(1020)
+    );
+    assert_eq!(
+        BlumMicali::new(1019, 3, 5, 1).unwrap_err(),
+        BlumMicaliError::NotGenerator(3, 1019)
+    );
+    assert_eq!(
+        BlumMicali::new(1019, 2, 0, 1).unwrap_err(),
+        BlumMicaliError::InvalidSeed(0)
+    );
+    assert_eq!(
+        BlumMicali::new(1019, 2, 5, 4).unwrap_err(),
+        BlumMicaliError::InvalidBitsPerStep(4, 3)
+    );
+}
@@ -0,0 +1,314 @@
+// ChaCha keystream generator
+use thiserror::Error;
+
+use super::traits::Rng;
+
+/// "expand 32-byte k"
+pub const CONSTANTS: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+/// ChaCha quarter round on the words `a`, `b`, `c` and `d` of the state
+///
+/// ```text
+/// a += b; d ^= a; d <<<= 16;
+/// c += d; b ^= c; b <<<= 12;
+/// a += b; d ^= a; d <<<= 8;
+/// c += d; b ^= c; b <<<= 7;
+/// ```
+pub fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+/// The `rounds` rounds of ChaCha on `state`, without the final addition
+///
+/// Each double round applies the quarter round to the 4 columns, then to the
+/// 4 diagonals. Alone, the rounds are a permutation of the state: HChaCha
+/// keeps part of their output to derive subkeys.
+pub fn chacha_rounds(state: &mut [u32; 16], rounds: usize) {
+    for _ in 0..rounds / 2 {
+        // column round
+        quarter_round(state, 0, 4, 8, 12);
+        quarter_round(state, 1, 5, 9, 13);
+        quarter_round(state, 2, 6, 10, 14);
+        quarter_round(state, 3, 7, 11, 15);
+        // diagonal round
+        quarter_round(state, 0, 5, 10, 15);
+        quarter_round(state, 1, 6, 11, 12);
+        quarter_round(state, 2, 7, 8, 13);
+        quarter_round(state, 3, 4, 9, 14);
+    }
+}
+
+/// ChaCha block function
+///
+/// The 4x4 input matrix is
+///
+/// ```text
+/// cccccccc  cccccccc  cccccccc  cccccccc
+/// kkkkkkkk  kkkkkkkk  kkkkkkkk  kkkkkkkk
+/// kkkkkkkk  kkkkkkkk  kkkkkkkk  kkkkkkkk
+/// bbbbbbbb  bbbbbbbb  nnnnnnnn  nnnnnnnn
+/// ```
+///
+/// with c = constant, k = key, b = block counter, n = nonce. After the
+/// rounds of [`chacha_rounds`], the input is added to the mixed state so the
+/// function cannot be inverted.
+pub fn chacha_block(input: &[u32; 16], rounds: usize) -> [u32; 16] {
+    let mut state = *input;
+    chacha_rounds(&mut state, rounds);
+
+    for (word, input_word) in state.iter_mut().zip(input.iter()) {
+        *word = word.wrapping_add(*input_word);
+    }
+
+    state
+}
+
+/// Cryptographically secure generator built on the ChaCha keystream
+///
+/// The 256-bit seed is the ChaCha key. Words 12-13 of the input matrix hold
+/// a 64-bit block counter and words 14-15 a 64-bit stream id (the original
+/// layout of Bernstein's ChaCha), so every seed gives $2^{64}$ independent
+/// streams of $2^{64}$ blocks each.
+///
+/// The output is the keystream read as little-endian 32-bit words. The
+/// position in the keystream is counted in words and can be set freely with
+/// [`ChaChaRng::set_word_pos`].
+#[derive(Debug)]
+pub struct ChaChaRng {
+    key: [u32; 8],
+    stream: u64,
+    rounds: usize,
+    word_pos: u128,
+    block: [u32; 16],
+    // counter of the block currently held in `block`
+    block_counter: Option<u64>,
+}
+
+impl ChaChaRng {
+    pub fn new(seed: &[u8; 32], rounds: usize) -> Result<Self, ChaChaError> {
+        if ![8, 12, 20].contains(&rounds) {
+            return Err(ChaChaError::InvalidRounds(rounds));
+        }
+
+        let mut rng = Self {
+            key: [0; 8],
+            stream: 0,
+            rounds,
+            word_pos: 0,
+            block: [0; 16],
+            block_counter: None,
+        };
+        rng.reseed(seed);
+
+        Ok(rng)
+    }
+
+    /// Seed a new generator with 32 bytes taken from another generator
+    pub fn from_rng<R: Rng>(rng: &mut R, rounds: usize) -> Result<Self, ChaChaError> {
+        let mut seed = [0; 32];
+        rng.fill_bytes(&mut seed);
+        Self::new(&seed, rounds)
+    }
+
+    /// Replace the key and restart at the beginning of the current stream
+    pub fn reseed(&mut self, seed: &[u8; 32]) {
+        for (word, bytes) in self.key.iter_mut().zip(seed.chunks(4)) {
+            *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        self.word_pos = 0;
+        self.block_counter = None;
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    pub fn get_stream(&self) -> u64 {
+        self.stream
+    }
+
+    /// Switch to another stream, keeping the word position
+    pub fn set_stream(&mut self, stream: u64) {
+        self.stream = stream;
+        self.block_counter = None;
+    }
+
+    /// Position of the next output word in the keystream
+    pub fn get_word_pos(&self) -> u128 {
+        self.word_pos
+    }
+
+    /// Seek to a word position, only the low 68 bits are used
+    /// (64-bit block counter and 16 words per block)
+    pub fn set_word_pos(&mut self, word_pos: u128) {
+        self.word_pos = word_pos & ((1 << 68) - 1);
+    }
+
+    pub fn next_word(&mut self) -> u32 {
+        let counter = (self.word_pos / 16) as u64;
+        if self.block_counter != Some(counter) {
+            self.refill(counter);
+        }
+
+        let word = self.block[(self.word_pos % 16) as usize];
+        self.word_pos = (self.word_pos + 1) & ((1 << 68) - 1);
+        word
+    }
+
+    fn refill(&mut self, counter: u64) {
+        let mut input = [0; 16];
+        input[..4].copy_from_slice(&CONSTANTS);
+        input[4..12].copy_from_slice(&self.key);
+        input[12] = counter as u32;
+        input[13] = (counter >> 32) as u32;
+        input[14] = self.stream as u32;
+        input[15] = (self.stream >> 32) as u32;
+
+        self.block = chacha_block(&input, self.rounds);
+        self.block_counter = Some(counter);
+    }
+}
+
+impl Rng for ChaChaRng {
+    fn next_u32(&mut self) -> u32 {
+        self.next_word()
+    }
+
+    /// Two consecutive words, the first one in the low half
+    fn next_u64(&mut self) -> u64 {
+        let low = self.next_word() as u64;
+        let high = self.next_word() as u64;
+        (high << 32) | low
+    }
+
+    /// Raw keystream bytes
+    ///
+    /// A partially used word is discarded, so the next call starts on a word
+    /// boundary.
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let word = self.next_word().to_le_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ChaChaError {
+    #[error("Invalid number of rounds: expected 8, 12 or 20, got {0}")]
+    InvalidRounds(usize),
+}
+
+#[test]
+fn test_quarter_round() {
+    // RFC 8439 section 2.2.1
+    let mut state = [
+        0x879531e0, 0xc5ecf37d, 0x516461b1, 0xc9a62f8a, 0x44c20ef3, 0x3390af7f, 0xd9fc690b,
+        0x2a5f714c, 0x53372767, 0xb00a5631, 0x974c541a, 0x359e9963, 0x5c971061, 0x3d631689,
+        0x2098d9d6, 0x91dbd320,
+    ];
+    quarter_round(&mut state, 2, 7, 8, 13);
+    assert_eq!(
+        state,
+        [
+            0x879531e0, 0xc5ecf37d, 0xbdb886dc, 0xc9a62f8a, 0x44c20ef3, 0x3390af7f, 0xd9fc690b,
+            0xcfacafd2, 0xe46bea80, 0xb00a5631, 0x974c541a, 0x359e9963, 0x5c971061, 0xccc07c79,
+            0x2098d9d6, 0x91dbd320,
+        ]
+    );
+}
+
+#[test]
+fn test_chacha_block() {
+    // RFC 8439 section 2.3.2
+    let input = [
+        0x61707865, 0x3320646e, 0x79622d32, 0x6b206574, 0x03020100, 0x07060504, 0x0b0a0908,
+        0x0f0e0d0c, 0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c, 0x00000001, 0x09000000,
+        0x4a000000, 0x00000000,
+    ];
+    assert_eq!(
+        chacha_block(&input, 20),
+        [
+            0xe4e7f110, 0x15593bd1, 0x1fdd0f50, 0xc47120a3, 0xc7f4d1c7, 0x0368c033, 0x9aaa2204,
+            0x4e6cd4c3, 0x466482d2, 0x09aa9f07, 0x05d7c214, 0xa2028bd9, 0xd19c12b5, 0xb94e16de,
+            0xe883d0cb, 0x4e3c50a2,
+        ]
+    );
+}
+
+#[test]
+fn test_chacha_rng() {
+    // RFC 8439 appendix A.1, test vectors #1 and #2: all zero key, nonce and counter
+    let mut rng = ChaChaRng::new(&[0; 32], 20).unwrap();
+    let mut keystream = [0u8; 128];
+    rng.fill_bytes(&mut keystream);
+    assert_eq!(
+        keystream[..16],
+        [
+            0x76, 0xb8, 0xe0, 0xad, 0xa0, 0xf1, 0x3d, 0x90, 0x40, 0x5d, 0x6a, 0xe5, 0x53, 0x86,
+            0xbd, 0x28
+        ]
+    );
+    assert_eq!(
+        keystream[64..80],
+        [
+            0x9f, 0x07, 0xe7, 0xbe, 0x55, 0x51, 0x38, 0x7a, 0x98, 0xba, 0x97, 0x7c, 0x73, 0x2d,
+            0x08, 0x0d
+        ]
+    );
+    assert_eq!(rng.get_word_pos(), 32);
+
+    // reduced rounds, first words of the all zero key
+    let mut rng = ChaChaRng::new(&[0; 32], 8).unwrap();
+    assert_eq!(rng.next_u32(), 0x2fef003e);
+    assert_eq!(rng.next_u32(), 0xd6405f89);
+    let mut rng = ChaChaRng::new(&[0; 32], 12).unwrap();
+    assert_eq!(rng.next_u64(), 0x53f95507_6a9af49b);
+}
+
+#[test]
+fn test_chacha_rng_seek() {
+    let mut rng = ChaChaRng::new(&[7; 32], 20).unwrap();
+    let words: Vec<u32> = (0..40).map(|_| rng.next_u32()).collect();
+
+    // seeking back and forth replays the keystream
+    rng.set_word_pos(17);
+    assert_eq!(rng.next_u32(), words[17]);
+    rng.set_word_pos(3);
+    assert_eq!(rng.next_u32(), words[3]);
+    assert_eq!(rng.get_word_pos(), 4);
+
+    // a different stream gives a different keystream at the same position
+    rng.set_stream(1);
+    rng.set_word_pos(0);
+    assert_eq!(rng.get_stream(), 1);
+    assert_ne!(rng.next_u32(), words[0]);
+
+    // reseeding restarts the stream
+    rng.set_stream(0);
+    rng.reseed(&[7; 32]);
+    assert_eq!(rng.next_u32(), words[0]);
+
+    // wrapping at the end of the 64-bit block counter
+    rng.set_word_pos((1 << 68) - 1);
+    rng.next_u32();
+    assert_eq!(rng.get_word_pos(), 0);
+}
+
+#[test]
+fn test_chacha_rng_errors() {
+    assert_eq!(
+        ChaChaRng::new(&[0; 32], 10).unwrap_err(),
+        ChaChaError::InvalidRounds(10)
+    );
+}
@@ -0,0 +1,302 @@
+// CTR_DRBG
+use super::drbg::{check_inputs, check_request, Drbg, DrbgError, RESEED_INTERVAL};
+use crate::symmetric_encryption::aes::aes::Aes;
+
+const BLOCK_LEN: usize = 16;
+
+/// CTR_DRBG with AES-128, AES-192 or AES-256 (NIST SP 800-90A, section 10.2.1)
+///
+/// The state is an AES key $K$ and a 128-bit counter $V$. Output blocks are
+/// $E_K(V + 1), E_K(V + 2), \dots$ and the update function replaces $K \| V$
+/// with the next `seedlen` = keylen + 128 bits of keystream xored with the
+/// provided data.
+///
+/// With the derivation function, inputs of any length are compressed to
+/// `seedlen` bits with [`CtrDrbg::block_cipher_df`]. Without it, the entropy
+/// input must be exactly `seedlen` bits of full entropy, no nonce is used
+/// and the other inputs are at most `seedlen` bits, padded with zeros.
+#[derive(Debug, Clone)]
+pub struct CtrDrbg {
+    cipher: Aes,
+    key: Vec<u8>,
+    value: [u8; BLOCK_LEN],
+    use_df: bool,
+    reseed_counter: u64,
+    reseed_interval: u64,
+}
+
+impl CtrDrbg {
+    pub fn instantiate(
+        key_len: usize,
+        use_df: bool,
+        entropy: &[u8],
+        nonce: &[u8],
+        personalization: &[u8],
+    ) -> Result<Self, DrbgError> {
+        if ![16, 24, 32].contains(&key_len) {
+            return Err(DrbgError::InvalidKeyLength(key_len));
+        }
+
+        let key = vec![0; key_len];
+        let mut drbg = Self {
+            cipher: Aes::new(&key).unwrap(),
+            key,
+            value: [0; BLOCK_LEN],
+            use_df,
+            reseed_counter: 1,
+            reseed_interval: RESEED_INTERVAL,
+        };
+
+        let seed_material = drbg.seed_material(entropy, &[nonce, personalization])?;
+        drbg.update(&seed_material);
+
+        Ok(drbg)
+    }
+
+    pub fn key_len(&self) -> usize {
+        self.key.len()
+    }
+
+    /// keylen + blocklen, in bytes
+    pub fn seed_len(&self) -> usize {
+        self.key.len() + BLOCK_LEN
+    }
+
+    /// Derive `seedlen` bits from the entropy input and the other inputs
+    fn seed_material(&self, entropy: &[u8], inputs: &[&[u8]]) -> Result<Vec<u8>, DrbgError> {
+        if self.use_df {
+            check_inputs(entropy, self.entropy_len(), inputs)?;
+            let mut material = entropy.to_vec();
+            for input in inputs {
+                material.extend_from_slice(input);
+            }
+            return Ok(self.block_cipher_df(&material));
+        }
+
+        // the nonce is ignored, the last input is the personalization string or additional input
+        if entropy.len() != self.seed_len() {
+            return Err(DrbgError::InvalidEntropyLength(
+                entropy.len(),
+                self.seed_len(),
+            ));
+        }
+        let input = inputs.last().copied().unwrap_or_default();
+        Ok(xor_padded(entropy, self.padded(input)?))
+    }
+
+    /// Pad an input to `seedlen` bits with zeros, without the derivation function
+    fn padded<'a>(&self, input: &'a [u8]) -> Result<&'a [u8], DrbgError> {
+        if input.len() > self.seed_len() {
+            return Err(DrbgError::InputTooLong(input.len(), self.seed_len()));
+        }
+        Ok(input)
+    }
+
+    /// CTR_DRBG_Update
+    fn update(&mut self, provided_data: &[u8]) {
+        let mut temp = Vec::with_capacity(self.seed_len() + BLOCK_LEN);
+        while temp.len() < self.seed_len() {
+            increment(&mut self.value);
+            temp.extend_from_slice(&self.cipher.encrypt_block(&self.value));
+        }
+        temp.truncate(self.seed_len());
+        let temp = xor_padded(&temp, provided_data);
+
+        let key_len = self.key.len();
+        self.key.copy_from_slice(&temp[..key_len]);
+        self.value.copy_from_slice(&temp[key_len..]);
+        self.cipher = Aes::new(&self.key).unwrap();
+    }
+
+    /// Block_Cipher_df, compress `input` to `seedlen` bits
+    ///
+    /// - $S = L \| N \| input \| 0x80$ padded with zeros to a multiple of the
+    ///   block length, with $L$ the input length and $N$ the output length in
+    ///   bytes as 32-bit big-endian integers
+    /// - $temp = BCC(K, IV_0 \| S) \| BCC(K, IV_1 \| S) \| \dots$ with the fixed
+    ///   key $K = 00 01 02 \dots$ and $IV_i$ = $i$ as a 32-bit integer padded
+    ///   with zeros to a block
+    /// - the first keylen bits of $temp$ are a new key $K'$ and the next block
+    ///   $X$, the output is $E_{K'}(X), E_{K'}(E_{K'}(X)), \dots$
+    pub fn block_cipher_df(&self, input: &[u8]) -> Vec<u8> {
+        let seed_len = self.seed_len();
+
+        let mut s = Vec::with_capacity(input.len() + 2 * BLOCK_LEN);
+        s.extend_from_slice(&(input.len() as u32).to_be_bytes());
+        s.extend_from_slice(&(seed_len as u32).to_be_bytes());
+        s.extend_from_slice(input);
+        s.push(0x80);
+        s.resize(s.len().div_ceil(BLOCK_LEN) * BLOCK_LEN, 0);
+
+        let df_key: Vec<u8> = (0..self.key.len() as u8).collect();
+        let df_cipher = Aes::new(&df_key).unwrap();
+
+        let mut temp = Vec::with_capacity(seed_len + BLOCK_LEN);
+        for i in 0u32.. {
+            if temp.len() >= seed_len {
+                break;
+            }
+            let mut iv = [0; BLOCK_LEN];
+            iv[..4].copy_from_slice(&i.to_be_bytes());
+            temp.extend_from_slice(&bcc(&df_cipher, &[&iv, &s]));
+        }
+
+        let (key, x) = temp.split_at(self.key.len());
+        let cipher = Aes::new(key).unwrap();
+        let mut x: [u8; BLOCK_LEN] = x[..BLOCK_LEN].try_into().unwrap();
+
+        let mut output = Vec::with_capacity(seed_len + BLOCK_LEN);
+        while output.len() < seed_len {
+            x = cipher.encrypt_block(&x);
+            output.extend_from_slice(&x);
+        }
+        output.truncate(seed_len);
+        output
+    }
+}
+
+impl Drbg for CtrDrbg {
+    fn security_strength(&self) -> usize {
+        self.key.len() * 8
+    }
+
+    fn entropy_len(&self) -> usize {
+        if self.use_df {
+            self.key.len()
+        } else {
+            self.seed_len()
+        }
+    }
+
+    fn reseed(&mut self, entropy: &[u8], additional_input: &[u8]) -> Result<(), DrbgError> {
+        let seed_material = self.seed_material(entropy, &[additional_input])?;
+        self.update(&seed_material);
+        self.reseed_counter = 1;
+        Ok(())
+    }
+
+    fn generate(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), DrbgError> {
+        check_request(output.len(), self.reseed_counter, self.reseed_interval)?;
+
+        // the processed additional input is used for both updates
+        let additional_input = if additional_input.is_empty() {
+            Vec::new()
+        } else if self.use_df {
+            check_inputs(&[], 0, &[additional_input])?;
+            self.block_cipher_df(additional_input)
+        } else {
+            self.padded(additional_input)?.to_vec()
+        };
+        if !additional_input.is_empty() {
+            self.update(&additional_input);
+        }
+
+        for block in output.chunks_mut(BLOCK_LEN) {
+            increment(&mut self.value);
+            let keystream = self.cipher.encrypt_block(&self.value);
+            block.copy_from_slice(&keystream[..block.len()]);
+        }
+
+        self.update(&additional_input);
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    fn reseed_counter(&self) -> u64 {
+        self.reseed_counter
+    }
+
+    fn reseed_interval(&self) -> u64 {
+        self.reseed_interval
+    }
+
+    fn set_reseed_interval(&mut self, interval: u64) {
+        self.reseed_interval = interval.min(RESEED_INTERVAL);
+    }
+}
+
+/// BCC: CBC-MAC of the concatenation of `parts` with a zero IV
+fn bcc(cipher: &Aes, parts: &[&[u8]]) -> [u8; BLOCK_LEN] {
+    let data: Vec<u8> = parts.concat();
+    let mut chaining = [0; BLOCK_LEN];
+    for block in data.chunks(BLOCK_LEN) {
+        for (c, b) in chaining.iter_mut().zip(block.iter()) {
+            *c ^= b;
+        }
+        chaining = cipher.encrypt_block(&chaining);
+    }
+    chaining
+}
+
+/// Increment the counter block modulo $2^{128}$
+fn increment(value: &mut [u8; BLOCK_LEN]) {
+    *value = u128::from_be_bytes(*value).wrapping_add(1).to_be_bytes();
+}
+
+/// `a` xor `b`, with `b` padded with zeros to the length of `a`
+fn xor_padded(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter()
+        .enumerate()
+        .map(|(i, byte)| byte ^ b.get(i).unwrap_or(&0))
+        .collect()
+}
+
+#[test]
+fn test_ctr_drbg_cavp() {
+    use super::drbg::run_cavp_vector;
+    use crate::common::cavp;
+
+    let vectors = cavp::parse(include_str!("testvectors/CTR_DRBG.rsp")).unwrap();
+    assert_eq!(vectors.len(), 81);
+
+    for vector in &vectors {
+        let (key_len, use_df) = if vector.param("AES-128 use df").is_some() {
+            (16, true)
+        } else if vector.param("AES-256 use df").is_some() {
+            (32, true)
+        } else if vector.param("AES-256 no df").is_some() {
+            (32, false)
+        } else {
+            panic!("unknown section in COUNT = {}", vector.count);
+        };
+
+        let mut drbg = CtrDrbg::instantiate(
+            key_len,
+            use_df,
+            &vector.bytes("EntropyInput").unwrap(),
+            &vector.bytes("Nonce").unwrap(),
+            &vector.bytes("PersonalizationString").unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            run_cavp_vector(&mut drbg, vector),
+            vector.bytes("ReturnedBits").unwrap(),
+            "COUNT = {}",
+            vector.count
+        );
+    }
+}
+
+#[test]
+fn test_ctr_drbg_errors() {
+    assert_eq!(
+        CtrDrbg::instantiate(20, true, &[0; 32], &[0; 16], b"").unwrap_err(),
+        DrbgError::InvalidKeyLength(20)
+    );
+    assert_eq!(
+        CtrDrbg::instantiate(32, false, &[0; 32], &[], b"").unwrap_err(),
+        DrbgError::InvalidEntropyLength(32, 48)
+    );
+    assert_eq!(
+        CtrDrbg::instantiate(16, true, &[0; 8], &[0; 8], b"").unwrap_err(),
+        DrbgError::EntropyTooShort(8, 16)
+    );
+
+    let mut drbg = CtrDrbg::instantiate(16, false, &[0; 32], &[], b"").unwrap();
+    assert_eq!(drbg.security_strength(), 128);
+    assert_eq!(
+        drbg.generate(&mut [0; 16], &[0; 33]),
+        Err(DrbgError::InputTooLong(33, 32))
+    );
+}
@@ -0,0 +1,260 @@
+// Common interface of the SP 800-90A DRBG mechanisms
+use std::{fs::File, io::Read};
+
+use thiserror::Error;
+
+use super::hmac_drbg::HmacDrbg;
+use crate::rng::traits::Rng;
+
+/// Largest request of a single generate call, $2^{19}$ bits
+pub const MAX_BYTES_PER_REQUEST: usize = 1 << 16;
+
+/// Number of generate calls allowed between two reseeds, $2^{48}$
+pub const RESEED_INTERVAL: u64 = 1 << 48;
+
+/// Largest personalization string or additional input, $2^{35}$ bits
+pub const MAX_INPUT_LEN: u64 = 1 << 32;
+
+/// Deterministic random bit generator mechanism (NIST SP 800-90A)
+///
+/// A mechanism is instantiated from entropy input, a nonce and an optional
+/// personalization string. Its internal state then only changes through:
+/// - `reseed`: mix fresh entropy into the state and reset the reseed counter
+/// - `generate`: produce output and update the state (backtracking resistance)
+///
+/// Both accept additional input, which is mixed into the state but need not
+/// be secret. The mechanisms never read an entropy source themselves, see
+/// [`DrbgRng`] for prediction resistance and automatic reseeding.
+pub trait Drbg {
+    /// Security strength in bits
+    fn security_strength(&self) -> usize;
+
+    /// Length in bytes of the entropy input expected by `reseed`
+    fn entropy_len(&self) -> usize {
+        self.security_strength() / 8
+    }
+
+    fn reseed(&mut self, entropy: &[u8], additional_input: &[u8]) -> Result<(), DrbgError>;
+
+    /// Fill `output`, fails with [`DrbgError::ReseedRequired`] once the reseed
+    /// counter exceeds the reseed interval
+    fn generate(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), DrbgError>;
+
+    /// Number of generate calls since the last (re)seed, plus one
+    fn reseed_counter(&self) -> u64;
+
+    fn reseed_interval(&self) -> u64;
+
+    /// Lower the reseed interval, at most [`RESEED_INTERVAL`]
+    fn set_reseed_interval(&mut self, interval: u64);
+}
+
+/// Source of entropy input for reseeding
+pub trait EntropySource {
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), DrbgError>;
+}
+
+/// Entropy from the operating system (`/dev/urandom`)
+#[derive(Debug, Default)]
+pub struct OsEntropy;
+
+impl EntropySource for OsEntropy {
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), DrbgError> {
+        File::open("/dev/urandom")
+            .and_then(|mut file| file.read_exact(dest))
+            .map_err(|e| DrbgError::EntropySource(e.to_string()))
+    }
+}
+
+/// Predetermined entropy inputs returned in order, used to replay test vectors
+impl EntropySource for std::collections::VecDeque<Vec<u8>> {
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), DrbgError> {
+        match self.pop_front() {
+            Some(entropy) if entropy.len() == dest.len() => {
+                dest.copy_from_slice(&entropy);
+                Ok(())
+            }
+            Some(entropy) => Err(DrbgError::EntropySource(format!(
+                "expected {} bytes, got {}",
+                dest.len(),
+                entropy.len()
+            ))),
+            None => Err(DrbgError::EntropySource("no entropy left".to_string())),
+        }
+    }
+}
+
+/// A DRBG mechanism together with its entropy source
+///
+/// - With prediction resistance, every request reseeds from the source first,
+///   so a compromise of the state does not reveal future outputs.
+/// - Otherwise the mechanism is reseeded when its reseed interval runs out.
+///
+/// Requests larger than [`MAX_BYTES_PER_REQUEST`] are split.
+#[derive(Debug)]
+pub struct DrbgRng<D: Drbg, E: EntropySource> {
+    drbg: D,
+    source: E,
+    prediction_resistance: bool,
+}
+
+impl<D: Drbg, E: EntropySource> DrbgRng<D, E> {
+    pub fn new(drbg: D, source: E, prediction_resistance: bool) -> Self {
+        Self {
+            drbg,
+            source,
+            prediction_resistance,
+        }
+    }
+
+    pub fn drbg(&self) -> &D {
+        &self.drbg
+    }
+
+    pub fn reseed(&mut self, additional_input: &[u8]) -> Result<(), DrbgError> {
+        let mut entropy = vec![0; self.drbg.entropy_len()];
+        self.source.fill_entropy(&mut entropy)?;
+        self.drbg.reseed(&entropy, additional_input)
+    }
+
+    pub fn generate(
+        &mut self,
+        output: &mut [u8],
+        additional_input: &[u8],
+    ) -> Result<(), DrbgError> {
+        for chunk in output.chunks_mut(MAX_BYTES_PER_REQUEST) {
+            // the additional input goes into the reseed (SP 800-90A 9.3.1)
+            if self.prediction_resistance {
+                self.reseed(additional_input)?;
+                self.drbg.generate(chunk, &[])?;
+                continue;
+            }
+
+            match self.drbg.generate(chunk, additional_input) {
+                Err(DrbgError::ReseedRequired) => {
+                    self.reseed(additional_input)?;
+                    self.drbg.generate(chunk, &[])?;
+                }
+                result => result?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DrbgRng<HmacDrbg, OsEntropy> {
+    /// HMAC_DRBG seeded by the operating system, the recommended generator for key material
+    pub fn from_os_entropy(personalization: &[u8]) -> Result<Self, DrbgError> {
+        let mut source = OsEntropy;
+        let mut entropy = [0; 32];
+        let mut nonce = [0; 16];
+        source.fill_entropy(&mut entropy)?;
+        source.fill_entropy(&mut nonce)?;
+
+        let drbg = HmacDrbg::instantiate(&entropy, &nonce, personalization)?;
+        Ok(Self::new(drbg, source, false))
+    }
+}
+
+impl<D: Drbg, E: EntropySource> Rng for DrbgRng<D, E> {
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_be_bytes(bytes)
+    }
+
+    /// Panics if the entropy source fails
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.generate(dest, &[]).expect("DRBG generate failed");
+    }
+}
+
+/// Check the inputs shared by every mechanism
+pub(crate) fn check_inputs(
+    entropy: &[u8],
+    min_entropy_len: usize,
+    inputs: &[&[u8]],
+) -> Result<(), DrbgError> {
+    if entropy.len() < min_entropy_len {
+        return Err(DrbgError::EntropyTooShort(entropy.len(), min_entropy_len));
+    }
+    if let Some(input) = inputs
+        .iter()
+        .find(|input| input.len() as u64 > MAX_INPUT_LEN)
+    {
+        return Err(DrbgError::InputTooLong(input.len(), MAX_INPUT_LEN as usize));
+    }
+    Ok(())
+}
+
+/// Check a generate request against the limits of SP 800-90A
+pub(crate) fn check_request(
+    output_len: usize,
+    reseed_counter: u64,
+    reseed_interval: u64,
+) -> Result<(), DrbgError> {
+    if output_len > MAX_BYTES_PER_REQUEST {
+        return Err(DrbgError::RequestTooLarge(
+            output_len,
+            MAX_BYTES_PER_REQUEST,
+        ));
+    }
+    if reseed_counter > reseed_interval {
+        return Err(DrbgError::ReseedRequired);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum DrbgError {
+    #[error("Entropy input of {0} bytes is shorter than the {1} bytes required")]
+    EntropyTooShort(usize, usize),
+
+    #[error("Invalid entropy input length {0}: expected {1} bytes")]
+    InvalidEntropyLength(usize, usize),
+
+    #[error("Input of {0} bytes is longer than the maximum of {1} bytes")]
+    InputTooLong(usize, usize),
+
+    #[error("Request of {0} bytes exceeds the maximum of {1} bytes")]
+    RequestTooLarge(usize, usize),
+
+    #[error("Reseed required: the reseed counter exceeds the reseed interval")]
+    ReseedRequired,
+
+    #[error("Invalid key length {0}: expected 16, 24 or 32 bytes")]
+    InvalidKeyLength(usize),
+
+    #[error("Entropy source failure: {0}")]
+    EntropySource(String),
+}
+
+/// Run a CAVP test case on an instantiated mechanism and return the second output
+///
+/// Procedure of the CAVP DRBG validation: optionally reseed, then generate
+/// twice, reseeding before each call when prediction resistance is used.
+#[cfg(test)]
+pub(crate) fn run_cavp_vector<D: Drbg>(
+    drbg: &mut D,
+    vector: &crate::common::cavp::CavpVector,
+) -> Vec<u8> {
+    let additional_inputs = vector.all_bytes("AdditionalInput").unwrap();
+    let entropy_pr = vector.all_bytes("EntropyInputPR").unwrap();
+    let mut output = vec![0; vector.bytes("ReturnedBits").unwrap().len()];
+
+    if let Ok(entropy) = vector.bytes("EntropyInputReseed") {
+        let additional_input = vector.bytes("AdditionalInputReseed").unwrap();
+        drbg.reseed(&entropy, &additional_input).unwrap();
+    }
+
+    for (i, additional_input) in additional_inputs.iter().enumerate() {
+        if let Some(entropy) = entropy_pr.get(i) {
+            drbg.reseed(entropy, additional_input).unwrap();
+            drbg.generate(&mut output, &[]).unwrap();
+        } else {
+            drbg.generate(&mut output, additional_input).unwrap();
+        }
+    }
+
+    output
+}
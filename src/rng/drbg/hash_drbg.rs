@@ -0,0 +1,182 @@
+// Hash_DRBG
+use super::drbg::{check_inputs, check_request, Drbg, DrbgError, RESEED_INTERVAL};
+use crate::hash::sha256::sha256::Sha256;
+
+/// Length of $V$ and $C$ for SHA-256, 440 bits
+const SEED_LEN: usize = 55;
+
+/// Hash_DRBG with SHA-256 (NIST SP 800-90A, section 10.1.1)
+///
+/// The state is a value $V$ and a constant $C$ of `seedlen` = 440 bits, both
+/// derived from the seed material with [`hash_df`]. A request hashes the
+/// counter $V, V + 1, V + 2, \dots$ and then updates
+///
+/// $$V = (V + H(0x03 \| V) + C + reseed\_counter) \bmod 2^{seedlen}$$
+#[derive(Debug, Clone)]
+pub struct HashDrbg {
+    value: [u8; SEED_LEN],
+    constant: [u8; SEED_LEN],
+    reseed_counter: u64,
+    reseed_interval: u64,
+}
+
+impl HashDrbg {
+    pub const SECURITY_STRENGTH: usize = 256;
+
+    /// Instantiate with at least 32 bytes of entropy and a nonce (usually 16 bytes)
+    pub fn instantiate(
+        entropy: &[u8],
+        nonce: &[u8],
+        personalization: &[u8],
+    ) -> Result<Self, DrbgError> {
+        check_inputs(entropy, Self::SECURITY_STRENGTH / 8, &[personalization])?;
+
+        let value = hash_df(&[entropy, nonce, personalization]);
+        Ok(Self {
+            value,
+            constant: hash_df(&[&[0x00], &value]),
+            reseed_counter: 1,
+            reseed_interval: RESEED_INTERVAL,
+        })
+    }
+
+    /// Hash $V, V + 1, \dots$ until `output` is full
+    fn hashgen(&self, output: &mut [u8]) {
+        let mut data = self.value;
+        for block in output.chunks_mut(Sha256::OUTPUT_SIZE) {
+            let digest = Sha256::digest(&data);
+            block.copy_from_slice(&digest[..block.len()]);
+            add_mod(&mut data, &[1]);
+        }
+    }
+}
+
+impl Drbg for HashDrbg {
+    fn security_strength(&self) -> usize {
+        Self::SECURITY_STRENGTH
+    }
+
+    fn reseed(&mut self, entropy: &[u8], additional_input: &[u8]) -> Result<(), DrbgError> {
+        check_inputs(entropy, self.entropy_len(), &[additional_input])?;
+
+        self.value = hash_df(&[&[0x01], &self.value, entropy, additional_input]);
+        self.constant = hash_df(&[&[0x00], &self.value]);
+        self.reseed_counter = 1;
+        Ok(())
+    }
+
+    fn generate(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), DrbgError> {
+        check_request(output.len(), self.reseed_counter, self.reseed_interval)?;
+        check_inputs(&[], 0, &[additional_input])?;
+
+        if !additional_input.is_empty() {
+            let w = hash(&[&[0x02], &self.value, additional_input]);
+            add_mod(&mut self.value, &w);
+        }
+
+        self.hashgen(output);
+
+        let h = hash(&[&[0x03], &self.value]);
+        let constant = self.constant;
+        add_mod(&mut self.value, &h);
+        add_mod(&mut self.value, &constant);
+        add_mod(&mut self.value, &self.reseed_counter.to_be_bytes());
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    fn reseed_counter(&self) -> u64 {
+        self.reseed_counter
+    }
+
+    fn reseed_interval(&self) -> u64 {
+        self.reseed_interval
+    }
+
+    fn set_reseed_interval(&mut self, interval: u64) {
+        self.reseed_interval = interval.min(RESEED_INTERVAL);
+    }
+}
+
+/// Hash of the concatenation of `parts`
+fn hash(parts: &[&[u8]]) -> [u8; 32] {
+    let mut sha = Sha256::new();
+    for part in parts {
+        sha.update(part);
+    }
+    sha.finalize()
+}
+
+/// Hash derivation function, stretches the concatenation of `input` to `seedlen` bits
+///
+/// $$temp = H(1 \| seedlen \| input) \| H(2 \| seedlen \| input) \| \dots$$
+///
+/// with a one byte counter and `seedlen` as a 32-bit big-endian integer.
+pub fn hash_df(input: &[&[u8]]) -> [u8; SEED_LEN] {
+    let mut output = [0; SEED_LEN];
+    let bits = (SEED_LEN as u32 * 8).to_be_bytes();
+
+    for (counter, block) in (1u8..).zip(output.chunks_mut(Sha256::OUTPUT_SIZE)) {
+        let mut sha = Sha256::new();
+        sha.update(&[counter]);
+        sha.update(&bits);
+        for part in input {
+            sha.update(part);
+        }
+        block.copy_from_slice(&sha.finalize()[..block.len()]);
+    }
+
+    output
+}
+
+/// `value = (value + x) mod 2^seedlen`, both big-endian
+fn add_mod(value: &mut [u8; SEED_LEN], x: &[u8]) {
+    let mut carry = 0u16;
+    let mut x = x.iter().rev();
+    for byte in value.iter_mut().rev() {
+        let sum = *byte as u16 + *x.next().unwrap_or(&0) as u16 + carry;
+        *byte = sum as u8;
+        carry = sum >> 8;
+    }
+}
+
+#[test]
+fn test_hash_drbg_cavp() {
+    use super::drbg::run_cavp_vector;
+    use crate::common::cavp;
+
+    let vectors = cavp::parse(include_str!("testvectors/Hash_DRBG.rsp")).unwrap();
+    assert_eq!(vectors.len(), 3);
+
+    for vector in &vectors {
+        let mut drbg = HashDrbg::instantiate(
+            &vector.bytes("EntropyInput").unwrap(),
+            &vector.bytes("Nonce").unwrap(),
+            &vector.bytes("PersonalizationString").unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            run_cavp_vector(&mut drbg, vector),
+            vector.bytes("ReturnedBits").unwrap(),
+            "COUNT = {}",
+            vector.count
+        );
+        // with prediction resistance, the last request follows a reseed
+        let prediction_resistance = !vector.all_bytes("EntropyInputPR").unwrap().is_empty();
+        let expected = if prediction_resistance { 2 } else { 3 };
+        assert_eq!(drbg.reseed_counter(), expected, "COUNT = {}", vector.count);
+    }
+}
+
+#[test]
+fn test_add_mod() {
+    let mut value = [0xff; SEED_LEN];
+    add_mod(&mut value, &[1]);
+    assert_eq!(value, [0; SEED_LEN]);
+
+    let mut value = [0; SEED_LEN];
+    value[SEED_LEN - 1] = 0xff;
+    add_mod(&mut value, &[0x01, 0x01]);
+    assert_eq!(value[SEED_LEN - 3..], [0x00, 0x02, 0x00]);
+}
@@ -0,0 +1,199 @@
+// HMAC_DRBG
+use super::drbg::{check_inputs, check_request, Drbg, DrbgError, RESEED_INTERVAL};
+use crate::hash::hmac::hmac::HmacSha256;
+
+/// HMAC_DRBG with HMAC-SHA-256 (NIST SP 800-90A, section 10.1.2)
+///
+/// The state is a key $K$ and a value $V$ of 32 bytes each. The update
+/// function mixes provided data into both:
+///
+/// $$
+/// \begin{align}
+/// &K = HMAC(K, V \| 0x00 \| data), \quad V = HMAC(K, V) \newline
+/// &K = HMAC(K, V \| 0x01 \| data), \quad V = HMAC(K, V) \quad \text{(only if data is not empty)}
+/// \end{align}
+/// $$
+///
+/// Output blocks are $V = HMAC(K, V)$, and the state is updated with the
+/// additional input after every request.
+#[derive(Debug, Clone)]
+pub struct HmacDrbg {
+    key: [u8; 32],
+    value: [u8; 32],
+    reseed_counter: u64,
+    reseed_interval: u64,
+}
+
+impl HmacDrbg {
+    pub const SECURITY_STRENGTH: usize = 256;
+
+    /// Instantiate with at least 32 bytes of entropy and a nonce (usually 16 bytes)
+    pub fn instantiate(
+        entropy: &[u8],
+        nonce: &[u8],
+        personalization: &[u8],
+    ) -> Result<Self, DrbgError> {
+        check_inputs(entropy, Self::SECURITY_STRENGTH / 8, &[personalization])?;
+
+        let mut drbg = Self {
+            key: [0x00; 32],
+            value: [0x01; 32],
+            reseed_counter: 1,
+            reseed_interval: RESEED_INTERVAL,
+        };
+        drbg.update(&[entropy, nonce, personalization]);
+
+        Ok(drbg)
+    }
+
+    /// HMAC_DRBG_Update, `provided_data` is the concatenation of the slices
+    fn update(&mut self, provided_data: &[&[u8]]) {
+        let is_empty = provided_data.iter().all(|data| data.is_empty());
+
+        for separator in [0x00, 0x01] {
+            let mut hmac = HmacSha256::new(&self.key);
+            hmac.update(&self.value);
+            hmac.update(&[separator]);
+            for data in provided_data {
+                hmac.update(data);
+            }
+            self.key = hmac.finalize();
+            self.value = HmacSha256::mac(&self.key, &self.value);
+
+            if is_empty {
+                break;
+            }
+        }
+    }
+}
+
+impl Drbg for HmacDrbg {
+    fn security_strength(&self) -> usize {
+        Self::SECURITY_STRENGTH
+    }
+
+    fn reseed(&mut self, entropy: &[u8], additional_input: &[u8]) -> Result<(), DrbgError> {
+        check_inputs(entropy, self.entropy_len(), &[additional_input])?;
+
+        self.update(&[entropy, additional_input]);
+        self.reseed_counter = 1;
+        Ok(())
+    }
+
+    fn generate(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), DrbgError> {
+        check_request(output.len(), self.reseed_counter, self.reseed_interval)?;
+        check_inputs(&[], 0, &[additional_input])?;
+
+        if !additional_input.is_empty() {
+            self.update(&[additional_input]);
+        }
+
+        for block in output.chunks_mut(32) {
+            self.value = HmacSha256::mac(&self.key, &self.value);
+            block.copy_from_slice(&self.value[..block.len()]);
+        }
+
+        self.update(&[additional_input]);
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    fn reseed_counter(&self) -> u64 {
+        self.reseed_counter
+    }
+
+    fn reseed_interval(&self) -> u64 {
+        self.reseed_interval
+    }
+
+    fn set_reseed_interval(&mut self, interval: u64) {
+        self.reseed_interval = interval.min(RESEED_INTERVAL);
+    }
+}
+
+#[test]
+fn test_hmac_drbg_cavp() {
+    use super::drbg::run_cavp_vector;
+    use crate::common::cavp;
+
+    let vectors = cavp::parse(include_str!("testvectors/HMAC_DRBG.rsp")).unwrap();
+    assert_eq!(vectors.len(), 180);
+
+    for vector in &vectors {
+        let mut drbg = HmacDrbg::instantiate(
+            &vector.bytes("EntropyInput").unwrap(),
+            &vector.bytes("Nonce").unwrap(),
+            &vector.bytes("PersonalizationString").unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            run_cavp_vector(&mut drbg, vector),
+            vector.bytes("ReturnedBits").unwrap(),
+            "COUNT = {}",
+            vector.count
+        );
+    }
+}
+
+#[test]
+fn test_hmac_drbg_prediction_resistance() {
+    use super::drbg::DrbgRng;
+    use crate::common::cavp;
+    use std::collections::VecDeque;
+
+    // the generator pulls EntropyInputPR from the source before every request
+    let vectors = cavp::parse(include_str!("testvectors/HMAC_DRBG.rsp")).unwrap();
+    for vector in vectors
+        .iter()
+        .filter(|v| v.param("PredictionResistance") == Some("True"))
+    {
+        let drbg = HmacDrbg::instantiate(
+            &vector.bytes("EntropyInput").unwrap(),
+            &vector.bytes("Nonce").unwrap(),
+            &vector.bytes("PersonalizationString").unwrap(),
+        )
+        .unwrap();
+        let source: VecDeque<Vec<u8>> = vector.all_bytes("EntropyInputPR").unwrap().into();
+        let mut rng = DrbgRng::new(drbg, source, true);
+
+        let mut output = vec![0; vector.bytes("ReturnedBits").unwrap().len()];
+        for additional_input in vector.all_bytes("AdditionalInput").unwrap() {
+            rng.generate(&mut output, &additional_input).unwrap();
+        }
+        assert_eq!(output, vector.bytes("ReturnedBits").unwrap());
+        assert_eq!(rng.drbg().reseed_counter(), 2);
+    }
+}
+
+#[test]
+fn test_hmac_drbg_reseed_interval() {
+    use super::drbg::DrbgRng;
+    use std::collections::VecDeque;
+
+    let mut drbg = HmacDrbg::instantiate(&[1; 32], &[2; 16], b"").unwrap();
+    drbg.set_reseed_interval(2);
+    let mut output = [0; 16];
+    drbg.generate(&mut output, &[]).unwrap();
+    drbg.generate(&mut output, &[]).unwrap();
+    assert_eq!(drbg.reseed_counter(), 3);
+    assert_eq!(
+        drbg.generate(&mut output, &[]),
+        Err(DrbgError::ReseedRequired)
+    );
+
+    // the wrapper reseeds on its own
+    let mut rng = DrbgRng::new(drbg, VecDeque::from(vec![vec![3; 32]]), false);
+    rng.generate(&mut output, &[]).unwrap();
+    assert_eq!(rng.drbg().reseed_counter(), 2);
+
+    assert_eq!(
+        HmacDrbg::instantiate(&[1; 16], &[2; 16], b"").unwrap_err(),
+        DrbgError::EntropyTooShort(16, 32)
+    );
+    let mut drbg = HmacDrbg::instantiate(&[1; 32], &[2; 16], b"").unwrap();
+    assert_eq!(
+        drbg.generate(&mut vec![0; (1 << 16) + 1], &[]),
+        Err(DrbgError::RequestTooLarge((1 << 16) + 1, 1 << 16))
+    );
+}
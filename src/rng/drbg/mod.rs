@@ -0,0 +1,5 @@
+pub mod ctr_drbg;
+#[allow(clippy::module_inception)]
+pub mod drbg;
+pub mod hash_drbg;
+pub mod hmac_drbg;
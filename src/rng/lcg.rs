@@ -1,11 +1,15 @@
 // Linear Congruential Generator
 use std::num::Wrapping;
 
+use super::traits::Rng;
+
 pub struct LCG {
     m: u64,
     a: u64,
     b: u64,
     s: Wrapping<u64>,
+    buffer: u64,
+    buffered: u32,
 }
 
 impl LCG {
@@ -15,13 +19,48 @@ impl LCG {
             a,
             b,
             s: Wrapping(seed),
+            buffer: 0,
+            buffered: 0,
         }
     }
 
+    pub fn multiplier(&self) -> u64 {
+        self.a
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.m
+    }
+
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> u64 {
         self.s = (self.s * Wrapping(self.a) + Wrapping(self.b)) % Wrapping(self.m);
         self.s.0
     }
+
+    /// Number of bits used from each output, $\lfloor \log_2 m \rfloor$, at least 1
+    pub fn bits_per_step(&self) -> u32 {
+        self.m.ilog2().max(1)
+    }
+
+    /// Next single bit, the `bits_per_step` least significant bits of each
+    /// output are returned msb-first
+    pub fn next_bit(&mut self) -> u8 {
+        if self.buffered == 0 {
+            self.buffer = self.next();
+            self.buffered = self.bits_per_step();
+        }
+        self.buffered -= 1;
+        ((self.buffer >> self.buffered) & 1) as u8
+    }
+}
+
+/// The outputs are below $m$: their bits are packed into the 64-bit words
+/// with [`LCG::next_bit`], as in [`super::bbs::BlumBlumShub`]
+impl Rng for LCG {
+    fn next_u64(&mut self) -> u64 {
+        (0..64).fold(0, |acc, _| (acc << 1) | self.next_bit() as u64)
+    }
 }
 
 impl Iterator for LCG {
@@ -55,4 +94,12 @@ fn test_lcg() {
     let random_number = lcg.next();
 
     assert_eq!(random_number, 1229667250);
+
+    // 16 bits of each output: 18685, 23320, 67645 mod 2^16 = 2109, 58520
+    let mut lcg = LCG::new(312, 100000, 70495, 24245);
+    assert_eq!(lcg.bits_per_step(), 16);
+    assert_eq!(
+        lcg.next_u64(),
+        18685 << 48 | 23320 << 32 | 2109 << 16 | 58520
+    );
 }
@@ -1 +1,11 @@
-pub mod lcg;
\ No newline at end of file
+pub mod bbs;
+pub mod blum_micali;
+pub mod chacha;
+pub mod drbg;
+pub mod lcg;
+pub mod pcg;
+pub mod splitmix64;
+pub mod tests;
+pub mod traits;
+pub mod xorshift;
+pub mod xoshiro;
@@ -0,0 +1,115 @@
+// Permuted Congruential Generator
+use super::traits::Rng;
+
+/// PCG32 (PCG-XSH-RR with 64-bit state and 32-bit output)
+///
+/// The state is a 64-bit LCG $s_{i+1} = a \cdot s_i + c \bmod 2^{64}$ whose
+/// weak low bits are hidden by an output permutation of the old state:
+/// xorshift the high bits down ($x = ((s \gg 18) \oplus s) \gg 27$), then
+/// rotate the 32-bit result right by the top 5 bits of the state.
+///
+/// The increment $c$ must be odd and selects one of $2^{63}$ streams. Since
+/// the state is an LCG, jumping ahead by $\delta$ steps takes $O(\log \delta)$
+/// with the composition of affine maps.
+///
+/// # See also
+/// - O'Neill, Melissa E. "PCG: A Family of Simple Fast Space-Efficient Statistically Good Algorithms for Random Number Generation." 2014.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pcg32 {
+    state: u64,
+    increment: u64,
+}
+
+impl Pcg32 {
+    pub const MULTIPLIER: u64 = 6364136223846793005;
+
+    /// Seed the state and select the stream, the reference `pcg32_srandom_r`
+    pub fn new(seed: u64, stream: u64) -> Self {
+        let mut rng = Self {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+        rng
+    }
+
+    /// Stream selected by the odd increment
+    pub fn stream(&self) -> u64 {
+        self.increment >> 1
+    }
+
+    /// Skip `delta` outputs
+    ///
+    /// $\delta$ steps of $s \mapsto a s + c$ are $s \mapsto A s + C$ with
+    /// $A = a^\delta$ and $C = c (a^{\delta-1} + \dots + a + 1)$, both computed
+    /// by squaring the map.
+    pub fn advance(&mut self, mut delta: u64) {
+        let (mut acc_mult, mut acc_plus) = (1u64, 0u64);
+        let (mut cur_mult, mut cur_plus) = (Self::MULTIPLIER, self.increment);
+
+        while delta > 0 {
+            if delta & 1 == 1 {
+                acc_mult = acc_mult.wrapping_mul(cur_mult);
+                acc_plus = acc_plus.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+            }
+            cur_plus = cur_mult.wrapping_add(1).wrapping_mul(cur_plus);
+            cur_mult = cur_mult.wrapping_mul(cur_mult);
+            delta >>= 1;
+        }
+
+        self.state = acc_mult.wrapping_mul(self.state).wrapping_add(acc_plus);
+    }
+
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(Self::MULTIPLIER)
+            .wrapping_add(self.increment);
+    }
+}
+
+impl Rng for Pcg32 {
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rotation = (old >> 59) as u32;
+        xorshifted.rotate_right(rotation)
+    }
+
+    /// Two consecutive outputs, the first one in the low half
+    fn next_u64(&mut self) -> u64 {
+        let low = self.next_u32() as u64;
+        let high = self.next_u32() as u64;
+        (high << 32) | low
+    }
+}
+
+#[test]
+fn test_pcg32() {
+    // pcg32-demo from the reference implementation
+    let mut rng = Pcg32::new(42, 54);
+    let outputs: Vec<u32> = (0..6).map(|_| rng.next_u32()).collect();
+    assert_eq!(
+        outputs,
+        vec![0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e]
+    );
+    assert_eq!(rng.stream(), 54);
+
+    let mut rng = Pcg32::new(42, 54);
+    rng.advance(4);
+    assert_eq!(rng.next_u32(), outputs[4]);
+
+    // advancing by 2^64 - 1 then 1 is the identity
+    let mut rng = Pcg32::new(42, 54);
+    rng.advance(u64::MAX);
+    rng.advance(1);
+    assert_eq!(rng.next_u32(), outputs[0]);
+
+    // another stream gives another sequence
+    let mut rng = Pcg32::new(42, 55);
+    assert_ne!(rng.next_u32(), outputs[0]);
+}
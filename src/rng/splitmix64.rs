@@ -0,0 +1,80 @@
+// SplitMix64
+use super::traits::Rng;
+
+/// SplitMix64 generator
+///
+/// A Weyl sequence $s_{i+1} = s_i + \gamma \bmod 2^{64}$, with the golden
+/// ratio constant $\gamma$ = `0x9e3779b97f4a7c15`, passed through a bijective
+/// mixing function (variant 13 of Stafford's MurmurHash3 finalizer):
+///
+/// ```text
+/// z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9
+/// z = (z ^ (z >> 27)) * 0x94d049bb133111eb
+/// z = z ^ (z >> 31)
+/// ```
+///
+/// The period is $2^{64}$ and any position can be reached in $O(1)$. Mostly
+/// used to expand a 64-bit seed into the state of other generators.
+///
+/// # See also
+/// - Steele, Lea and Flood. "Fast Splittable Pseudorandom Number Generators." OOPSLA 2014.
+#[derive(Debug, Clone)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Skip `delta` outputs
+    pub fn advance(&mut self, delta: u64) {
+        self.state = self.state.wrapping_add(delta.wrapping_mul(Self::GAMMA));
+    }
+
+    /// New generator seeded from the next output of this one
+    pub fn split(&mut self) -> Self {
+        Self::new(self.next_u64())
+    }
+
+    pub fn mix(z: u64) -> u64 {
+        let z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        let z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl Rng for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(Self::GAMMA);
+        Self::mix(self.state)
+    }
+}
+
+#[test]
+fn test_splitmix64() {
+    let mut rng = SplitMix64::new(1234567);
+    let outputs: Vec<u64> = (0..5).map(|_| rng.next_u64()).collect();
+    assert_eq!(
+        outputs,
+        vec![
+            6457827717110365317,
+            3203168211198807973,
+            9817491932198370423,
+            4593380528125082431,
+            16408922859458223821,
+        ]
+    );
+
+    let mut rng = SplitMix64::new(1234567);
+    rng.advance(3);
+    assert_eq!(rng.next_u64(), outputs[3]);
+
+    let mut rng = SplitMix64::new(1234567);
+    let mut child = rng.split();
+    assert_eq!(child.next_u64(), SplitMix64::new(outputs[0]).next_u64());
+    assert_eq!(rng.next_u64(), outputs[1]);
+}
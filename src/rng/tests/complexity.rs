@@ -0,0 +1,151 @@
+// Linear complexity test (NIST SP 800-22, section 2.10)
+use super::{
+    report::{require_length, TestError, TestResult},
+    special_functions::igamc,
+};
+
+/// Linear complexity test
+///
+/// The linear complexity $L_i$ (length of the shortest LFSR generating the
+/// block) of every block of $M$ bits is compared with its mean
+///
+/// $$\mu = \frac{M}{2} + \frac{9 + (-1)^{M+1}}{36} - \frac{M/3 + 2/9}{2^M}$$
+///
+/// through $T_i = (-1)^M (L_i - \mu) + 2/9$, sorted into 7 classes and
+/// checked with a $\chi^2$ test with 6 degrees of freedom. At least 200
+/// blocks are required.
+pub fn linear_complexity(bits: &[u8], m: usize) -> Result<TestResult, TestError> {
+    if !(500..=5000).contains(&m) {
+        return Err(TestError::InvalidParameter("Linear complexity", m));
+    }
+    require_length("Linear complexity", bits, 200 * m)?;
+
+    const PROBABILITIES: [f64; 7] = [0.01047, 0.03125, 0.125, 0.5, 0.25, 0.0625, 0.020833];
+
+    let m_f = m as f64;
+    let sign = if m.is_multiple_of(2) { 1.0 } else { -1.0 };
+    let mu = m_f / 2.0 + (9.0 - sign) / 36.0 - (m_f / 3.0 + 2.0 / 9.0) / 2f64.powf(m_f);
+
+    let mut frequencies = [0usize; 7];
+    for block in bits.chunks_exact(m) {
+        let t = sign * (berlekamp_massey(block) as f64 - mu) + 2.0 / 9.0;
+        let class = match t {
+            t if t <= -2.5 => 0,
+            t if t <= -1.5 => 1,
+            t if t <= -0.5 => 2,
+            t if t <= 0.5 => 3,
+            t if t <= 1.5 => 4,
+            t if t <= 2.5 => 5,
+            _ => 6,
+        };
+        frequencies[class] += 1;
+    }
+
+    let blocks = (bits.len() / m) as f64;
+    let chi_squared: f64 = frequencies
+        .iter()
+        .zip(PROBABILITIES)
+        .map(|(&v, p)| (v as f64 - blocks * p).powi(2) / (blocks * p))
+        .sum();
+
+    Ok(TestResult::new(
+        "Linear complexity",
+        vec![igamc(3.0, chi_squared / 2.0)],
+    ))
+}
+
+/// Linear complexity of a bit sequence with the Berlekamp-Massey algorithm
+///
+/// Maintains the connection polynomial $C(x)$ of the shortest LFSR that
+/// generates the bits seen so far. When the next bit is mispredicted
+/// (discrepancy $d = 1$), $C$ is corrected with the last polynomial $B$
+/// that was replaced: $C(x) \mathrel{+}= x^{i - m} B(x)$.
+///
+/// The polynomials and the window of the last bits are packed 64 bits per
+/// word, so the discrepancy is the parity of $C \land window$.
+pub fn berlekamp_massey(bits: &[u8]) -> usize {
+    let n = bits.len();
+    let words = n / 64 + 1;
+    let mut c = vec![0u64; words];
+    c[0] = 1;
+    let mut b = c.clone();
+    // bit j is s_{i-j}
+    let mut window = vec![0u64; words];
+
+    let mut l = 0;
+    // index of the last length change
+    let mut m: isize = -1;
+
+    for (i, &bit) in bits.iter().enumerate() {
+        shift_in(&mut window, bit);
+        let discrepancy = c
+            .iter()
+            .zip(window.iter())
+            .map(|(c, w)| (c & w).count_ones())
+            .sum::<u32>()
+            & 1;
+        if discrepancy == 0 {
+            continue;
+        }
+
+        let previous = (2 * l <= i).then(|| c.clone());
+        xor_shifted(&mut c, &b, (i as isize - m) as usize);
+        if let Some(previous) = previous {
+            b = previous;
+            l = i + 1 - l;
+            m = i as isize;
+        }
+    }
+
+    l
+}
+
+/// Shift the packed bits left by one and insert `bit` at position 0
+fn shift_in(words: &mut [u64], bit: u8) {
+    let mut carry = bit as u64;
+    for word in words.iter_mut() {
+        let next_carry = *word >> 63;
+        *word = (*word << 1) | carry;
+        carry = next_carry;
+    }
+}
+
+/// `c ^= b << shift` on packed bits, truncated to the length of `c`
+fn xor_shifted(c: &mut [u64], b: &[u64], shift: usize) {
+    let (word_shift, bit_shift) = (shift / 64, shift % 64);
+    for (source, word) in c.iter_mut().skip(word_shift).enumerate() {
+        let mut value = b[source] << bit_shift;
+        if bit_shift > 0 && source > 0 {
+            value |= b[source - 1] >> (64 - bit_shift);
+        }
+        *word ^= value;
+    }
+}
+
+#[test]
+fn test_berlekamp_massey() {
+    use super::frequency::examples::bits;
+
+    // SP 800-22 section 2.10.4
+    assert_eq!(berlekamp_massey(&bits("1101011110001")), 4);
+    assert_eq!(berlekamp_massey(&bits("0000")), 0);
+    assert_eq!(berlekamp_massey(&bits("0001")), 4);
+    assert_eq!(berlekamp_massey(&bits("1111")), 1);
+
+    // s_i = s_{i-1} ^ s_{i-4}: maximal length LFSR of degree 4
+    let mut lfsr = vec![1, 0, 0, 0];
+    for i in 4..30 {
+        lfsr.push(lfsr[i - 1] ^ lfsr[i - 4]);
+    }
+    assert_eq!(berlekamp_massey(&lfsr), 4);
+
+    // across word boundaries: a single one at position 99 needs an LFSR of length 100
+    let mut impulse = vec![0; 150];
+    impulse[99] = 1;
+    assert_eq!(berlekamp_massey(&impulse), 100);
+
+    assert_eq!(
+        linear_complexity(&lfsr, 100).unwrap_err(),
+        TestError::InvalidParameter("Linear complexity", 100)
+    );
+}
@@ -0,0 +1,183 @@
+// Discrete Fourier transform (spectral) test (NIST SP 800-22, section 2.6)
+use std::f64::consts::{PI, SQRT_2};
+
+use super::{
+    report::{require_length, TestError, TestResult},
+    special_functions::erfc,
+};
+
+/// Spectral test based on the discrete Fourier transform
+///
+/// Periodic features show up as peaks in the spectrum of
+/// $X_i = 2\varepsilon_i - 1$. For a random sequence 95% of the moduli of
+/// the first $n/2$ coefficients are below $T = \sqrt{n \ln(1 / 0.05)}$.
+/// With $N_1$ the observed count below $T$ and $N_0 = 0.95 n / 2$:
+///
+/// $$d = \frac{N_1 - N_0}{\sqrt{n \cdot 0.95 \cdot 0.05 / 4}}, \quad P = \text{erfc}(|d| / \sqrt{2})$$
+pub fn spectral_dft(bits: &[u8]) -> Result<TestResult, TestError> {
+    require_length("Spectral DFT", bits, 100)?;
+
+    let n = bits.len();
+    let x: Vec<Complex> = bits
+        .iter()
+        .map(|&bit| Complex::new(2.0 * bit as f64 - 1.0, 0.0))
+        .collect();
+    let spectrum = dft(&x);
+
+    let threshold = ((1.0 / 0.05f64).ln() * n as f64).sqrt();
+    let n0 = 0.95 * n as f64 / 2.0;
+    let n1 = spectrum[..n / 2]
+        .iter()
+        .filter(|s| s.norm() < threshold)
+        .count() as f64;
+    let d = (n1 - n0) / (n as f64 * 0.95 * 0.05 / 4.0).sqrt();
+
+    Ok(TestResult::new(
+        "Spectral DFT",
+        vec![erfc(d.abs() / SQRT_2)],
+    ))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    /// $e^{i\theta}$
+    fn from_angle(theta: f64) -> Self {
+        Self::new(theta.cos(), theta.sin())
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.re + other.re, self.im + other.im)
+    }
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.re - other.re, self.im - other.im)
+    }
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+
+    fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    fn norm(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+}
+
+/// $S_k = \sum_j x_j e^{-2\pi i jk/n}$ for any length
+///
+/// Powers of two use the radix-2 FFT directly, other lengths go through
+/// Bluestein's algorithm: with $jk = (j^2 + k^2 - (k - j)^2) / 2$ the
+/// transform becomes a convolution, computed with power of two FFTs.
+fn dft(x: &[Complex]) -> Vec<Complex> {
+    let n = x.len();
+    if n.is_power_of_two() {
+        let mut data = x.to_vec();
+        fft(&mut data, false);
+        return data;
+    }
+
+    // chirp w_j = e^{-i pi j^2 / n}, j^2 reduced mod 2n to keep the angle small
+    let chirp: Vec<Complex> = (0..n as u64)
+        .map(|j| Complex::from_angle(-PI * ((j * j) % (2 * n as u64)) as f64 / n as f64))
+        .collect();
+
+    let size = (2 * n - 1).next_power_of_two();
+    let mut a = vec![Complex::new(0.0, 0.0); size];
+    for (a, (x, w)) in a.iter_mut().zip(x.iter().zip(chirp.iter())) {
+        *a = x.mul(*w);
+    }
+    let mut b = vec![Complex::new(0.0, 0.0); size];
+    b[0] = chirp[0].conj();
+    for j in 1..n {
+        b[j] = chirp[j].conj();
+        b[size - j] = chirp[j].conj();
+    }
+
+    fft(&mut a, false);
+    fft(&mut b, false);
+    let mut c: Vec<Complex> = a.iter().zip(b.iter()).map(|(a, b)| a.mul(*b)).collect();
+    fft(&mut c, true);
+
+    c.iter().zip(chirp.iter()).map(|(c, w)| c.mul(*w)).collect()
+}
+
+/// In place iterative radix-2 FFT, `inverse` includes the $1/n$ factor
+fn fft(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+
+    // bit reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let root = Complex::from_angle(sign * 2.0 * PI / len as f64);
+        for chunk in data.chunks_mut(len) {
+            let mut w = Complex::new(1.0, 0.0);
+            let (low, high) = chunk.split_at_mut(len / 2);
+            for (u, v) in low.iter_mut().zip(high.iter_mut()) {
+                let t = v.mul(w);
+                *v = u.sub(t);
+                *u = u.add(t);
+                w = w.mul(root);
+            }
+        }
+        len <<= 1;
+    }
+
+    if inverse {
+        for value in data.iter_mut() {
+            *value = Complex::new(value.re / n as f64, value.im / n as f64);
+        }
+    }
+}
+
+#[test]
+fn test_spectral_dft() {
+    // the fast transforms agree with the definition
+    let x: Vec<Complex> = (0..12)
+        .map(|i| Complex::new((i * 7 % 5) as f64, 0.0))
+        .collect();
+    for len in [8, 12] {
+        let fast = dft(&x[..len]);
+        for (k, s) in fast.iter().enumerate() {
+            let naive = x[..len]
+                .iter()
+                .enumerate()
+                .fold(Complex::new(0.0, 0.0), |acc, (j, x)| {
+                    acc.add(x.mul(Complex::from_angle(-2.0 * PI * (j * k) as f64 / len as f64)))
+                });
+            assert!(s.sub(naive).norm() < 1e-9);
+        }
+    }
+
+    // a periodic sequence has a single peak, far fewer coefficients than expected are large
+    let periodic: Vec<u8> = (0..1000).map(|i| (i % 4 < 2) as u8).collect();
+    assert!(spectral_dft(&periodic).unwrap().p_values[0] < 0.01);
+}
@@ -0,0 +1,153 @@
+// Frequency tests (NIST SP 800-22, sections 2.1, 2.2 and 2.13)
+use std::f64::consts::SQRT_2;
+
+use super::{
+    report::{require_length, TestError, TestResult},
+    special_functions::{erfc, igamc, normal_cdf},
+};
+
+/// Frequency (monobit) test
+///
+/// With $S_n = \sum (2 \varepsilon_i - 1)$, the statistic
+/// $s_{obs} = |S_n| / \sqrt{n}$ is half-normal for a random sequence and
+/// $P = \text{erfc}(s_{obs} / \sqrt{2})$.
+pub fn monobit(bits: &[u8]) -> Result<TestResult, TestError> {
+    require_length("Frequency (monobit)", bits, 100)?;
+
+    let n = bits.len() as f64;
+    let s_obs = partial_sum(bits).abs() as f64 / n.sqrt();
+
+    Ok(TestResult::new(
+        "Frequency (monobit)",
+        vec![erfc(s_obs / SQRT_2)],
+    ))
+}
+
+/// Frequency test within blocks of `m` bits
+///
+/// With $\pi_i$ the proportion of ones in each of the $N$ blocks,
+/// $\chi^2 = 4M \sum (\pi_i - 1/2)^2$ has $N$ degrees of freedom.
+pub fn block_frequency(bits: &[u8], m: usize) -> Result<TestResult, TestError> {
+    require_length("Block frequency", bits, 100)?;
+    if m == 0 || m > bits.len() {
+        return Err(TestError::InvalidParameter("Block frequency", m));
+    }
+
+    let blocks = bits.len() / m;
+    let chi_squared = 4.0
+        * m as f64
+        * bits
+            .chunks_exact(m)
+            .map(|block| {
+                let ones = block.iter().filter(|&&bit| bit == 1).count();
+                (ones as f64 / m as f64 - 0.5).powi(2)
+            })
+            .sum::<f64>();
+
+    Ok(TestResult::new(
+        "Block frequency",
+        vec![igamc(blocks as f64 / 2.0, chi_squared / 2.0)],
+    ))
+}
+
+/// Cumulative sums test, forward and backward
+///
+/// $z$ is the maximal excursion $\max_k |S_k|$ of the random walk of the
+/// partial sums. The p-value is the probability that a random walk of $n$
+/// steps goes at least that far:
+///
+/// $$
+/// P = 1 - \sum_{k} \left[ \Phi\left(\frac{(4k+1)z}{\sqrt{n}}\right) - \Phi\left(\frac{(4k-1)z}{\sqrt{n}}\right) \right]
+///       + \sum_{k} \left[ \Phi\left(\frac{(4k+3)z}{\sqrt{n}}\right) - \Phi\left(\frac{(4k+1)z}{\sqrt{n}}\right) \right]
+/// $$
+pub fn cumulative_sums(bits: &[u8]) -> Result<TestResult, TestError> {
+    require_length("Cumulative sums", bits, 100)?;
+
+    let n = bits.len();
+    let forward = max_excursion(bits.iter());
+    let backward = max_excursion(bits.iter().rev());
+
+    Ok(TestResult::new(
+        "Cumulative sums",
+        vec![
+            cumulative_sums_p_value(n, forward),
+            cumulative_sums_p_value(n, backward),
+        ],
+    ))
+}
+
+fn cumulative_sums_p_value(n: usize, z: usize) -> f64 {
+    let (n, z) = (n as f64, z as f64);
+    let sqrt_n = n.sqrt();
+
+    // summation bounds truncated towards zero, as in the NIST reference code
+    let mut sum1 = 0.0;
+    let mut k = ((-n / z + 1.0) / 4.0) as i64;
+    while k as f64 <= (n / z - 1.0) / 4.0 {
+        let k_f = k as f64;
+        sum1 +=
+            normal_cdf((4.0 * k_f + 1.0) * z / sqrt_n) - normal_cdf((4.0 * k_f - 1.0) * z / sqrt_n);
+        k += 1;
+    }
+
+    let mut sum2 = 0.0;
+    let mut k = ((-n / z - 3.0) / 4.0) as i64;
+    while k as f64 <= (n / z - 1.0) / 4.0 {
+        let k_f = k as f64;
+        sum2 +=
+            normal_cdf((4.0 * k_f + 3.0) * z / sqrt_n) - normal_cdf((4.0 * k_f + 1.0) * z / sqrt_n);
+        k += 1;
+    }
+
+    1.0 - sum1 + sum2
+}
+
+/// $\sum (2 \varepsilon_i - 1)$
+fn partial_sum(bits: &[u8]) -> i64 {
+    bits.iter().map(|&bit| 2 * bit as i64 - 1).sum()
+}
+
+fn max_excursion<'a>(bits: impl Iterator<Item = &'a u8>) -> usize {
+    let mut sum = 0i64;
+    let mut max = 0;
+    for &bit in bits {
+        sum += 2 * bit as i64 - 1;
+        max = max.max(sum.unsigned_abs() as usize);
+    }
+    max
+}
+
+#[cfg(test)]
+pub(crate) mod examples {
+    /// First 100 bits of the binary expansion of pi, the example of SP 800-22
+    pub const PI_100: &str = "1100100100001111110110101010001000100001011010001100001000110100110001001100011001100010100010111000";
+
+    pub fn bits(s: &str) -> Vec<u8> {
+        s.bytes().map(|c| c - b'0').collect()
+    }
+
+    pub fn assert_p_value(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {expected}, got {actual}"
+        );
+    }
+}
+
+#[test]
+fn test_frequency() {
+    use examples::{assert_p_value, bits, PI_100};
+
+    let pi = bits(PI_100);
+    assert_p_value(monobit(&pi).unwrap().p_values[0], 0.109599);
+    assert_p_value(block_frequency(&pi, 10).unwrap().p_values[0], 0.706438);
+
+    let cusum = cumulative_sums(&pi).unwrap();
+    assert_p_value(cusum.p_values[0], 0.219194);
+    assert_p_value(cusum.p_values[1], 0.114866);
+
+    assert_eq!(
+        monobit(&pi[..10]).unwrap_err(),
+        TestError::SequenceTooShort("Frequency (monobit)", 100, 10)
+    );
+}
@@ -0,0 +1,8 @@
+pub mod complexity;
+pub mod dft;
+pub mod frequency;
+pub mod report;
+pub mod runs;
+pub mod serial;
+pub mod special_functions;
+pub mod spectral;
@@ -0,0 +1,225 @@
+// Results of the statistical tests and the report of a whole suite
+use std::fmt;
+
+use thiserror::Error;
+
+use super::{complexity, dft, frequency, runs, serial};
+use crate::{common::bits::Bits, rng::traits::Rng};
+
+/// Significance level recommended by NIST SP 800-22
+pub const ALPHA: f64 = 0.01;
+
+/// Outcome of one statistical test
+///
+/// Most tests produce a single p-value, the serial and cumulative sums tests
+/// produce two. The p-value is the probability that a truly random sequence
+/// gives a statistic at least as extreme as the observed one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    pub name: &'static str,
+    pub p_values: Vec<f64>,
+}
+
+impl TestResult {
+    pub fn new(name: &'static str, p_values: Vec<f64>) -> Self {
+        Self { name, p_values }
+    }
+
+    /// The sequence passes if every p-value is at least `alpha`
+    pub fn passed(&self, alpha: f64) -> bool {
+        self.p_values.iter().all(|&p| p >= alpha)
+    }
+}
+
+/// Results of the whole suite on one sequence
+///
+/// Tests whose requirements are not met (mostly the sequence length) are kept
+/// with their error and count neither as passed nor as failed.
+#[derive(Debug)]
+pub struct Report {
+    pub length: usize,
+    pub alpha: f64,
+    pub results: Vec<Result<TestResult, TestError>>,
+}
+
+impl Report {
+    /// Run every test with the recommended parameters for the length of `bits`
+    ///
+    /// `bits` holds one bit (0 or 1) per byte.
+    pub fn run(bits: &[u8]) -> Self {
+        let n = bits.len();
+        // log2 of the length bounds the pattern lengths of the serial tests
+        let log_n = n.max(1).ilog2() as usize;
+        let serial_m = 16.min(log_n.saturating_sub(3)).max(2);
+        let entropy_m = 10.min(log_n.saturating_sub(6)).max(1);
+
+        let results = vec![
+            frequency::monobit(bits),
+            frequency::block_frequency(bits, 128),
+            runs::runs(bits),
+            runs::longest_run(bits),
+            serial::serial(bits, serial_m),
+            serial::approximate_entropy(bits, entropy_m),
+            frequency::cumulative_sums(bits),
+            dft::spectral_dft(bits),
+            complexity::linear_complexity(bits, 500),
+        ];
+
+        Self {
+            length: n,
+            alpha: ALPHA,
+            results,
+        }
+    }
+
+    pub fn from_bits(bits: &Bits) -> Self {
+        Self::run(bits.as_slice())
+    }
+
+    /// Run the suite on the first `n` bits of a generator
+    pub fn from_rng<R: Rng>(rng: &mut R, n: usize) -> Self {
+        Self::run(&bits_from_rng(rng, n))
+    }
+
+    /// True if no test that could run failed
+    pub fn passed(&self) -> bool {
+        self.results
+            .iter()
+            .flatten()
+            .all(|result| result.passed(self.alpha))
+    }
+
+    pub fn failures(&self) -> Vec<&TestResult> {
+        self.results
+            .iter()
+            .flatten()
+            .filter(|result| !result.passed(self.alpha))
+            .collect()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} bits, alpha = {}", self.length, self.alpha)?;
+        for result in &self.results {
+            match result {
+                Ok(result) => {
+                    let p_values: Vec<String> =
+                        result.p_values.iter().map(|p| format!("{p:.6}")).collect();
+                    let verdict = if result.passed(self.alpha) {
+                        "PASS"
+                    } else {
+                        "FAIL"
+                    };
+                    writeln!(
+                        f,
+                        "{:<22} {:<20} {verdict}",
+                        result.name,
+                        p_values.join(" ")
+                    )?;
+                }
+                Err(e) => writeln!(f, "{:<22} {:<20} SKIP ({e})", "", "")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// First `n` output bits of a generator, msb-first within each 64-bit word
+pub fn bits_from_rng<R: Rng>(rng: &mut R, n: usize) -> Vec<u8> {
+    let mut bits = Vec::with_capacity(n + 64);
+    while bits.len() < n {
+        let word = rng.next_u64();
+        bits.extend((0..64).rev().map(|i| ((word >> i) & 1) as u8));
+    }
+    bits.truncate(n);
+    bits
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum TestError {
+    #[error("{0} needs at least {1} bits, got {2}")]
+    SequenceTooShort(&'static str, usize, usize),
+
+    #[error("{0}: invalid parameter {1}")]
+    InvalidParameter(&'static str, usize),
+}
+
+/// Check the minimum length of a test
+pub(crate) fn require_length(
+    name: &'static str,
+    bits: &[u8],
+    min_len: usize,
+) -> Result<(), TestError> {
+    if bits.len() < min_len {
+        return Err(TestError::SequenceTooShort(name, min_len, bits.len()));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_report() {
+    use crate::rng::{chacha::ChaChaRng, lcg::LCG};
+
+    let mut rng = ChaChaRng::new(&[42; 32], 20).unwrap();
+    let report = Report::from_rng(&mut rng, 100_000);
+    assert_eq!(report.results.len(), 9);
+    assert!(report.passed(), "{report}");
+
+    // bit i of a power-of-two modulus LCG repeats every 2^(i + 1) steps: the
+    // low bits of the outputs fail the spectral test, the top 16 bits pass
+    let outputs: Vec<u64> = LCG::new(1, 1 << 31, 1_103_515_245, 12345)
+        .take(7000)
+        .collect();
+    let bits = |low: u32| -> Vec<u8> {
+        outputs
+            .iter()
+            .flat_map(|x| (low..31).rev().map(move |i| ((x >> i) & 1) as u8))
+            .take(100_000)
+            .collect()
+    };
+    let report = Report::run(&bits(0));
+    let failures: Vec<_> = report.failures().iter().map(|result| result.name).collect();
+    assert_eq!(failures, ["Spectral DFT"], "{report}");
+    assert!(Report::run(&bits(15)).passed());
+
+    // too short for the linear complexity test
+    let report = Report::run(&bits_from_rng(&mut rng, 1000));
+    assert!(matches!(
+        report.results[8],
+        Err(TestError::SequenceTooShort(..))
+    ));
+}
+
+#[test]
+fn test_report_nist_e() {
+    // first 1,000,000 bits of the binary expansion of e, SP 800-22 appendix B
+    let bits: Vec<u8> = include_bytes!("data/e.bin")
+        .iter()
+        .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1))
+        .collect();
+    let report = Report::run(&bits);
+    assert!(report.passed(), "{report}");
+
+    let expected: [(&str, &[f64]); 8] = [
+        ("Frequency (monobit)", &[0.953749]),
+        ("Runs", &[0.561917]),
+        ("Longest run of ones", &[0.718945]),
+        ("Serial", &[0.766182, 0.462921]),
+        ("Approximate entropy", &[0.700073]),
+        ("Cumulative sums", &[0.669887, 0.724266]),
+        ("Spectral DFT", &[0.847187]),
+        ("Linear complexity", &[0.826335]),
+    ];
+    for (name, p_values) in expected {
+        let result = report
+            .results
+            .iter()
+            .flatten()
+            .find(|result| result.name == name)
+            .unwrap();
+        for (actual, expected) in result.p_values.iter().zip(p_values) {
+            assert!((actual - expected).abs() < 1e-6, "{name}: {actual}");
+        }
+    }
+}
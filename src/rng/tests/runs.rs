@@ -0,0 +1,108 @@
+// Runs tests (NIST SP 800-22, sections 2.3 and 2.4)
+use std::f64::consts::SQRT_2;
+
+use super::{
+    report::{require_length, TestError, TestResult},
+    special_functions::{erfc, igamc},
+};
+
+/// Runs test: number of uninterrupted runs of identical bits
+///
+/// With $\pi$ the proportion of ones and $V$ the number of runs:
+///
+/// $$P = \text{erfc}\left(\frac{|V - 2n\pi(1 - \pi)|}{2\sqrt{2n}\pi(1 - \pi)}\right)$$
+///
+/// The test is not applicable ($P = 0$) when the sequence already fails
+/// the frequency prerequisite $|\pi - 1/2| < 2 / \sqrt{n}$.
+pub fn runs(bits: &[u8]) -> Result<TestResult, TestError> {
+    require_length("Runs", bits, 100)?;
+
+    let n = bits.len() as f64;
+    let pi = bits.iter().filter(|&&bit| bit == 1).count() as f64 / n;
+    if (pi - 0.5).abs() >= 2.0 / n.sqrt() {
+        return Ok(TestResult::new("Runs", vec![0.0]));
+    }
+
+    let v_obs = 1 + bits.windows(2).filter(|pair| pair[0] != pair[1]).count();
+    let p_value = erfc(
+        (v_obs as f64 - 2.0 * n * pi * (1.0 - pi)).abs()
+            / (2.0 * SQRT_2 * n.sqrt() * pi * (1.0 - pi)),
+    );
+
+    Ok(TestResult::new("Runs", vec![p_value]))
+}
+
+/// Test for the longest run of ones in a block
+///
+/// The longest run of ones of every block of $M$ bits is sorted into $K + 1$
+/// classes, whose expected probabilities depend on $M$:
+///
+/// | n          | M      | K | classes              |
+/// |------------|--------|---|----------------------|
+/// | >= 128     | 8      | 3 | <= 1, 2, 3, >= 4     |
+/// | >= 6272    | 128    | 5 | <= 4, 5, ..., >= 9   |
+/// | >= 750000  | 10^4   | 6 | <= 10, 11, ..., >= 16 |
+pub fn longest_run(bits: &[u8]) -> Result<TestResult, TestError> {
+    require_length("Longest run of ones", bits, 128)?;
+
+    let (m, min_class, probabilities): (usize, usize, &[f64]) = match bits.len() {
+        n if n < 6272 => (8, 1, &[0.21484375, 0.3671875, 0.23046875, 0.1875]),
+        n if n < 750_000 => (
+            128,
+            4,
+            &[
+                0.1174035788,
+                0.242955959,
+                0.249363483,
+                0.17517706,
+                0.102701071,
+                0.112398847,
+            ],
+        ),
+        _ => (
+            10_000,
+            10,
+            &[0.0882, 0.2092, 0.2483, 0.1933, 0.1208, 0.0675, 0.0727],
+        ),
+    };
+    let k = probabilities.len() - 1;
+
+    let mut frequencies = vec![0usize; k + 1];
+    for block in bits.chunks_exact(m) {
+        let longest = block
+            .split(|&bit| bit == 0)
+            .map(|run| run.len())
+            .max()
+            .unwrap_or(0);
+        let class = longest.clamp(min_class, min_class + k) - min_class;
+        frequencies[class] += 1;
+    }
+
+    let blocks = (bits.len() / m) as f64;
+    let chi_squared: f64 = frequencies
+        .iter()
+        .zip(probabilities)
+        .map(|(&v, &p)| (v as f64 - blocks * p).powi(2) / (blocks * p))
+        .sum();
+
+    Ok(TestResult::new(
+        "Longest run of ones",
+        vec![igamc(k as f64 / 2.0, chi_squared / 2.0)],
+    ))
+}
+
+#[test]
+fn test_runs() {
+    use super::frequency::examples::{assert_p_value, bits, PI_100};
+
+    assert_p_value(runs(&bits(PI_100)).unwrap().p_values[0], 0.500798);
+
+    // SP 800-22 section 2.4.8
+    let epsilon = bits(
+        "11001100000101010110110001001100111000000000001001001101010100010001001111010110100000001101011111001100111001101101100010110010",
+    );
+    assert_p_value(longest_run(&epsilon).unwrap().p_values[0], 0.180609);
+
+    // far too many ones
+    assert_eq!(runs(&[1; 100]).unwrap().p_values, vec![0.0]);
+}
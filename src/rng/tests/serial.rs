@@ -0,0 +1,121 @@
+// Serial and approximate entropy tests (NIST SP 800-22, sections 2.11 and 2.12)
+use super::{
+    report::{require_length, TestError, TestResult},
+    special_functions::igamc,
+};
+
+/// Serial test: frequency of all the overlapping patterns of `m` bits
+///
+/// The sequence is extended with its first $m - 1$ bits so that every
+/// position starts a pattern. With $\nu_i$ the counts of the $2^m$ patterns:
+///
+/// $$\psi_m^2 = \frac{2^m}{n} \sum \nu_i^2 - n$$
+///
+/// The two statistics $\nabla \psi_m^2 = \psi_m^2 - \psi_{m-1}^2$ and
+/// $\nabla^2 \psi_m^2 = \psi_m^2 - 2\psi_{m-1}^2 + \psi_{m-2}^2$ have
+/// $2^{m-1}$ and $2^{m-2}$ degrees of freedom.
+pub fn serial(bits: &[u8], m: usize) -> Result<TestResult, TestError> {
+    require_length("Serial", bits, 100)?;
+    if m < 2 || m >= bits.len().ilog2() as usize {
+        return Err(TestError::InvalidParameter("Serial", m));
+    }
+
+    let psi_m = psi_squared(bits, m);
+    let psi_m1 = psi_squared(bits, m - 1);
+    let psi_m2 = psi_squared(bits, m - 2);
+
+    let delta1 = psi_m - psi_m1;
+    let delta2 = psi_m - 2.0 * psi_m1 + psi_m2;
+
+    Ok(TestResult::new(
+        "Serial",
+        vec![
+            igamc(2f64.powi(m as i32 - 2), delta1 / 2.0),
+            igamc(2f64.powi(m as i32 - 3), delta2 / 2.0),
+        ],
+    ))
+}
+
+/// Approximate entropy test: compare the frequencies of overlapping patterns
+/// of `m` and `m + 1` bits
+///
+/// With $\varphi^{(m)} = \sum \pi_i \ln \pi_i$ over the pattern frequencies:
+///
+/// $$ApEn(m) = \varphi^{(m)} - \varphi^{(m+1)}, \quad \chi^2 = 2n(\ln 2 - ApEn(m))$$
+///
+/// with $2^m$ degrees of freedom.
+pub fn approximate_entropy(bits: &[u8], m: usize) -> Result<TestResult, TestError> {
+    require_length("Approximate entropy", bits, 100)?;
+    if m == 0 || m + 1 >= bits.len().ilog2() as usize {
+        return Err(TestError::InvalidParameter("Approximate entropy", m));
+    }
+
+    let n = bits.len() as f64;
+    let phi = |m: usize| -> f64 {
+        pattern_counts(bits, m)
+            .into_iter()
+            .filter(|&count| count > 0)
+            .map(|count| {
+                let pi = count as f64 / n;
+                pi * pi.ln()
+            })
+            .sum()
+    };
+
+    let ap_en = phi(m) - phi(m + 1);
+    let chi_squared = 2.0 * n * (2f64.ln() - ap_en);
+
+    Ok(TestResult::new(
+        "Approximate entropy",
+        vec![igamc(2f64.powi(m as i32 - 1), chi_squared / 2.0)],
+    ))
+}
+
+/// $\psi_m^2$, zero for $m \le 0$
+fn psi_squared(bits: &[u8], m: usize) -> f64 {
+    if m == 0 {
+        return 0.0;
+    }
+    let n = bits.len() as f64;
+    let sum: f64 = pattern_counts(bits, m)
+        .into_iter()
+        .map(|count| (count as f64).powi(2))
+        .sum();
+    2f64.powi(m as i32) / n * sum - n
+}
+
+/// Counts of the overlapping patterns of `m` bits, wrapping around the end
+fn pattern_counts(bits: &[u8], m: usize) -> Vec<usize> {
+    let mask = (1usize << m) - 1;
+    let mut counts = vec![0; 1 << m];
+
+    let mut pattern = 0;
+    for &bit in bits.iter().cycle().take(m - 1) {
+        pattern = (pattern << 1) | bit as usize;
+    }
+    for &bit in bits.iter().skip(m - 1).chain(bits.iter().take(m - 1)) {
+        pattern = ((pattern << 1) | bit as usize) & mask;
+        counts[pattern] += 1;
+    }
+
+    counts
+}
+
+#[test]
+fn test_serial() {
+    use super::frequency::examples::{assert_p_value, bits, PI_100};
+
+    assert_p_value(
+        approximate_entropy(&bits(PI_100), 2).unwrap().p_values[0],
+        0.235301,
+    );
+
+    // SP 800-22 section 2.11.4 counts for 0011011101 with m = 3
+    let epsilon = bits("0011011101");
+    assert_eq!(pattern_counts(&epsilon, 3), vec![0, 1, 1, 2, 1, 2, 2, 1]);
+    assert_eq!(pattern_counts(&epsilon, 2), vec![1, 3, 3, 3]);
+    assert_eq!(pattern_counts(&epsilon, 1), vec![4, 6]);
+    assert!((psi_squared(&epsilon, 3) - 2.8).abs() < 1e-9);
+    assert!((psi_squared(&epsilon, 2) - 1.2).abs() < 1e-9);
+    assert!((psi_squared(&epsilon, 1) - 0.4).abs() < 1e-9);
+}
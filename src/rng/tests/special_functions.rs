@@ -0,0 +1,123 @@
+// Special functions for the p-values of the statistical tests
+use std::f64::consts::{PI, SQRT_2};
+
+/// Natural logarithm of the gamma function (Lanczos approximation, $g = 7$)
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        // reflection formula
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let sum = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |acc, (i, c)| {
+            acc + c / (x + i as f64 + 1.0)
+        });
+
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// Regularized upper incomplete gamma function
+///
+/// $$Q(a, x) = \frac{1}{\Gamma(a)} \int_x^\infty e^{-t} t^{a-1} dt$$
+///
+/// The p-value of a $\chi^2$ statistic with $k$ degrees of freedom is
+/// $Q(k/2, \chi^2/2)$. Computed with the series of $P = 1 - Q$ for
+/// $x < a + 1$ and with a continued fraction otherwise.
+pub fn igamc(a: f64, x: f64) -> f64 {
+    const EPSILON: f64 = 1e-15;
+    const MAX_ITERATIONS: usize = 10_000;
+
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let prefactor = (-x + a * x.ln() - ln_gamma(a)).exp();
+
+    if x < a + 1.0 {
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        for n in 1..MAX_ITERATIONS {
+            term *= x / (a + n as f64);
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        return 1.0 - sum * prefactor;
+    }
+
+    // modified Lentz's method
+    let tiny = 1e-300;
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / tiny;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for n in 1..MAX_ITERATIONS {
+        let an = -(n as f64) * (n as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < tiny {
+            d = tiny;
+        }
+        c = b + an / c;
+        if c.abs() < tiny {
+            c = tiny;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    prefactor * h
+}
+
+/// Complementary error function, $\text{erfc}(x) = Q(1/2, x^2)$ for $x \ge 0$
+pub fn erfc(x: f64) -> f64 {
+    if x < 0.0 {
+        2.0 - igamc(0.5, x * x)
+    } else {
+        igamc(0.5, x * x)
+    }
+}
+
+/// Cumulative distribution function of the standard normal distribution
+pub fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / SQRT_2)
+}
+
+#[test]
+fn test_special_functions() {
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-12;
+
+    assert!(close(ln_gamma(1.0), 0.0));
+    assert!(close(ln_gamma(5.0), 24f64.ln()));
+    assert!(close(ln_gamma(0.5), PI.sqrt().ln()));
+
+    assert!(close(erfc(0.0), 1.0));
+    assert!(close(erfc(1.0), 0.157_299_207_050_285_13));
+    assert!(close(erfc(-1.0), 1.842_700_792_949_715));
+    assert!(close(normal_cdf(1.96), 0.975_002_104_851_780_1));
+
+    // Q(1, x) = e^-x
+    assert!(close(igamc(1.0, 3.0), (-3f64).exp()));
+    assert!(close(igamc(1.0, 0.2), (-0.2f64).exp()));
+    // chi-squared with 2 degrees of freedom
+    assert!(close(igamc(1.0, 5.991_464_547_107_979 / 2.0), 0.05));
+}
@@ -0,0 +1,290 @@
+// Spectral test for linear congruential generators
+use std::f64::consts::PI;
+
+use super::report::TestError;
+use crate::{math::modular_arithmetic::mod_pow::mod_mul, rng::lcg::LCG};
+
+/// Result of the spectral test in dimension $t$
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpectralResult {
+    pub dimension: usize,
+    /// $\nu_t^2$
+    pub nu_squared: u128,
+    /// Figure of merit $\mu_t$
+    pub merit: f64,
+}
+
+impl SpectralResult {
+    /// $\nu_t$: distance between the hyperplanes covering the points, scaled by $m$
+    pub fn nu(&self) -> f64 {
+        (self.nu_squared as f64).sqrt()
+    }
+
+    /// Knuth's criterion: $\mu_t \ge 0.1$ passes, $\mu_t \ge 1$ passes with flying colors
+    pub fn passed(&self) -> bool {
+        self.merit >= 0.1
+    }
+}
+
+/// Spectral test of the multiplier `a` modulo `m` in dimensions 2 to `max_dimension`
+///
+/// The overlapping tuples $(x_n, x_{n+1}, \dots, x_{n+t-1})$ of an LCG lie on
+/// a lattice, covered by parallel hyperplanes at distance $1 / \nu_t$ (for
+/// points in the unit cube), where
+///
+/// $$\nu_t = \min \left\{ \|s\| : s \neq 0, \; s_1 + s_2 a + \dots + s_t a^{t-1} \equiv 0 \pmod m \right\}$$
+///
+/// is the length of the shortest vector of the dual lattice. Large $\nu_t$
+/// means the points fill the space evenly. The figure of merit compares it
+/// with the best possible value:
+///
+/// $$\mu_t = \frac{\pi^{t/2} \nu_t^t}{\Gamma(t/2 + 1) \, m}$$
+///
+/// The additive constant of the LCG plays no role. Uses Knuth's algorithm:
+/// the dual basis $U$ and the primal basis $V$ (with $U V^T = m I$) are
+/// reduced together, then the shortest vector is found by exhaustive search
+/// in the box bounded by $|x_j| \le \sqrt{\nu^2 \, V_j \cdot V_j} / m$.
+///
+/// # See also
+/// - Knuth, Donald E. The Art of Computer Programming, Volume 2, Algorithm 3.3.4 S.
+pub fn spectral_test(
+    a: u64,
+    m: u64,
+    max_dimension: usize,
+) -> Result<Vec<SpectralResult>, TestError> {
+    // keep the dot products within i128
+    if !(2..1 << 48).contains(&m) || a == 0 || a >= m {
+        return Err(TestError::InvalidParameter("Spectral test", m as usize));
+    }
+    if !(2..=8).contains(&max_dimension) {
+        return Err(TestError::InvalidParameter("Spectral test", max_dimension));
+    }
+
+    let m_i = m as i128;
+    let a_i = a as i128;
+    let mut results = Vec::new();
+
+    // 2 dimensions: Euclid's algorithm on (a, m) gives a reduced basis
+    let (mut h, mut h_prev, mut p, mut p_prev) = (a_i, m_i, 1i128, 0i128);
+    let mut s = 1 + a_i * a_i;
+    let (mut u, mut v);
+    loop {
+        let q = h_prev / h;
+        u = h_prev - q * h;
+        v = p_prev - q * p;
+        if u * u + v * v >= s {
+            break;
+        }
+        s = u * u + v * v;
+        h_prev = h;
+        h = u;
+        p_prev = p;
+        p = v;
+    }
+    u -= h;
+    v -= p;
+    if u * u + v * v < s {
+        s = u * u + v * v;
+        h_prev = u;
+        p_prev = v;
+    }
+
+    // rows of U are dual lattice vectors, rows of V lattice vectors, U V^T = m I
+    let mut basis_u = vec![vec![-h, p], vec![-h_prev, p_prev]];
+    let mut basis_v = vec![vec![p_prev, h_prev], vec![-p, -h]];
+    if dot(&basis_u[0], &basis_v[0]) < 0 {
+        for row in basis_v.iter_mut() {
+            row.iter_mut().for_each(|x| *x = -*x);
+        }
+    }
+    results.push(SpectralResult::new(2, s, m));
+
+    let mut r = a;
+    for t in 3..=max_dimension {
+        // extend the bases with the new coordinate
+        r = mod_mul(a, r, m);
+        let r_i = r as i128;
+        for row in basis_u.iter_mut() {
+            row.push(0);
+        }
+        let mut new_u = vec![0; t];
+        new_u[0] = -r_i;
+        new_u[t - 1] = 1;
+        for i in 0..t - 1 {
+            let q = div_round(basis_v[i][0] * r_i, m_i);
+            let coordinate = basis_v[i][0] * r_i - q * m_i;
+            basis_v[i].push(coordinate);
+            for (x, y) in new_u.iter_mut().zip(basis_u[i].iter()) {
+                *x += q * y;
+            }
+        }
+        let mut new_v = vec![0; t];
+        new_v[t - 1] = m_i;
+        basis_u.push(new_u);
+        basis_v.push(new_v);
+        s = s.min(dot(&basis_u[t - 1], &basis_u[t - 1]));
+
+        reduce(&mut basis_u, &mut basis_v, &mut s);
+        search(&basis_u, &basis_v, m_i, &mut s);
+
+        results.push(SpectralResult::new(t, s, m));
+    }
+
+    Ok(results)
+}
+
+impl SpectralResult {
+    fn new(dimension: usize, nu_squared: i128, m: u64) -> Self {
+        let t = dimension as f64;
+        // Gamma(t/2 + 1) for integer and half integer arguments
+        let gamma = (1..=dimension).filter(|k| k % 2 == dimension % 2).fold(
+            if dimension.is_multiple_of(2) {
+                1.0
+            } else {
+                PI.sqrt()
+            },
+            |acc, k| acc * k as f64 / 2.0,
+        );
+        let merit = PI.powf(t / 2.0) * (nu_squared as f64).powf(t / 2.0) / (gamma * m as f64);
+
+        Self {
+            dimension,
+            nu_squared: nu_squared as u128,
+            merit,
+        }
+    }
+}
+
+impl LCG {
+    /// Spectral test of the multiplier of the generator
+    pub fn spectral_test(&self, max_dimension: usize) -> Result<Vec<SpectralResult>, TestError> {
+        spectral_test(self.multiplier(), self.modulus(), max_dimension)
+    }
+}
+
+/// Pairwise reduction of the bases until no transformation shortens V
+fn reduce(basis_u: &mut [Vec<i128>], basis_v: &mut [Vec<i128>], s: &mut i128) {
+    let t = basis_u.len();
+    let mut unchanged = 0;
+    let mut j = 0;
+
+    // stop after a full cycle without transformation
+    while unchanged < t {
+        let mut transformed = false;
+        let vj_vj = dot(&basis_v[j], &basis_v[j]);
+        for i in 0..t {
+            if i == j {
+                continue;
+            }
+            let vi_vj = dot(&basis_v[i], &basis_v[j]);
+            if 2 * vi_vj.abs() > vj_vj {
+                let q = div_round(vi_vj, vj_vj);
+                for k in 0..t {
+                    basis_v[i][k] -= q * basis_v[j][k];
+                    basis_u[j][k] += q * basis_u[i][k];
+                }
+                *s = (*s).min(dot(&basis_u[j], &basis_u[j]));
+                transformed = true;
+            }
+        }
+
+        unchanged = if transformed { 0 } else { unchanged + 1 };
+        j = (j + 1) % t;
+    }
+}
+
+/// Exhaustive search of the shortest nonzero $\sum x_j U_j$
+fn search(basis_u: &[Vec<i128>], basis_v: &[Vec<i128>], m: i128, s: &mut i128) {
+    let t = basis_u.len();
+    // |x_j| <= sqrt(s V_j.V_j) / m, computed in floating point with a margin of one
+    let bounds: Vec<i128> = basis_v
+        .iter()
+        .map(|v| ((*s as f64 * dot(v, v) as f64).sqrt() / m as f64) as i128 + 1)
+        .collect();
+
+    // by symmetry only vectors whose first nonzero coordinate is positive are needed
+    let mut y = vec![0i128; t];
+    search_level(basis_u, &bounds, 0, &mut y, false, s);
+}
+
+fn search_level(
+    basis_u: &[Vec<i128>],
+    bounds: &[i128],
+    level: usize,
+    y: &mut [i128],
+    nonzero: bool,
+    s: &mut i128,
+) {
+    if level == basis_u.len() {
+        if nonzero {
+            *s = (*s).min(dot(y, y));
+        }
+        return;
+    }
+
+    let low = if nonzero { -bounds[level] } else { 0 };
+    for value in low..=bounds[level] {
+        for (y, u) in y.iter_mut().zip(basis_u[level].iter()) {
+            *y += value * u;
+        }
+        search_level(basis_u, bounds, level + 1, y, nonzero || value != 0, s);
+        for (y, u) in y.iter_mut().zip(basis_u[level].iter()) {
+            *y -= value * u;
+        }
+    }
+}
+
+fn dot(a: &[i128], b: &[i128]) -> i128 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+/// `a / b` rounded to the nearest integer
+fn div_round(a: i128, b: i128) -> i128 {
+    let (a, b) = if b < 0 { (-a, -b) } else { (a, b) };
+    (2 * a + b).div_euclid(2 * b)
+}
+
+#[test]
+fn test_spectral_test() {
+    // RANDU: x_{n+1} = 65539 x_n mod 2^31, every triple lies on one of 15 planes
+    // since 9 x_n - 6 x_{n+1} + x_{n+2} = 0 mod 2^31
+    let results = spectral_test(65539, 1 << 31, 3).unwrap();
+    assert_eq!(results[1].nu_squared, 118);
+    assert!(results[0].passed());
+    assert!(!results[1].passed());
+
+    // brute force over the dual lattice for a small modulus
+    let (a, m) = (137u64, 256u64);
+    let results = spectral_test(a, m, 4).unwrap();
+    for result in &results {
+        let t = result.dimension;
+        let mut best = u128::MAX;
+        let range = -16i64..=16;
+        let mut s = vec![-16i64; t];
+        loop {
+            let value = s
+                .iter()
+                .rev()
+                .fold(0i64, |acc, &x| (acc * a as i64 + x).rem_euclid(m as i64));
+            let norm: i64 = s.iter().map(|x| x * x).sum();
+            if value == 0 && norm > 0 {
+                best = best.min(norm as u128);
+            }
+            // next vector of the box
+            let Some(i) = s.iter().position(|&x| x < *range.end()) else {
+                break;
+            };
+            s[i] += 1;
+            s[..i].iter_mut().for_each(|x| *x = *range.start());
+        }
+        assert_eq!(result.nu_squared, best, "dimension {t}");
+    }
+
+    let lcg = LCG::new(1, 1 << 31, 65539, 0);
+    assert_eq!(lcg.spectral_test(3).unwrap()[1].nu_squared, 118);
+
+    assert_eq!(
+        spectral_test(3, 101, 9).unwrap_err(),
+        TestError::InvalidParameter("Spectral test", 9)
+    );
+}
@@ -0,0 +1,63 @@
+/// Common interface of the random number generators in this module
+///
+/// Only [`Rng::next_u64`] is required, the other methods are derived from it.
+/// Generators that naturally produce fewer bits per step (e.g. Blum Blum Shub)
+/// buffer their output until 64 bits are available.
+pub trait Rng {
+    /// Next 64 bits of output
+    fn next_u64(&mut self) -> u64;
+
+    /// Next 32 bits of output, taken from the low half of [`Rng::next_u64`]
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    /// Fill `dest` with output bytes, big-endian within each 64-bit word
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let word = self.next_u64().to_be_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+    }
+
+    /// Uniform float in $[0, 1)$ from the top 53 bits of [`Rng::next_u64`]
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform integer in $[0, bound)$, without modulo bias
+    ///
+    /// Outputs in the last incomplete multiple of `bound` are rejected, so
+    /// every remainder is equally likely. Panics if `bound` is 0.
+    fn next_below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "bound must be positive");
+        let limit = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < limit {
+                return value % bound;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_rng_helpers() {
+    use super::splitmix64::SplitMix64;
+
+    let mut rng = SplitMix64::new(42);
+    let mut counts = [0; 6];
+    for _ in 0..6000 {
+        counts[rng.next_below(6) as usize] += 1;
+    }
+    assert!(
+        counts.iter().all(|&c| (900..1100).contains(&c)),
+        "{counts:?}"
+    );
+
+    for _ in 0..1000 {
+        let x = rng.next_f64();
+        assert!((0.0..1.0).contains(&x));
+    }
+    assert_eq!(rng.next_below(1), 0);
+}
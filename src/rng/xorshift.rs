@@ -0,0 +1,249 @@
+// Marsaglia's xorshift generators
+use thiserror::Error;
+
+use super::traits::Rng;
+use crate::math::linear_algebra::gf2::{Gf2Error, Gf2System};
+
+/// xorshift64 with the shift triple (13, 7, 17)
+///
+/// ```text
+/// x ^= x << 13; x ^= x >> 7; x ^= x << 17
+/// ```
+///
+/// Every step is a linear map of the state over GF(2), with period
+/// $2^{64} - 1$ on nonzero states. The output is the state itself.
+///
+/// # See also
+/// - Marsaglia, George. "Xorshift RNGs." Journal of Statistical Software, 2003.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Xorshift64 {
+    state: u64,
+}
+
+impl Xorshift64 {
+    pub fn new(seed: u64) -> Result<Self, XorshiftError> {
+        if seed == 0 {
+            return Err(XorshiftError::ZeroState);
+        }
+        Ok(Self { state: seed })
+    }
+
+    /// Clone a generator from bit `bit` of 64 or more consecutive outputs
+    ///
+    /// Every output bit is a known linear combination of the 64 seed bits, so
+    /// the observed bits form a linear system over GF(2). The returned
+    /// generator continues after the observed outputs.
+    pub fn predict_from_bits(observed: &[u8], bit: u32) -> Result<Self, XorshiftError> {
+        if bit >= 64 {
+            return Err(XorshiftError::InvalidBit(bit));
+        }
+
+        let mut x = symbolic_word(0, 64);
+        let equations = observed.iter().map(|&value| {
+            x = xor(&x, &shl(&x, 13));
+            x = xor(&x, &shr(&x, 7));
+            x = xor(&x, &shl(&x, 17));
+            (x[bit as usize], value)
+        });
+        let seed = solve_state(equations, 64)? as u64;
+
+        let mut rng = Self::new(seed)?;
+        for _ in observed {
+            rng.next_u64();
+        }
+        Ok(rng)
+    }
+}
+
+impl Rng for Xorshift64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
+
+/// xor128: xorshift on four 32-bit words
+///
+/// ```text
+/// t = x ^ (x << 11)
+/// x = y; y = z; z = w
+/// w = w ^ (w >> 19) ^ t ^ (t >> 8)
+/// ```
+///
+/// Period $2^{128} - 1$, the output is the new $w$. Like every xorshift it is
+/// linear over GF(2): even the least significant bit of the outputs alone
+/// reveals the whole state after 128 outputs, see
+/// [`Xorshift128::predict_from_bits`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Xorshift128 {
+    state: [u32; 4],
+}
+
+impl Xorshift128 {
+    /// Seeds of Marsaglia's paper
+    pub const DEFAULT_STATE: [u32; 4] = [123456789, 362436069, 521288629, 88675123];
+
+    pub fn new(state: [u32; 4]) -> Result<Self, XorshiftError> {
+        if state == [0; 4] {
+            return Err(XorshiftError::ZeroState);
+        }
+        Ok(Self { state })
+    }
+
+    pub fn next_word(&mut self) -> u32 {
+        let [x, y, z, w] = self.state;
+        let t = x ^ (x << 11);
+        let next = w ^ (w >> 19) ^ t ^ (t >> 8);
+        self.state = [y, z, w, next];
+        next
+    }
+
+    /// Clone a generator from bit `bit` of 128 or more consecutive outputs
+    ///
+    /// The state words are tracked symbolically: bit $i$ of each word is the
+    /// set of seed bits whose xor it equals. Each observed bit gives one
+    /// equation over GF(2), and 128 independent equations determine the seed.
+    /// The returned generator continues after the observed outputs.
+    pub fn predict_from_bits(observed: &[u8], bit: u32) -> Result<Self, XorshiftError> {
+        if bit >= 32 {
+            return Err(XorshiftError::InvalidBit(bit));
+        }
+
+        let mut state: Vec<Symbolic> = (0..4).map(|i| symbolic_word(32 * i, 32)).collect();
+        let equations = observed.iter().map(|&value| {
+            let t = xor(&state[0], &shl(&state[0], 11));
+            let w = &state[3];
+            let w = xor(&xor(w, &shr(w, 19)), &xor(&t, &shr(&t, 8)));
+            state.rotate_left(1);
+            state[3] = w;
+            (state[3][bit as usize], value)
+        });
+        let seed = solve_state(equations, 128)?;
+
+        let words = [0, 1, 2, 3].map(|i| (seed >> (32 * i)) as u32);
+        let mut rng = Self::new(words)?;
+        for _ in observed {
+            rng.next_word();
+        }
+        Ok(rng)
+    }
+}
+
+impl Rng for Xorshift128 {
+    fn next_u32(&mut self) -> u32 {
+        self.next_word()
+    }
+
+    /// Two consecutive outputs, the first one in the low half
+    fn next_u64(&mut self) -> u64 {
+        let low = self.next_word() as u64;
+        let high = self.next_word() as u64;
+        (high << 32) | low
+    }
+}
+
+/// Bits of a word as linear combinations of the seed bits, bit `i` of a mask is seed bit `i`
+type Symbolic = Vec<u128>;
+
+/// Word made of the seed bits `offset..offset + width`
+fn symbolic_word(offset: usize, width: usize) -> Symbolic {
+    (0..width).map(|i| 1 << (offset + i)).collect()
+}
+
+fn xor(a: &Symbolic, b: &Symbolic) -> Symbolic {
+    a.iter().zip(b.iter()).map(|(a, b)| a ^ b).collect()
+}
+
+fn shl(a: &Symbolic, k: usize) -> Symbolic {
+    (0..a.len())
+        .map(|i| if i >= k { a[i - k] } else { 0 })
+        .collect()
+}
+
+fn shr(a: &Symbolic, k: usize) -> Symbolic {
+    (0..a.len())
+        .map(|i| a.get(i + k).copied().unwrap_or(0))
+        .collect()
+}
+
+/// Solve the equations (seed bits mask, observed value) for the seed
+fn solve_state(
+    equations: impl Iterator<Item = (u128, u8)>,
+    vars: usize,
+) -> Result<u128, XorshiftError> {
+    let mut system = Gf2System::new(vars);
+    for (mask, value) in equations {
+        system.add_equation(&[mask as u64, (mask >> 64) as u64], value);
+    }
+
+    Ok(system
+        .solve()?
+        .iter()
+        .enumerate()
+        .fold(0, |seed, (i, &bit)| seed | (bit as u128) << i))
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum XorshiftError {
+    #[error("The state must not be all zero")]
+    ZeroState,
+
+    #[error("Invalid bit index {0}")]
+    InvalidBit(u32),
+
+    #[error("Cannot recover the state: {0}")]
+    Unsolvable(#[from] Gf2Error),
+}
+
+#[test]
+fn test_xorshift() {
+    let mut rng = Xorshift64::new(88172645463325252).unwrap();
+    let outputs: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+    assert_eq!(
+        outputs,
+        vec![
+            8748534153485358512,
+            3040900993826735515,
+            3453997556048239312,
+            16431732851926010853,
+        ]
+    );
+
+    let mut rng = Xorshift128::new(Xorshift128::DEFAULT_STATE).unwrap();
+    let outputs: Vec<u32> = (0..4).map(|_| rng.next_u32()).collect();
+    assert_eq!(outputs, vec![3701687786, 458299110, 2500872618, 3633119408]);
+
+    assert_eq!(Xorshift64::new(0).unwrap_err(), XorshiftError::ZeroState);
+    assert_eq!(
+        Xorshift128::new([0; 4]).unwrap_err(),
+        XorshiftError::ZeroState
+    );
+}
+
+#[test]
+fn test_xorshift_prediction() {
+    // the least significant bit of 200 outputs is enough to clone the generator
+    let mut rng = Xorshift128::new([0xdeadbeef, 0x01234567, 0x89abcdef, 0x0badf00d]).unwrap();
+    let low_bits: Vec<u8> = (0..200).map(|_| (rng.next_u32() & 1) as u8).collect();
+    let mut clone = Xorshift128::predict_from_bits(&low_bits, 0).unwrap();
+    for _ in 0..100 {
+        assert_eq!(clone.next_u32(), rng.next_u32());
+    }
+
+    let mut rng = Xorshift64::new(0x0123_4567_89ab_cdef).unwrap();
+    let high_bits: Vec<u8> = (0..100).map(|_| (rng.next_u64() >> 63) as u8).collect();
+    let mut clone = Xorshift64::predict_from_bits(&high_bits, 63).unwrap();
+    assert_eq!(clone.next_u64(), rng.next_u64());
+
+    // too few outputs leave the state undetermined
+    assert!(matches!(
+        Xorshift128::predict_from_bits(&low_bits[..100], 0),
+        Err(XorshiftError::Unsolvable(Gf2Error::Underdetermined(..)))
+    ));
+    assert_eq!(
+        Xorshift128::predict_from_bits(&low_bits, 32).unwrap_err(),
+        XorshiftError::InvalidBit(32)
+    );
+}
@@ -0,0 +1,199 @@
+// xoshiro256**
+use thiserror::Error;
+
+use super::{splitmix64::SplitMix64, traits::Rng};
+
+/// xoshiro256** generator
+///
+/// The 256-bit state $s_0, s_1, s_2, s_3$ goes through a linear
+/// transformation over GF(2) (xor, shift, rotate) with period $2^{256} - 1$:
+///
+/// ```text
+/// t = s1 << 17
+/// s2 ^= s0; s3 ^= s1; s1 ^= s2; s0 ^= s3
+/// s2 ^= t; s3 = s3 <<< 45
+/// ```
+///
+/// The output $((s_1 \cdot 5) \lll 7) \cdot 9$ is computed before the step
+/// and scrambles the linear state, so the low bits pass the linearity tests
+/// that plain xorshift fails.
+///
+/// Since the transformation is linear, jumping ahead by $2^{128}$ or
+/// $2^{192}$ steps is a fixed combination of the next 256 states, given by
+/// the coefficients of $x^{2^{128}} \bmod P(x)$ with $P$ the characteristic
+/// polynomial. Jumping splits the sequence into non-overlapping streams.
+///
+/// # See also
+/// - Blackman and Vigna. "Scrambled Linear Pseudorandom Number Generators." ACM TOMS 2021.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Xoshiro256StarStar {
+    s: [u64; 4],
+}
+
+impl Xoshiro256StarStar {
+    const JUMP: [u64; 4] = [
+        0x180e_c6d3_3cfd_0aba,
+        0xd5a6_1266_f0c9_392c,
+        0xa958_2618_e03f_c9aa,
+        0x39ab_dc45_29b1_661c,
+    ];
+
+    const LONG_JUMP: [u64; 4] = [
+        0x76e1_5d3e_fefd_cbbf,
+        0xc500_4e44_1c52_2fb3,
+        0x7771_0069_854e_e241,
+        0x3910_9bb0_2acb_e635,
+    ];
+
+    pub fn new(state: [u64; 4]) -> Result<Self, XoshiroError> {
+        if state == [0; 4] {
+            return Err(XoshiroError::ZeroState);
+        }
+        Ok(Self { s: state })
+    }
+
+    /// Expand a 64-bit seed with [`SplitMix64`], as recommended by the authors
+    pub fn seed_from_u64(seed: u64) -> Self {
+        let mut splitmix = SplitMix64::new(seed);
+        // SplitMix64 is a bijection of its state, four outputs are never all zero
+        Self {
+            s: [(); 4].map(|_| splitmix.next_u64()),
+        }
+    }
+
+    pub fn state(&self) -> [u64; 4] {
+        self.s
+    }
+
+    /// Advance by $2^{128}$ steps, gives $2^{128}$ non-overlapping streams
+    pub fn jump(&mut self) {
+        self.apply_jump(&Self::JUMP);
+    }
+
+    /// Advance by $2^{192}$ steps, gives $2^{64}$ starting points for [`Xoshiro256StarStar::jump`]
+    pub fn long_jump(&mut self) {
+        self.apply_jump(&Self::LONG_JUMP);
+    }
+
+    /// Return a generator at the current position and jump this one ahead
+    ///
+    /// Every call yields a stream of $2^{128}$ outputs that do not overlap with the others.
+    pub fn split(&mut self) -> Self {
+        let stream = self.clone();
+        self.jump();
+        stream
+    }
+
+    fn apply_jump(&mut self, polynomial: &[u64; 4]) {
+        let mut s = [0; 4];
+        for word in polynomial {
+            for bit in 0..64 {
+                if (word >> bit) & 1 == 1 {
+                    for (acc, x) in s.iter_mut().zip(self.s.iter()) {
+                        *acc ^= x;
+                    }
+                }
+                self.step();
+            }
+        }
+        self.s = s;
+    }
+
+    fn step(&mut self) {
+        let t = self.s[1] << 17;
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+    }
+}
+
+impl Rng for Xoshiro256StarStar {
+    fn next_u64(&mut self) -> u64 {
+        let result = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        self.step();
+        result
+    }
+
+    /// High half of the output, the low bits are slightly weaker
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum XoshiroError {
+    #[error("The state must not be all zero")]
+    ZeroState,
+}
+
+#[test]
+fn test_xoshiro256starstar() {
+    let mut rng = Xoshiro256StarStar::new([1, 2, 3, 4]).unwrap();
+    let outputs: Vec<u64> = (0..6).map(|_| rng.next_u64()).collect();
+    assert_eq!(
+        outputs,
+        vec![
+            11520,
+            0,
+            1509978240,
+            1215971899390074240,
+            1216172134540287360,
+            607988272756665600,
+        ]
+    );
+
+    let mut rng = Xoshiro256StarStar::seed_from_u64(0);
+    assert_eq!(
+        rng.state(),
+        [
+            16294208416658607535,
+            7960286522194355700,
+            487617019471545679,
+            17909611376780542444,
+        ]
+    );
+    assert_ne!(rng.next_u64(), rng.next_u64());
+
+    assert_eq!(
+        Xoshiro256StarStar::new([0; 4]).unwrap_err(),
+        XoshiroError::ZeroState
+    );
+}
+
+#[test]
+fn test_xoshiro256starstar_jump() {
+    let mut rng = Xoshiro256StarStar::new([1, 2, 3, 4]).unwrap();
+    rng.jump();
+    assert_eq!(
+        rng.state(),
+        [
+            10122426448480695249,
+            8079205330032121950,
+            7289065458748526725,
+            9477464255293849680,
+        ]
+    );
+    assert_eq!(rng.next_u64(), 13534147089533256664);
+
+    let mut rng = Xoshiro256StarStar::new([1, 2, 3, 4]).unwrap();
+    rng.long_jump();
+    assert_eq!(
+        rng.state(),
+        [
+            678511610814637056,
+            15850499779492529430,
+            6002989639035333134,
+            3559352929785830385,
+        ]
+    );
+
+    // split streams start 2^128 steps apart
+    let mut rng = Xoshiro256StarStar::new([1, 2, 3, 4]).unwrap();
+    let mut first = rng.split();
+    let mut second = rng.split();
+    assert_eq!(first.next_u64(), 11520);
+    assert_eq!(second.next_u64(), 13534147089533256664);
+}
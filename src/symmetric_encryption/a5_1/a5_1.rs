@@ -0,0 +1,225 @@
+use crate::common::keystream::KeystreamBuffer;
+use crate::symmetric_encryption::traits::{seek_by_discarding, SeekError, StreamCipher};
+use thiserror::Error;
+
+/// A register of A5/1: its length, feedback taps and clocking bit
+struct Register {
+    len: u32,
+    taps: u32,
+    clocking_bit: u32,
+}
+
+// R1: x^19 + x^18 + x^17 + x^14 + 1, clocked by bit 8
+const R1: Register = Register {
+    len: 19,
+    taps: 1 << 13 | 1 << 16 | 1 << 17 | 1 << 18,
+    clocking_bit: 8,
+};
+
+// R2: x^22 + x^21 + 1, clocked by bit 10
+const R2: Register = Register {
+    len: 22,
+    taps: 1 << 20 | 1 << 21,
+    clocking_bit: 10,
+};
+
+// R3: x^23 + x^22 + x^21 + x^8 + 1, clocked by bit 10
+const R3: Register = Register {
+    len: 23,
+    taps: 1 << 7 | 1 << 20 | 1 << 21 | 1 << 22,
+    clocking_bit: 10,
+};
+
+impl Register {
+    /// Shift `value` left by one, the parity of the taps entering at bit 0
+    fn clock(&self, value: u32) -> u32 {
+        let feedback = (value & self.taps).count_ones() & 1;
+        (value << 1 | feedback) & ((1 << self.len) - 1)
+    }
+
+    fn clocking_bit(&self, value: u32) -> u32 {
+        value >> self.clocking_bit & 1
+    }
+
+    fn output_bit(&self, value: u32) -> u32 {
+        value >> (self.len - 1) & 1
+    }
+}
+
+/// GSM A5/1 stream cipher, 64-bit key and 22-bit frame number
+///
+/// Three LFSRs of 19, 22 and 23 bits are clocked irregularly: at each clock,
+/// the registers whose clocking bit agrees with the majority of the three
+/// clocking bits move, the others stay. At least two registers move at every
+/// clock, each with probability $3/4$. The output bit is the XOR of the most
+/// significant bits of the registers.
+///
+/// GSM sets the cipher up for every TDMA frame and uses 228 keystream bits
+/// per frame, 114 for each direction, see [`A51::bursts`].
+#[derive(Debug, Clone)]
+pub struct A51 {
+    r1: u32,
+    r2: u32,
+    r3: u32,
+    /// Number of majority clocks since the mixing started
+    count: u64,
+    /// Output bits of the last clocks not used yet
+    buffer: KeystreamBuffer,
+}
+
+impl A51 {
+    /// Number of majority clocks run without output after loading the key and frame
+    pub const MIXING_CLOCKS: u64 = 100;
+
+    /// Number of keystream bits of a burst, for one direction of a frame
+    pub const BURST_BITS: u32 = 114;
+
+    /// A5/1 from a 64-bit key and a frame number, in the byte order of the
+    /// reference implementation of Briceno, Goldberg and Wagner
+    ///
+    /// The registers start at zero. The key bits, `key[i / 8] >> (i % 8) & 1`,
+    /// then the 22 frame bits, least significant first, are XORed into bit 0
+    /// of every register after a regular clock of all three. 100 majority
+    /// clocks follow, their output being discarded.
+    pub fn new(key: &[u8; 8], frame: u32) -> Result<Self, A51Error> {
+        if frame >= 1 << 22 {
+            return Err(A51Error::InvalidFrame(frame));
+        }
+
+        let mut a51 = Self {
+            r1: 0,
+            r2: 0,
+            r3: 0,
+            count: 0,
+            buffer: KeystreamBuffer::new(),
+        };
+        let key_bits = (0..64).map(|i| (key[i / 8] >> (i % 8) & 1) as u32);
+        let frame_bits = (0..22).map(|i| frame >> i & 1);
+        for bit in key_bits.chain(frame_bits) {
+            a51.r1 = R1.clock(a51.r1) ^ bit;
+            a51.r2 = R2.clock(a51.r2) ^ bit;
+            a51.r3 = R3.clock(a51.r3) ^ bit;
+        }
+
+        for _ in 0..Self::MIXING_CLOCKS {
+            a51.clock();
+        }
+        Ok(a51)
+    }
+
+    /// The two 114-bit bursts of `frame`, each packed in 15 bytes, the first
+    /// bit in the most significant bit of the first byte
+    ///
+    /// The first burst encrypts the traffic from the network to the handset,
+    /// the second one the traffic in the other direction.
+    pub fn bursts(key: &[u8; 8], frame: u32) -> Result<([u8; 15], [u8; 15]), A51Error> {
+        let mut a51 = Self::new(key, frame)?;
+        let half = Self::BURST_BITS / 2;
+        let mut burst = || {
+            let high = a51.next_bits(half) as u128;
+            let low = a51.next_bits(half) as u128;
+            // 114 bits followed by 6 zeros
+            let bytes = ((high << half | low) << 6).to_be_bytes();
+            <[u8; 15]>::try_from(&bytes[1..]).unwrap()
+        };
+        Ok((burst(), burst()))
+    }
+
+    /// XOR `data` with the next keystream bytes, encrypting or decrypting it in place
+    ///
+    /// Each keystream byte holds 8 output bits, the first one in the most
+    /// significant bit, as in the bursts.
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            *byte ^= self.next_bits(8) as u8;
+        }
+    }
+
+    /// Number of keystream bits produced so far
+    pub fn position(&self) -> u64 {
+        self.count - Self::MIXING_CLOCKS - self.buffer.len() as u64
+    }
+
+    /// The next `n` output bits, $1 \le n \le 64$, the first one in bit `n - 1`
+    fn next_bits(&mut self, n: u32) -> u64 {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        let bits = buffer.next_bits(n, || {
+            // the majority rule is sequential: one clock at a time
+            let bits = (0..64).fold(0, |bits, _| bits << 1 | self.clock() as u64);
+            (bits, 64)
+        });
+        self.buffer = buffer;
+        bits
+    }
+
+    /// One majority clock, returning the output bit
+    fn clock(&mut self) -> u32 {
+        let c1 = R1.clocking_bit(self.r1);
+        let c2 = R2.clocking_bit(self.r2);
+        let c3 = R3.clocking_bit(self.r3);
+        let majority = (c1 & c2) | (c1 & c3) | (c2 & c3);
+
+        if c1 == majority {
+            self.r1 = R1.clock(self.r1);
+        }
+        if c2 == majority {
+            self.r2 = R2.clock(self.r2);
+        }
+        if c3 == majority {
+            self.r3 = R3.clock(self.r3);
+        }
+
+        self.count += 1;
+        R1.output_bit(self.r1) ^ R2.output_bit(self.r2) ^ R3.output_bit(self.r3)
+    }
+}
+
+impl StreamCipher for A51 {
+    fn apply_keystream(&mut self, data: &mut [u8]) {
+        A51::apply_keystream(self, data);
+    }
+
+    fn seek(&mut self, position: u64) -> Result<(), SeekError> {
+        seek_by_discarding(self, position)
+    }
+
+    /// [`A51::position`] counts bits
+    fn current_pos(&self) -> u64 {
+        self.position() / 8
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum A51Error {
+    #[error("Invalid frame number: {0} does not fit in 22 bits")]
+    InvalidFrame(u32),
+}
+
+#[test]
+fn test_a5_1() {
+    use crate::common::hex::Hex;
+    // test vector of the reference implementation of Briceno, Goldberg and Wagner
+    let key = [0x12, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF];
+    let (a_to_b, b_to_a) = A51::bursts(&key, 0x134).unwrap();
+    assert_eq!(
+        a_to_b.to_vec(),
+        Hex::hex_to_bytes(b"534EAA582FE8151AB6E1855A728C00").unwrap()
+    );
+    assert_eq!(
+        b_to_a.to_vec(),
+        Hex::hex_to_bytes(b"24FD35A35D5FB6526D32F906DF1AC0").unwrap()
+    );
+
+    // the keystream continues across calls, the bursts being its first 228 bits
+    let mut a51 = A51::new(&key, 0x134).unwrap();
+    let mut keystream = [0; 14];
+    a51.apply_keystream(&mut keystream[..5]);
+    a51.apply_keystream(&mut keystream[5..]);
+    assert_eq!(keystream, a_to_b[..14]);
+    assert_eq!(a51.position(), 112);
+
+    assert_eq!(
+        A51::new(&key, 1 << 22).unwrap_err(),
+        A51Error::InvalidFrame(1 << 22)
+    );
+}
@@ -0,0 +1,295 @@
+use thiserror::Error;
+
+/// AES block cipher (FIPS 197) with 128, 192 or 256-bit keys
+///
+/// The 16-byte block is viewed as a 4x4 state of bytes filled column by
+/// column. Every round applies:
+/// - SubBytes: the S-box to every byte (confusion)
+/// - ShiftRows: rotate row r of the state left by r positions
+/// - MixColumns: multiply every column by a fixed polynomial over GF(2^8) (diffusion)
+/// - AddRoundKey: xor with the round key
+///
+/// The last round skips MixColumns.
+#[derive(Debug, Clone)]
+pub struct Aes {
+    round_keys: Vec<[u8; 16]>,
+}
+
+impl Aes {
+    const S_BOX: [u8; 256] = [
+        0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab,
+        0x76, 0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4,
+        0x72, 0xc0, 0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71,
+        0xd8, 0x31, 0x15, 0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2,
+        0xeb, 0x27, 0xb2, 0x75, 0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6,
+        0xb3, 0x29, 0xe3, 0x2f, 0x84, 0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb,
+        0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf, 0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45,
+        0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8, 0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5,
+        0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2, 0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44,
+        0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73, 0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a,
+        0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb, 0xe0, 0x32, 0x3a, 0x0a, 0x49,
+        0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79, 0xe7, 0xc8, 0x37, 0x6d,
+        0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08, 0xba, 0x78, 0x25,
+        0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a, 0x70, 0x3e,
+        0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e, 0xe1,
+        0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+        0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb,
+        0x16,
+    ];
+
+    const INV_S_BOX: [u8; 256] = [
+        0x52, 0x09, 0x6a, 0xd5, 0x30, 0x36, 0xa5, 0x38, 0xbf, 0x40, 0xa3, 0x9e, 0x81, 0xf3, 0xd7,
+        0xfb, 0x7c, 0xe3, 0x39, 0x82, 0x9b, 0x2f, 0xff, 0x87, 0x34, 0x8e, 0x43, 0x44, 0xc4, 0xde,
+        0xe9, 0xcb, 0x54, 0x7b, 0x94, 0x32, 0xa6, 0xc2, 0x23, 0x3d, 0xee, 0x4c, 0x95, 0x0b, 0x42,
+        0xfa, 0xc3, 0x4e, 0x08, 0x2e, 0xa1, 0x66, 0x28, 0xd9, 0x24, 0xb2, 0x76, 0x5b, 0xa2, 0x49,
+        0x6d, 0x8b, 0xd1, 0x25, 0x72, 0xf8, 0xf6, 0x64, 0x86, 0x68, 0x98, 0x16, 0xd4, 0xa4, 0x5c,
+        0xcc, 0x5d, 0x65, 0xb6, 0x92, 0x6c, 0x70, 0x48, 0x50, 0xfd, 0xed, 0xb9, 0xda, 0x5e, 0x15,
+        0x46, 0x57, 0xa7, 0x8d, 0x9d, 0x84, 0x90, 0xd8, 0xab, 0x00, 0x8c, 0xbc, 0xd3, 0x0a, 0xf7,
+        0xe4, 0x58, 0x05, 0xb8, 0xb3, 0x45, 0x06, 0xd0, 0x2c, 0x1e, 0x8f, 0xca, 0x3f, 0x0f, 0x02,
+        0xc1, 0xaf, 0xbd, 0x03, 0x01, 0x13, 0x8a, 0x6b, 0x3a, 0x91, 0x11, 0x41, 0x4f, 0x67, 0xdc,
+        0xea, 0x97, 0xf2, 0xcf, 0xce, 0xf0, 0xb4, 0xe6, 0x73, 0x96, 0xac, 0x74, 0x22, 0xe7, 0xad,
+        0x35, 0x85, 0xe2, 0xf9, 0x37, 0xe8, 0x1c, 0x75, 0xdf, 0x6e, 0x47, 0xf1, 0x1a, 0x71, 0x1d,
+        0x29, 0xc5, 0x89, 0x6f, 0xb7, 0x62, 0x0e, 0xaa, 0x18, 0xbe, 0x1b, 0xfc, 0x56, 0x3e, 0x4b,
+        0xc6, 0xd2, 0x79, 0x20, 0x9a, 0xdb, 0xc0, 0xfe, 0x78, 0xcd, 0x5a, 0xf4, 0x1f, 0xdd, 0xa8,
+        0x33, 0x88, 0x07, 0xc7, 0x31, 0xb1, 0x12, 0x10, 0x59, 0x27, 0x80, 0xec, 0x5f, 0x60, 0x51,
+        0x7f, 0xa9, 0x19, 0xb5, 0x4a, 0x0d, 0x2d, 0xe5, 0x7a, 0x9f, 0x93, 0xc9, 0x9c, 0xef, 0xa0,
+        0xe0, 0x3b, 0x4d, 0xae, 0x2a, 0xf5, 0xb0, 0xc8, 0xeb, 0xbb, 0x3c, 0x83, 0x53, 0x99, 0x61,
+        0x17, 0x2b, 0x04, 0x7e, 0xba, 0x77, 0xd6, 0x26, 0xe1, 0x69, 0x14, 0x63, 0x55, 0x21, 0x0c,
+        0x7d,
+    ];
+
+    // round constants of the key expansion, powers of x in GF(2^8)
+    const RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+    pub fn new(key: &[u8]) -> Result<Self, AesError> {
+        // Nk: key length in 32-bit words, Nr: number of rounds
+        let (nk, nr) = match key.len() {
+            16 => (4, 10),
+            24 => (6, 12),
+            32 => (8, 14),
+            len => return Err(AesError::InvalidKeyLength(len)),
+        };
+
+        Ok(Self {
+            round_keys: Self::key_expansion(key, nk, nr),
+        })
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+
+    /// Expand the key into Nr + 1 round keys
+    ///
+    /// $w_i = w_{i - Nk} \oplus temp$ where $temp = w_{i-1}$, except:
+    /// - every Nk words: $temp = SubWord(RotWord(w_{i-1})) \oplus Rcon$
+    /// - for 256-bit keys, 4 words after that: $temp = SubWord(w_{i-1})$
+    fn key_expansion(key: &[u8], nk: usize, nr: usize) -> Vec<[u8; 16]> {
+        let total_words = 4 * (nr + 1);
+        let mut words: Vec<[u8; 4]> = key
+            .chunks(4)
+            .map(|word| [word[0], word[1], word[2], word[3]])
+            .collect();
+
+        for i in nk..total_words {
+            let mut temp = words[i - 1];
+            if i % nk == 0 {
+                temp.rotate_left(1);
+                temp = temp.map(|byte| Self::S_BOX[byte as usize]);
+                temp[0] ^= Self::RCON[i / nk - 1];
+            } else if nk > 6 && i % nk == 4 {
+                temp = temp.map(|byte| Self::S_BOX[byte as usize]);
+            }
+
+            let previous = words[i - nk];
+            words.push([
+                previous[0] ^ temp[0],
+                previous[1] ^ temp[1],
+                previous[2] ^ temp[2],
+                previous[3] ^ temp[3],
+            ]);
+        }
+
+        words
+            .chunks(4)
+            .map(|round_words| {
+                let mut round_key = [0; 16];
+                for (i, word) in round_words.iter().enumerate() {
+                    round_key[4 * i..4 * i + 4].copy_from_slice(word);
+                }
+                round_key
+            })
+            .collect()
+    }
+
+    pub fn encrypt_block(&self, block: &[u8; 16]) -> [u8; 16] {
+        let mut state = *block;
+        let rounds = self.rounds();
+
+        Self::add_round_key(&mut state, &self.round_keys[0]);
+        for round in 1..rounds {
+            Self::sub_bytes(&mut state, &Self::S_BOX);
+            Self::shift_rows(&mut state);
+            Self::mix_columns(&mut state);
+            Self::add_round_key(&mut state, &self.round_keys[round]);
+        }
+        Self::sub_bytes(&mut state, &Self::S_BOX);
+        Self::shift_rows(&mut state);
+        Self::add_round_key(&mut state, &self.round_keys[rounds]);
+
+        state
+    }
+
+    /// Run the inverse steps in the reverse order
+    pub fn decrypt_block(&self, block: &[u8; 16]) -> [u8; 16] {
+        let mut state = *block;
+        let rounds = self.rounds();
+
+        Self::add_round_key(&mut state, &self.round_keys[rounds]);
+        for round in (1..rounds).rev() {
+            Self::inv_shift_rows(&mut state);
+            Self::sub_bytes(&mut state, &Self::INV_S_BOX);
+            Self::add_round_key(&mut state, &self.round_keys[round]);
+            Self::inv_mix_columns(&mut state);
+        }
+        Self::inv_shift_rows(&mut state);
+        Self::sub_bytes(&mut state, &Self::INV_S_BOX);
+        Self::add_round_key(&mut state, &self.round_keys[0]);
+
+        state
+    }
+
+    fn add_round_key(state: &mut [u8; 16], round_key: &[u8; 16]) {
+        for (byte, key_byte) in state.iter_mut().zip(round_key.iter()) {
+            *byte ^= key_byte;
+        }
+    }
+
+    fn sub_bytes(state: &mut [u8; 16], s_box: &[u8; 256]) {
+        for byte in state.iter_mut() {
+            *byte = s_box[*byte as usize];
+        }
+    }
+
+    /// Byte `4 * c + r` is row r of column c, row r moves r columns to the left
+    fn shift_rows(state: &mut [u8; 16]) {
+        let copy = *state;
+        for c in 0..4 {
+            for r in 1..4 {
+                state[4 * c + r] = copy[4 * ((c + r) % 4) + r];
+            }
+        }
+    }
+
+    fn inv_shift_rows(state: &mut [u8; 16]) {
+        let copy = *state;
+        for c in 0..4 {
+            for r in 1..4 {
+                state[4 * ((c + r) % 4) + r] = copy[4 * c + r];
+            }
+        }
+    }
+
+    /// Multiply every column by $\{03\}x^3 + \{01\}x^2 + \{01\}x + \{02\}$
+    fn mix_columns(state: &mut [u8; 16]) {
+        for column in state.chunks_mut(4) {
+            let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+            column[0] = gf_mul(a0, 2) ^ gf_mul(a1, 3) ^ a2 ^ a3;
+            column[1] = a0 ^ gf_mul(a1, 2) ^ gf_mul(a2, 3) ^ a3;
+            column[2] = a0 ^ a1 ^ gf_mul(a2, 2) ^ gf_mul(a3, 3);
+            column[3] = gf_mul(a0, 3) ^ a1 ^ a2 ^ gf_mul(a3, 2);
+        }
+    }
+
+    /// Multiply every column by $\{0b\}x^3 + \{0d\}x^2 + \{09\}x + \{0e\}$
+    fn inv_mix_columns(state: &mut [u8; 16]) {
+        for column in state.chunks_mut(4) {
+            let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+            column[0] = gf_mul(a0, 14) ^ gf_mul(a1, 11) ^ gf_mul(a2, 13) ^ gf_mul(a3, 9);
+            column[1] = gf_mul(a0, 9) ^ gf_mul(a1, 14) ^ gf_mul(a2, 11) ^ gf_mul(a3, 13);
+            column[2] = gf_mul(a0, 13) ^ gf_mul(a1, 9) ^ gf_mul(a2, 14) ^ gf_mul(a3, 11);
+            column[3] = gf_mul(a0, 11) ^ gf_mul(a1, 13) ^ gf_mul(a2, 9) ^ gf_mul(a3, 14);
+        }
+    }
+}
+
+/// Multiplication in GF(2^8) modulo $x^8 + x^4 + x^3 + x + 1$ (shift-and-add)
+pub fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        // multiply a by x, reducing when the degree overflows
+        let carry = a & 0x80;
+        a <<= 1;
+        if carry != 0 {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AesError {
+    #[error("Invalid key length: expected 16, 24 or 32 bytes, got {0} bytes")]
+    InvalidKeyLength(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Aes, AesError};
+    use crate::common::hex::Hex;
+
+    fn block(hex: &str) -> [u8; 16] {
+        Hex::hex_to_bytes(hex.as_bytes())
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_encrypt() {
+        // FIPS 197 appendix B
+        let key = Hex::hex_to_bytes(b"2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+        let aes = Aes::new(&key).unwrap();
+        assert_eq!(
+            aes.encrypt_block(&block("3243f6a8885a308d313198a2e0370734")),
+            block("3925841d02dc09fbdc118597196a0b32")
+        );
+
+        // FIPS 197 appendix C
+        let plain_text = block("00112233445566778899aabbccddeeff");
+        let cases = [
+            (
+                "000102030405060708090a0b0c0d0e0f",
+                "69c4e0d86a7b0430d8cdb78070b4c55a",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f1011121314151617",
+                "dda97ca4864cdfe06eaf70a0ec0d7191",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+                "8ea2b7ca516745bfeafc49904b496089",
+            ),
+        ];
+        for (key, cipher_text) in cases {
+            let aes = Aes::new(&Hex::hex_to_bytes(key.as_bytes()).unwrap()).unwrap();
+            assert_eq!(aes.encrypt_block(&plain_text), block(cipher_text));
+            assert_eq!(aes.decrypt_block(&block(cipher_text)), plain_text);
+        }
+    }
+
+    #[test]
+    fn test_rounds() {
+        assert_eq!(Aes::new(&[0; 16]).unwrap().rounds(), 10);
+        assert_eq!(Aes::new(&[0; 24]).unwrap().rounds(), 12);
+        assert_eq!(Aes::new(&[0; 32]).unwrap().rounds(), 14);
+        assert_eq!(
+            Aes::new(&[0; 8]).unwrap_err(),
+            AesError::InvalidKeyLength(8)
+        );
+    }
+}
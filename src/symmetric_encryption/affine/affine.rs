@@ -1,41 +1,112 @@
 use crate::{
-    math::modular_arithmetic::mod_inverse::mod_inverse,
-    symmetric_encryption::utils::{alphabet_size_i32, get_char_at_index, get_char_index, ALPHABET},
+    math::modular_arithmetic::{gcd::gcd, mod_inverse::mod_inverse},
+    symmetric_encryption::{
+        traits::{ClassicalCipher, ClassicalCipherError},
+        utils::{default_alphabet, substitute, Alphabet, PassThrough},
+    },
 };
 
 /// Affine Cipher Encryption
 pub fn encrypt(text: &str, a: i32, b: i32) -> Result<String, String> {
-    text.chars().try_fold(String::new(), |mut acc, c| {
-        let Some(index) = ALPHABET.find(c) else {
-            return Err("Invalid character".to_string());
-        };
+    encrypt_with(text, a, b, default_alphabet(), PassThrough::Reject)
+}
+
+/// Affine Cipher Decryption
+pub fn decrypt(cipher: &str, a: i32, b: i32) -> Result<String, String> {
+    decrypt_with(cipher, a, b, default_alphabet(), PassThrough::Reject)
+}
 
-        // new_index = (a * index as i32 + b) % alphabet_size_i32();
-        let new_index = (a * index as i32 + b).rem_euclid(alphabet_size_i32());
+/// Affine Cipher Encryption over any alphabet of $n$ characters: $y = ax + b \bmod n$
+///
+/// Uppercase characters missing from the alphabet are encrypted as their
+/// lowercase form and converted back, the other characters outside the
+/// alphabet are handled according to `policy`.
+pub fn encrypt_with(
+    text: &str,
+    a: i32,
+    b: i32,
+    alphabet: &Alphabet,
+    policy: PassThrough,
+) -> Result<String, String> {
+    let size = alphabet.len() as i64;
+    let (a, b) = ((a as i64).rem_euclid(size), (b as i64).rem_euclid(size));
 
-        let new_char = get_char_at_index(new_index)?;
+    // new_index = (a * index + b) % n
+    substitute(text, alphabet, policy, |index| {
+        (a * index as i64 + b).rem_euclid(size) as usize
+    })
+}
 
-        acc.push(new_char);
+/// Affine Cipher Decryption over any alphabet of $n$ characters: $x = a^{-1}(y - b) \bmod n$
+pub fn decrypt_with(
+    cipher: &str,
+    a: i32,
+    b: i32,
+    alphabet: &Alphabet,
+    policy: PassThrough,
+) -> Result<String, String> {
+    let size = alphabet.len() as i64;
+    let a_inverse = mod_inverse(a.rem_euclid(size as i32), size as i32)? as i64;
+    let b = (b as i64).rem_euclid(size);
 
-        Ok(acc)
+    substitute(cipher, alphabet, policy, |index| {
+        (a_inverse * (index as i64 - b)).rem_euclid(size) as usize
     })
 }
 
-/// Affine Cipher Decryption
-pub fn decrypt(cipher: &str, a: i32, b: i32) -> Result<String, String> {
-    let a_inverse = mod_inverse(a as i32, alphabet_size_i32())?;
+/// The affine cipher over an alphabet, as a [`ClassicalCipher`] keyed by $(a, b)$
+#[derive(Debug, Clone, PartialEq)]
+pub struct Affine {
+    alphabet: Alphabet,
+    policy: PassThrough,
+}
 
-    cipher.chars().try_fold(String::new(), |mut acc, c| {
-        let index = get_char_index(c)?;
+impl Affine {
+    pub fn new(alphabet: Alphabet, policy: PassThrough) -> Self {
+        Self { alphabet, policy }
+    }
+}
 
-        let new_index = (a_inverse * (index as i32 - b)).rem_euclid(alphabet_size_i32());
+impl Default for Affine {
+    /// Lowercase alphabet, other characters rejected, like [`encrypt`]
+    fn default() -> Self {
+        Self::new(default_alphabet().clone(), PassThrough::Reject)
+    }
+}
 
-        let new_char = get_char_at_index(new_index)?;
+impl ClassicalCipher for Affine {
+    type Key = (i32, i32);
 
-        acc.push(new_char);
+    /// $a$ must be invertible modulo $n$, i.e. $\gcd(a, n) = 1$
+    fn check_key(&self, &(a, _): &(i32, i32)) -> Result<(), ClassicalCipherError> {
+        let n = self.alphabet.len();
+        if gcd(a.rem_euclid(n as i32), n as i32) != 1 {
+            return Err(ClassicalCipherError::NonInvertibleKey { a, n });
+        }
+        Ok(())
+    }
 
-        Ok(acc)
-    })
+    fn encrypt(&self, text: &str, key: &(i32, i32)) -> Result<String, ClassicalCipherError> {
+        self.check_key(key)?;
+        encrypt_with(text, key.0, key.1, &self.alphabet, self.policy)
+            .map_err(|_| ClassicalCipherError::InvalidCharacter)
+    }
+
+    /// With a valid key, fails only on a character outside the alphabet,
+    /// when rejected by the policy
+    fn decrypt(&self, cipher: &str, key: &(i32, i32)) -> Result<String, ClassicalCipherError> {
+        self.check_key(key)?;
+        decrypt_with(cipher, key.0, key.1, &self.alphabet, self.policy)
+            .map_err(|_| ClassicalCipherError::InvalidCharacter)
+    }
+
+    /// The $\varphi(n) \cdot n$ keys with $0 < a < n$ invertible and $0 \le b < n$
+    fn keys(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        let n = self.alphabet.len() as i32;
+        (1..n)
+            .filter(move |&a| gcd(a, n) == 1)
+            .flat_map(move |a| (0..n).map(move |b| (a, b)))
+    }
 }
 
 #[test]
@@ -50,3 +121,90 @@ fn test_affine() {
     let decrypted_text = decrypt(cipher, a, b).unwrap();
     assert_eq!(text, &decrypted_text);
 }
+
+#[test]
+fn test_affine_alphabets() {
+    // n = 62: a must be odd and not a multiple of 31
+    let alphanumeric = Alphabet::alphanumeric();
+    let text = "Attack0900";
+    let cipher = encrypt_with(text, 5, 8, &alphanumeric, PassThrough::Reject).unwrap();
+    assert_eq!(
+        decrypt_with(&cipher, 5, 8, &alphanumeric, PassThrough::Reject).unwrap(),
+        text
+    );
+    assert!(decrypt_with(&cipher, 31, 8, &alphanumeric, PassThrough::Reject).is_err());
+
+    // n = 33: 3 and 11 are not invertible
+    let cyrillic = Alphabet::cyrillic();
+    assert_eq!(
+        encrypt_with("абв", 2, 1, &cyrillic, PassThrough::Reject).unwrap(),
+        "бге"
+    );
+    assert_eq!(
+        decrypt_with("бге", 2, 1, &cyrillic, PassThrough::Reject).unwrap(),
+        "абв"
+    );
+    assert!(decrypt_with("бге", 3, 1, &cyrillic, PassThrough::Reject).is_err());
+
+    let greek = Alphabet::greek();
+    let cipher = encrypt_with("θεωρια", 5, 3, &greek, PassThrough::Reject).unwrap();
+    assert_eq!(
+        decrypt_with(&cipher, 5, 3, &greek, PassThrough::Reject).unwrap(),
+        "θεωρια"
+    );
+}
+
+#[test]
+fn test_affine_pass_through() {
+    let alphabet = Alphabet::lowercase();
+    let text = "Hello, World!";
+
+    // case is preserved like in the Caesar cipher
+    assert_eq!(encrypt("Hello", 3, 7).unwrap(), "Ctoox");
+    assert_eq!(decrypt("Ctoox", 3, 7).unwrap(), "Hello");
+
+    let cipher = encrypt_with(text, 3, 7, &alphabet, PassThrough::Preserve).unwrap();
+    assert_eq!(cipher, "Ctoox, Vxgoq!");
+    assert_eq!(
+        decrypt_with(&cipher, 3, 7, &alphabet, PassThrough::Preserve).unwrap(),
+        text
+    );
+
+    assert_eq!(
+        encrypt_with(text, 3, 7, &alphabet, PassThrough::Strip).unwrap(),
+        "CtooxVxgoq"
+    );
+    assert_eq!(encrypt(text, 3, 7).unwrap_err(), "Invalid character");
+}
+
+#[test]
+fn test_affine_trait() {
+    let affine = Affine::new(Alphabet::lowercase(), PassThrough::Preserve);
+    assert_eq!(
+        affine.encrypt("Hello, World!", &(3, 7)).unwrap(),
+        "Ctoox, Vxgoq!"
+    );
+    assert_eq!(
+        affine.decrypt("Ctoox, Vxgoq!", &(3, 7)).unwrap(),
+        "Hello, World!"
+    );
+    assert_eq!(affine.key_space_size(), 312);
+    assert_eq!(
+        affine.check_key(&(13, 1)),
+        Err(ClassicalCipherError::NonInvertibleKey { a: 13, n: 26 })
+    );
+    assert_eq!(
+        affine.decrypt("abc", &(2, 1)),
+        Err(ClassicalCipherError::NonInvertibleKey { a: 2, n: 26 })
+    );
+    assert_eq!(
+        Affine::default().encrypt("a b", &(3, 1)),
+        Err(ClassicalCipherError::InvalidCharacter)
+    );
+
+    // phi(33) = 20
+    assert_eq!(
+        Affine::new(Alphabet::cyrillic(), PassThrough::Reject).key_space_size(),
+        20 * 33
+    );
+}
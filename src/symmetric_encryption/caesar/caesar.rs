@@ -1,43 +1,91 @@
-use crate::symmetric_encryption::utils::{alphabet_size_i32, get_char_at_index, get_char_index};
-
-pub fn encrypt(text: &str, mut shift: i32) -> Result<String, String> {
-    // handle negative shift
-    shift = if shift < 0 {
-        alphabet_size_i32() + shift
-    } else {
-        shift
-    };
-
-    text.chars().try_fold(String::new(), |mut acc, c| {
-        let is_uppercase = c.is_ascii_uppercase();
-
-        let c = if is_uppercase {
-            c.to_ascii_lowercase()
-        } else {
-            c
-        };
-
-        let index = get_char_index(c)?;
-        // wrap around if index + shift is greater than ALPHABET.len()
-        // new_index = index + shift % alphabet_size_i32()
-        // using rem_euclid instead of %, to ensure that the new_index is always within [0, n-1]
-        let new_index = (index + shift).rem_euclid(alphabet_size_i32());
-
-        let new_char = get_char_at_index(new_index)?;
-
-        acc.push(if is_uppercase {
-            new_char.to_ascii_uppercase()
-        } else {
-            new_char
-        });
-
-        Ok(acc)
-    })
+use crate::symmetric_encryption::{
+    traits::{ClassicalCipher, ClassicalCipherError},
+    utils::{default_alphabet, substitute, Alphabet, PassThrough},
+};
+
+pub fn encrypt(text: &str, shift: i32) -> Result<String, String> {
+    encrypt_with(text, shift, default_alphabet(), PassThrough::Reject)
 }
 
 pub fn decrypt(cipher: &str, shift: i32) -> Result<String, String> {
-    // In a 26-letter alphabet, shifting backward by n is equivalent to shifting forward by 26 - n.
-    encrypt(cipher, alphabet_size_i32() - shift)
+    decrypt_with(cipher, shift, default_alphabet(), PassThrough::Reject)
+}
+
+/// Caesar encryption over any alphabet of $n$ characters: $y = x + k \bmod n$
+///
+/// Uppercase characters missing from the alphabet are shifted as their
+/// lowercase form and converted back, the other characters outside the
+/// alphabet are handled according to `policy`.
+pub fn encrypt_with(
+    text: &str,
+    shift: i32,
+    alphabet: &Alphabet,
+    policy: PassThrough,
+) -> Result<String, String> {
+    // using rem_euclid instead of %, to ensure that the shift is always within [0, n-1],
+    // which also handles negative shifts
+    let shift = (shift as i64).rem_euclid(alphabet.len() as i64) as usize;
+
+    // wrap around if index + shift is greater than the alphabet size
+    substitute(text, alphabet, policy, |index| {
+        (index + shift) % alphabet.len()
+    })
+}
+
+pub fn decrypt_with(
+    cipher: &str,
+    shift: i32,
+    alphabet: &Alphabet,
+    policy: PassThrough,
+) -> Result<String, String> {
+    // In an n-letter alphabet, shifting backward by k is equivalent to shifting forward by n - k.
+    let shift = (shift as i64).rem_euclid(alphabet.len() as i64) as i32;
+    encrypt_with(cipher, alphabet.len() as i32 - shift, alphabet, policy)
+}
+
+/// The Caesar cipher over an alphabet, as a [`ClassicalCipher`] keyed by the shift
+#[derive(Debug, Clone, PartialEq)]
+pub struct Caesar {
+    alphabet: Alphabet,
+    policy: PassThrough,
+}
+
+impl Caesar {
+    pub fn new(alphabet: Alphabet, policy: PassThrough) -> Self {
+        Self { alphabet, policy }
+    }
+}
+
+impl Default for Caesar {
+    /// Lowercase alphabet, other characters rejected, like [`encrypt`]
+    fn default() -> Self {
+        Self::new(default_alphabet().clone(), PassThrough::Reject)
+    }
+}
+
+impl ClassicalCipher for Caesar {
+    type Key = i32;
+
+    /// Every shift is valid, shifts equal modulo $n$ being the same key
+    fn check_key(&self, _key: &i32) -> Result<(), ClassicalCipherError> {
+        Ok(())
+    }
+
+    /// Fails only on a character outside the alphabet, when rejected by the policy
+    fn encrypt(&self, text: &str, key: &i32) -> Result<String, ClassicalCipherError> {
+        encrypt_with(text, *key, &self.alphabet, self.policy)
+            .map_err(|_| ClassicalCipherError::InvalidCharacter)
+    }
+
+    fn decrypt(&self, cipher: &str, key: &i32) -> Result<String, ClassicalCipherError> {
+        decrypt_with(cipher, *key, &self.alphabet, self.policy)
+            .map_err(|_| ClassicalCipherError::InvalidCharacter)
+    }
+
+    /// The shifts $0$ to $n - 1$
+    fn keys(&self) -> impl Iterator<Item = i32> + '_ {
+        0..self.alphabet.len() as i32
+    }
 }
 
 #[test]
@@ -62,3 +110,99 @@ fn caesar_test() {
     let err = encrypt(non_alphabetic_text, shift).unwrap_err();
     assert_eq!(&err, "Invalid character");
 }
+
+#[test]
+fn test_caesar_alphabets() {
+    // 'z' + 1 is 'A' in the mixed-case alphabet
+    let mixed_case = Alphabet::mixed_case();
+    assert_eq!(
+        encrypt_with("Hallo", 3, &mixed_case, PassThrough::Reject).unwrap(),
+        "Kdoor"
+    );
+    assert_eq!(
+        encrypt_with("xyz", 3, &mixed_case, PassThrough::Reject).unwrap(),
+        "ABC"
+    );
+    assert_eq!(
+        decrypt_with("ABC", 3, &mixed_case, PassThrough::Reject).unwrap(),
+        "xyz"
+    );
+
+    let printable = Alphabet::printable_ascii();
+    let text = "Hello, World!";
+    let cipher = encrypt_with(text, 50, &printable, PassThrough::Reject).unwrap();
+    assert_eq!(
+        decrypt_with(&cipher, 50, &printable, PassThrough::Reject).unwrap(),
+        text
+    );
+
+    let cyrillic = Alphabet::cyrillic();
+    assert_eq!(
+        encrypt_with("Привет", 3, &cyrillic, PassThrough::Reject).unwrap(),
+        "Тулезх"
+    );
+    assert_eq!(
+        decrypt_with("Тулезх", 3, &cyrillic, PassThrough::Reject).unwrap(),
+        "Привет"
+    );
+    assert_eq!(
+        encrypt_with("я", 1, &cyrillic, PassThrough::Reject).unwrap(),
+        "а"
+    );
+
+    let greek = Alphabet::greek();
+    assert_eq!(
+        encrypt_with("αλφα", -1, &greek, PassThrough::Reject).unwrap(),
+        "ωκυω"
+    );
+    assert_eq!(
+        decrypt_with("ωκυω", -1, &greek, PassThrough::Reject).unwrap(),
+        "αλφα"
+    );
+}
+
+#[test]
+fn test_caesar_pass_through() {
+    let alphabet = Alphabet::lowercase();
+    let text = "Hello, World!";
+
+    let cipher = encrypt_with(text, 3, &alphabet, PassThrough::Preserve).unwrap();
+    assert_eq!(cipher, "Khoor, Zruog!");
+    assert_eq!(
+        decrypt_with(&cipher, 3, &alphabet, PassThrough::Preserve).unwrap(),
+        text
+    );
+
+    assert_eq!(
+        encrypt_with(text, 3, &alphabet, PassThrough::Strip).unwrap(),
+        "KhoorZruog"
+    );
+    assert_eq!(
+        encrypt_with(text, 3, &alphabet, PassThrough::Reject).unwrap_err(),
+        "Invalid character"
+    );
+}
+
+#[test]
+fn test_caesar_trait() {
+    let caesar = Caesar::new(Alphabet::lowercase(), PassThrough::Preserve);
+    assert_eq!(
+        caesar.encrypt("Hello, World!", &3).unwrap(),
+        "Khoor, Zruog!"
+    );
+    assert_eq!(
+        caesar.decrypt("Khoor, Zruog!", &3).unwrap(),
+        "Hello, World!"
+    );
+    assert_eq!(caesar.key_space_size(), 26);
+    assert!(caesar.check_key(&-40).is_ok());
+
+    assert_eq!(
+        Caesar::new(Alphabet::greek(), PassThrough::Reject).key_space_size(),
+        24
+    );
+    assert_eq!(
+        Caesar::default().encrypt("a b", &1),
+        Err(ClassicalCipherError::InvalidCharacter)
+    );
+}
@@ -272,9 +272,8 @@ impl Des {
     }
 
     pub fn feistel_rounds(&self, mut l: u32, mut r: u32, subkeys: &[u64; 16]) -> (u32, u32) {
-        for round in 0..16 {
-            let subkey = subkeys[round];
-            (l, r) = self.feistel_round(l, r, subkey);
+        for subkey in subkeys.iter() {
+            (l, r) = self.feistel_round(l, r, *subkey);
         }
 
         (l, r)
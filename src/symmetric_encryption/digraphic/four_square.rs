@@ -0,0 +1,102 @@
+use super::square::{pairs_with, prepare_with, Reduction, Square};
+
+/// Four-square Cipher Encryption
+///
+/// Four squares in a 2x2 grid: the top-left and bottom-right ones are the
+/// standard square, the top-right and bottom-left ones are keyed. The first
+/// letter of each digraph is located in the top-left square, the second in
+/// the bottom-right one, and they are replaced by the letters at the other
+/// corners of their rectangle: in the top-right square (row of the first
+/// letter, column of the second), then in the bottom-left square (row of
+/// the second letter, column of the first).
+pub fn encrypt(text: &str, top_keyword: &str, bottom_keyword: &str) -> Result<String, String> {
+    encrypt_with(text, top_keyword, bottom_keyword, Reduction::MergeIJ)
+}
+
+/// Four-square Cipher Decryption, a filler completing the last pair is not removed
+pub fn decrypt(cipher: &str, top_keyword: &str, bottom_keyword: &str) -> Result<String, String> {
+    decrypt_with(cipher, top_keyword, bottom_keyword, Reduction::MergeIJ)
+}
+
+/// Four-square encryption with squares reduced to 25 letters by `reduction`
+pub fn encrypt_with(
+    text: &str,
+    top_keyword: &str,
+    bottom_keyword: &str,
+    reduction: Reduction,
+) -> Result<String, String> {
+    let (plain, top, bottom) = squares(top_keyword, bottom_keyword, reduction)?;
+
+    Ok(pairs_with(&prepare_with(text, reduction)?, reduction)
+        .into_iter()
+        .flat_map(|(a, b)| {
+            let (r1, c1) = plain.position(a);
+            let (r2, c2) = plain.position(b);
+            [top.letter_at(r1, c2), bottom.letter_at(r2, c1)]
+        })
+        .collect())
+}
+
+pub fn decrypt_with(
+    cipher: &str,
+    top_keyword: &str,
+    bottom_keyword: &str,
+    reduction: Reduction,
+) -> Result<String, String> {
+    let (plain, top, bottom) = squares(top_keyword, bottom_keyword, reduction)?;
+    let letters = prepare_with(cipher, reduction)?;
+    if letters.len() % 2 != 0 {
+        return Err("Ciphertext length must be even".to_string());
+    }
+
+    Ok(pairs_with(&letters, reduction)
+        .into_iter()
+        .flat_map(|(a, b)| {
+            let (r1, c2) = top.position(a);
+            let (r2, c1) = bottom.position(b);
+            [plain.letter_at(r1, c1), plain.letter_at(r2, c2)]
+        })
+        .collect())
+}
+
+/// The standard square and the two keyed squares
+fn squares(
+    top_keyword: &str,
+    bottom_keyword: &str,
+    reduction: Reduction,
+) -> Result<(Square, Square, Square), String> {
+    Ok((
+        Square::with_reduction("", reduction)?,
+        Square::with_reduction(top_keyword, reduction)?,
+        Square::with_reduction(bottom_keyword, reduction)?,
+    ))
+}
+
+#[test]
+fn test_four_square() {
+    let (top, bottom) = ("example", "keyword");
+
+    // example of the Wikipedia article "Four-square cipher", with Q omitted
+    let text = "Help me, Obi-Wan Kenobi";
+    let cipher = encrypt_with(text, top, bottom, Reduction::OmitQ).unwrap();
+    assert_eq!(cipher, "fygmkyhobxmfkkkimd");
+    assert_eq!(
+        decrypt_with(&cipher, top, bottom, Reduction::OmitQ).unwrap(),
+        "helpmeobiwankenobi"
+    );
+
+    // the default squares merge i and j instead
+    let cipher = encrypt(text, top, bottom).unwrap();
+    assert_eq!(decrypt(&cipher, top, bottom).unwrap(), "helpmeobiwankenobi");
+
+    // "he": h = (1, 2), e = (0, 4) in the standard square
+    // top-right (1, 4) = f, bottom-left (0, 2) = y
+    assert_eq!(encrypt("he", top, bottom).unwrap(), "fy");
+
+    // doubled letters need no filler
+    assert_eq!(
+        decrypt(&encrypt("balloon", top, bottom).unwrap(), top, bottom).unwrap(),
+        "balloonx"
+    );
+    assert!(decrypt("abc", top, bottom).is_err());
+}
@@ -0,0 +1,4 @@
+pub mod four_square;
+pub mod playfair;
+pub mod square;
+pub mod two_square;
@@ -0,0 +1,104 @@
+use super::square::{filler_for, prepare, Square};
+
+/// Playfair Cipher Encryption
+///
+/// The text is split into digraphs, a filler separating doubled letters and
+/// completing the last pair. Each digraph is then encrypted in the square:
+/// - same row: each letter is replaced by the one on its right
+/// - same column: each letter is replaced by the one below
+/// - otherwise: the letters are the opposite corners of a rectangle, each is
+///   replaced by the corner in its own row
+pub fn encrypt(text: &str, keyword: &str) -> Result<String, String> {
+    let square = Square::new(keyword)?;
+    Ok(digraphs(&prepare(text)?)
+        .into_iter()
+        .flat_map(|(a, b)| {
+            let (c, d) = encrypt_digraph(&square, a, b, 1);
+            [c, d]
+        })
+        .collect())
+}
+
+/// Playfair Cipher Decryption, the fillers are not removed
+pub fn decrypt(cipher: &str, keyword: &str) -> Result<String, String> {
+    let square = Square::new(keyword)?;
+    let letters = prepare(cipher)?;
+    if letters.len() % 2 != 0 {
+        return Err("Ciphertext length must be even".to_string());
+    }
+
+    Ok(letters
+        .chunks(2)
+        .flat_map(|pair| {
+            let (c, d) = encrypt_digraph(&square, pair[0], pair[1], 4);
+            [c, d]
+        })
+        .collect())
+}
+
+/// Split letters into digraphs with two different letters
+///
+/// `balloon` becomes `ba lx lo on`.
+pub fn digraphs(letters: &[char]) -> Vec<(char, char)> {
+    let mut digraphs = Vec::with_capacity(letters.len() / 2 + 1);
+    let mut i = 0;
+    while i < letters.len() {
+        let a = letters[i];
+        match letters.get(i + 1) {
+            Some(&b) if b != a => {
+                digraphs.push((a, b));
+                i += 2;
+            }
+            _ => {
+                digraphs.push((a, filler_for(a)));
+                i += 1;
+            }
+        }
+    }
+    digraphs
+}
+
+/// Shift by `step` (1 to encrypt, 4 to decrypt) along rows or columns, or swap rectangle corners
+fn encrypt_digraph(square: &Square, a: char, b: char, step: usize) -> (char, char) {
+    let (r1, c1) = square.position(a);
+    let (r2, c2) = square.position(b);
+
+    if r1 == r2 {
+        (
+            square.letter_at(r1, c1 + step),
+            square.letter_at(r2, c2 + step),
+        )
+    } else if c1 == c2 {
+        (
+            square.letter_at(r1 + step, c1),
+            square.letter_at(r2 + step, c2),
+        )
+    } else {
+        (square.letter_at(r1, c2), square.letter_at(r2, c1))
+    }
+}
+
+#[test]
+fn test_playfair() {
+    let keyword = "playfair example";
+    let text = "Hide the gold in the tree stump";
+    let cipher = "bmodzbxdnabekudmuixmmouvif";
+
+    assert_eq!(encrypt(text, keyword).unwrap(), cipher);
+    // "tree" is split as "tr ex es"
+    assert_eq!(
+        decrypt(cipher, keyword).unwrap(),
+        "hidethegoldinthetrexestump"
+    );
+    assert_eq!(
+        decrypt(&cipher.to_uppercase(), keyword).unwrap(),
+        "hidethegoldinthetrexestump"
+    );
+    assert!(decrypt("abc", keyword).is_err());
+
+    // odd length, doubled filler and j
+    assert_eq!(digraphs(&['b', 'a', 'l', 'l', 'o', 'o', 'n']).len(), 4);
+    assert_eq!(digraphs(&['x', 'x']), [('x', 'q'), ('x', 'q')]);
+    let cipher = encrypt("jazz", "monarchy").unwrap();
+    assert_eq!(decrypt(&cipher, "monarchy").unwrap(), "iazxzx");
+}
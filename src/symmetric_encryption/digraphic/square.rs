@@ -0,0 +1,177 @@
+// 5x5 Polybius square shared by the digraphic ciphers
+
+/// Letter standing in for `j`, the two share a cell of the square
+pub const MERGED: (char, char) = ('j', 'i');
+
+/// Letter inserted between doubled letters and appended to odd-length texts
+pub const FILLER: char = 'x';
+
+/// Filler used when the letter to separate or complete is the filler itself
+pub const ALTERNATE_FILLER: char = 'q';
+
+/// Letter left out of the square with [`Reduction::OmitQ`]
+pub const OMITTED: char = 'q';
+
+/// How the 26 letters fit in the 25 cells of a square
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Reduction {
+    /// `i` and `j` share a cell, `j` is written `i`
+    #[default]
+    MergeIJ,
+    /// `q` has no cell and is dropped from the text, as in many textbook
+    /// examples of the two-square and four-square ciphers
+    OmitQ,
+}
+
+impl Reduction {
+    /// The filler to place after `c`, `z` replacing the alternate filler when `q` is omitted
+    pub fn filler_for(self, c: char) -> char {
+        match (self, filler_for(c)) {
+            (Reduction::OmitQ, OMITTED) => 'z',
+            (_, filler) => filler,
+        }
+    }
+
+    /// Whether `c` has a cell of its own
+    fn has_cell(self, c: char) -> bool {
+        match self {
+            Reduction::MergeIJ => c != MERGED.0,
+            Reduction::OmitQ => c != OMITTED,
+        }
+    }
+}
+
+/// 25 letters in a 5x5 grid, `i` and `j` sharing a cell unless `q` is omitted
+///
+/// A keyed square starts with the distinct letters of the keyword, followed
+/// by the rest of the alphabet in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Square {
+    letters: [char; 25],
+    positions: [(usize, usize); 26],
+}
+
+impl Square {
+    pub fn new(keyword: &str) -> Result<Self, String> {
+        Self::with_reduction(keyword, Reduction::MergeIJ)
+    }
+
+    pub fn with_reduction(keyword: &str, reduction: Reduction) -> Result<Self, String> {
+        let keyword = prepare_with(keyword, reduction)?;
+
+        let mut letters = ['a'; 25];
+        let mut positions = [(usize::MAX, usize::MAX); 26];
+        let mut len = 0;
+        for c in keyword.into_iter().chain('a'..='z') {
+            if !reduction.has_cell(c) || positions[letter_index(c)].0 != usize::MAX {
+                continue;
+            }
+            positions[letter_index(c)] = (len / 5, len % 5);
+            letters[len] = c;
+            len += 1;
+        }
+        if reduction == Reduction::MergeIJ {
+            positions[letter_index(MERGED.0)] = positions[letter_index(MERGED.1)];
+        }
+
+        Ok(Self { letters, positions })
+    }
+
+    /// The square without keyword: `abcde/fghik/lmnop/qrstu/vwxyz`
+    pub fn standard() -> Self {
+        Self::new("").unwrap()
+    }
+
+    /// Row and column of a lowercase letter, `j` is found at the place of `i`
+    pub fn position(&self, c: char) -> (usize, usize) {
+        self.positions[letter_index(c)]
+    }
+
+    pub fn letter_at(&self, row: usize, column: usize) -> char {
+        self.letters[(row % 5) * 5 + column % 5]
+    }
+}
+
+/// Lowercase letters of `text` with `j` replaced by `i`
+///
+/// Whitespace and ASCII punctuation are dropped, other characters are rejected.
+pub fn prepare(text: &str) -> Result<Vec<char>, String> {
+    prepare_with(text, Reduction::MergeIJ)
+}
+
+/// Lowercase letters of `text` that fit in a square reduced with `reduction`:
+/// `j` replaced by `i`, or `q` dropped
+pub fn prepare_with(text: &str, reduction: Reduction) -> Result<Vec<char>, String> {
+    text.chars()
+        .filter(|c| !c.is_whitespace() && !c.is_ascii_punctuation())
+        .filter_map(|c| {
+            if !c.is_ascii_alphabetic() {
+                return Some(Err("Invalid character".to_string()));
+            }
+            match (reduction, c.to_ascii_lowercase()) {
+                (Reduction::MergeIJ, c) if c == MERGED.0 => Some(Ok(MERGED.1)),
+                (Reduction::OmitQ, OMITTED) => None,
+                (_, c) => Some(Ok(c)),
+            }
+        })
+        .collect()
+}
+
+/// Split prepared letters into pairs, completing an odd length with the filler
+pub fn pairs(letters: &[char]) -> Vec<(char, char)> {
+    pairs_with(letters, Reduction::MergeIJ)
+}
+
+/// [`pairs`] with the filler of `reduction`
+pub fn pairs_with(letters: &[char], reduction: Reduction) -> Vec<(char, char)> {
+    letters
+        .chunks(2)
+        .map(|pair| match pair {
+            [a, b] => (*a, *b),
+            [a] => (*a, reduction.filler_for(*a)),
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// The filler to place after `c`
+pub fn filler_for(c: char) -> char {
+    if c == FILLER {
+        ALTERNATE_FILLER
+    } else {
+        FILLER
+    }
+}
+
+fn letter_index(c: char) -> usize {
+    (c as u8 - b'a') as usize
+}
+
+#[test]
+fn test_square() {
+    let square = Square::new("Playfair example").unwrap();
+    let rows: Vec<String> = (0..5)
+        .map(|r| (0..5).map(|c| square.letter_at(r, c)).collect())
+        .collect();
+    assert_eq!(rows, ["playf", "irexm", "bcdgh", "knoqs", "tuvwz"]);
+    assert_eq!(square.position('j'), square.position('i'));
+    assert_eq!(square.position('z'), (4, 4));
+
+    assert_eq!(Square::standard().position('k'), (1, 4));
+    assert!(Square::new("clé").is_err());
+    assert_eq!(
+        prepare("Jump, now!").unwrap(),
+        ['i', 'u', 'm', 'p', 'n', 'o', 'w']
+    );
+    assert_eq!(pairs(&['a', 'b', 'x']), [('a', 'b'), ('x', 'q')]);
+
+    let square = Square::with_reduction("", Reduction::OmitQ).unwrap();
+    assert_eq!(square.position('j'), (1, 4));
+    assert_eq!(square.letter_at(3, 0), 'p');
+    assert_eq!(square.letter_at(3, 1), 'r');
+    assert_eq!(
+        prepare_with("Quiz, Jo!", Reduction::OmitQ).unwrap(),
+        ['u', 'i', 'z', 'j', 'o']
+    );
+    assert_eq!(pairs_with(&['x'], Reduction::OmitQ), [('x', 'z')]);
+}
@@ -0,0 +1,138 @@
+use super::square::{pairs_with, prepare_with, Reduction, Square};
+
+/// Two-square Cipher Encryption (horizontal)
+///
+/// The first letter of each digraph is located in the left square, the
+/// second in the right square. They are opposite corners of a rectangle and
+/// are replaced by the other two corners: first the one in the right square,
+/// then the one in the left square. When both letters are in the same row the
+/// digraph comes out reversed, the well-known weakness of this cipher.
+pub fn encrypt(text: &str, left_keyword: &str, right_keyword: &str) -> Result<String, String> {
+    encrypt_with(text, left_keyword, right_keyword, Reduction::MergeIJ)
+}
+
+/// Two-square Cipher Decryption, a filler completing the last pair is not removed
+pub fn decrypt(cipher: &str, left_keyword: &str, right_keyword: &str) -> Result<String, String> {
+    decrypt_with(cipher, left_keyword, right_keyword, Reduction::MergeIJ)
+}
+
+/// Horizontal two-square encryption with squares reduced to 25 letters by `reduction`
+pub fn encrypt_with(
+    text: &str,
+    left_keyword: &str,
+    right_keyword: &str,
+    reduction: Reduction,
+) -> Result<String, String> {
+    let left = Square::with_reduction(left_keyword, reduction)?;
+    let right = Square::with_reduction(right_keyword, reduction)?;
+
+    Ok(pairs_with(&prepare_with(text, reduction)?, reduction)
+        .into_iter()
+        .flat_map(|(a, b)| {
+            let (r1, c1) = left.position(a);
+            let (r2, c2) = right.position(b);
+            [right.letter_at(r1, c2), left.letter_at(r2, c1)]
+        })
+        .collect())
+}
+
+pub fn decrypt_with(
+    cipher: &str,
+    left_keyword: &str,
+    right_keyword: &str,
+    reduction: Reduction,
+) -> Result<String, String> {
+    let left = Square::with_reduction(left_keyword, reduction)?;
+    let right = Square::with_reduction(right_keyword, reduction)?;
+    let letters = prepare_with(cipher, reduction)?;
+    if letters.len() % 2 != 0 {
+        return Err("Ciphertext length must be even".to_string());
+    }
+
+    Ok(pairs_with(&letters, reduction)
+        .into_iter()
+        .flat_map(|(a, b)| {
+            let (r1, c2) = right.position(a);
+            let (r2, c1) = left.position(b);
+            [left.letter_at(r1, c1), right.letter_at(r2, c2)]
+        })
+        .collect())
+}
+
+/// Two-square Cipher Encryption (vertical)
+///
+/// The first letter of each digraph is located in the top square, the
+/// second in the bottom square, and they are replaced by the other corners
+/// of their rectangle: first the one in the top square, then the one in the
+/// bottom square. When both letters are in the same column the digraph is
+/// left unchanged. Encrypting twice gives back the text, so decryption is
+/// the same operation.
+pub fn encrypt_vertical(
+    text: &str,
+    top_keyword: &str,
+    bottom_keyword: &str,
+    reduction: Reduction,
+) -> Result<String, String> {
+    let top = Square::with_reduction(top_keyword, reduction)?;
+    let bottom = Square::with_reduction(bottom_keyword, reduction)?;
+
+    Ok(pairs_with(&prepare_with(text, reduction)?, reduction)
+        .into_iter()
+        .flat_map(|(a, b)| {
+            let (r1, c1) = top.position(a);
+            let (r2, c2) = bottom.position(b);
+            if c1 == c2 {
+                [a, b]
+            } else {
+                [top.letter_at(r1, c2), bottom.letter_at(r2, c1)]
+            }
+        })
+        .collect())
+}
+
+/// Vertical two-square decryption, a filler completing the last pair is not removed
+pub fn decrypt_vertical(
+    cipher: &str,
+    top_keyword: &str,
+    bottom_keyword: &str,
+    reduction: Reduction,
+) -> Result<String, String> {
+    if prepare_with(cipher, reduction)?.len() % 2 != 0 {
+        return Err("Ciphertext length must be even".to_string());
+    }
+    encrypt_vertical(cipher, top_keyword, bottom_keyword, reduction)
+}
+
+#[test]
+fn test_two_square() {
+    let (left, right) = ("example", "keyword");
+    let text = "help me obi wan kenobi";
+    let cipher = encrypt(text, left, right).unwrap();
+    assert_eq!(decrypt(&cipher, left, right).unwrap(), "helpmeobiwankenobi");
+    let cipher = encrypt_with(text, left, right, Reduction::OmitQ).unwrap();
+    assert_eq!(
+        decrypt_with(&cipher, left, right, Reduction::OmitQ).unwrap(),
+        "helpmeobiwankenobi"
+    );
+
+    // a is on the first row of the left square, w on the first row of the right one
+    assert_eq!(encrypt("aw", left, right).unwrap(), "wa");
+
+    let cipher = encrypt("odd", left, right).unwrap();
+    assert_eq!(decrypt(&cipher, left, right).unwrap(), "oddx");
+    assert!(decrypt("abc", left, right).is_err());
+}
+
+#[test]
+fn test_two_square_vertical() {
+    // example of the Wikipedia article "Two-square cipher", with Q omitted:
+    // "he" and "an" are in the same column and stay in clear
+    let (top, bottom) = ("example", "keyword");
+    let cipher = encrypt_vertical("help me obi wan kenobi", top, bottom, Reduction::OmitQ).unwrap();
+    assert_eq!(cipher, "hedlxwsdjyanhotkdg");
+    assert_eq!(
+        decrypt_vertical(&cipher, top, bottom, Reduction::OmitQ).unwrap(),
+        "helpmeobiwankenobi"
+    );
+    assert!(decrypt_vertical("abc", top, bottom, Reduction::OmitQ).is_err());
+}
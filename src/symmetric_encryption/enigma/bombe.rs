@@ -0,0 +1,323 @@
+// Simplified Turing-Welchman bombe: crib-based search of the rotor order and positions
+use super::{
+    enigma::{Enigma, Model},
+    rotor::letter_index,
+};
+
+/// Number of positions of three rotors
+const STATES: usize = 26 * 26 * 26;
+
+/// Part of the key space searched by the bombe
+///
+/// The real bombes searched every starting position of one rotor order at a
+/// time, with the ring settings unknown. Here the reflector and the ring
+/// settings are given, and only the listed rotor orders are tried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySpace<'a> {
+    pub model: Model,
+    pub reflector: &'a str,
+    pub rings: &'a str,
+    /// Rotors, left to right
+    pub rotor_orders: Vec<[&'a str; 3]>,
+}
+
+impl<'a> KeySpace<'a> {
+    /// Every order of three different rotors among `rotors`
+    pub fn all_orders(
+        model: Model,
+        reflector: &'a str,
+        rings: &'a str,
+        rotors: &[&'a str],
+    ) -> Self {
+        let mut rotor_orders = vec![];
+        for &left in rotors {
+            for &middle in rotors {
+                for &right in rotors {
+                    if left != middle && middle != right && left != right {
+                        rotor_orders.push([left, middle, right]);
+                    }
+                }
+            }
+        }
+
+        Self {
+            model,
+            reflector,
+            rings,
+            rotor_orders,
+        }
+    }
+}
+
+/// A setting consistent with the crib
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stop<'a> {
+    pub rotors: [&'a str; 3],
+    /// Starting positions of the rotors, at the first letter of the message
+    pub positions: String,
+    /// Plugboard connections deduced from the crib, a letter paired with
+    /// itself being unplugged
+    pub steckers: Vec<(char, char)>,
+}
+
+/// Offsets at which `crib` can be placed under `cipher`
+///
+/// The Enigma never encrypts a letter to itself, so the crib cannot be
+/// where one of its letters is over the same ciphertext letter.
+pub fn crib_positions(cipher: &str, crib: &str) -> Result<Vec<usize>, String> {
+    let cipher = letters(cipher)?;
+    let crib = letters(crib)?;
+    if crib.len() > cipher.len() {
+        return Ok(vec![]);
+    }
+
+    Ok((0..=cipher.len() - crib.len())
+        .filter(|&offset| crib.iter().zip(&cipher[offset..]).all(|(p, c)| p != c))
+        .collect())
+}
+
+/// Search the key space for the settings turning `crib` into the ciphertext at `offset`
+///
+/// Each crib letter $p_i$ over the ciphertext letter $c_i$ links the two in
+/// the menu: if $S$ is the plugboard and $E_i$ the scrambler of the rotors
+/// and reflector at that position, $S(c_i) = E_i(S(p_i))$. For each rotor
+/// order and starting position, the bombe assumes a partner for the most
+/// connected menu letter and propagates it through the links and through
+/// the symmetry of the plugboard, Welchman's diagonal board. A hypothesis
+/// giving one letter two partners is a contradiction, the others are stops.
+///
+/// The links form a graph on the 676 (letter, partner) pairs, whose
+/// connected components are the classes of hypotheses implying each other.
+/// They are built with a union-find, which is what the electrical
+/// connections of the bombe computed at once.
+pub fn search<'a>(
+    cipher: &str,
+    crib: &str,
+    offset: usize,
+    key_space: &KeySpace<'a>,
+) -> Result<Vec<Stop<'a>>, String> {
+    let cipher = letters(cipher)?;
+    let crib = letters(crib)?;
+    if crib.is_empty() || offset + crib.len() > cipher.len() {
+        return Err("The crib must fit in the ciphertext".to_string());
+    }
+    let links: Vec<(usize, usize)> = crib
+        .into_iter()
+        .zip(cipher[offset..].iter().copied())
+        .collect();
+    if links.iter().any(|(p, c)| p == c) {
+        return Err("A letter cannot be encrypted to itself".to_string());
+    }
+
+    // the test register is on the most connected letter of the menu
+    let mut connections = [0; 26];
+    for &(p, c) in &links {
+        connections[p] += 1;
+        connections[c] += 1;
+    }
+    let test_letter = (0..26).max_by_key(|&x| connections[x]).unwrap();
+
+    let mut stops = vec![];
+    // the diagonal board: a plugged to x means x plugged to a
+    let mut diagonal = UnionFind::new(26 * 26);
+    for a in 0..26 {
+        for x in a + 1..26 {
+            diagonal.union(a * 26 + x, x * 26 + a);
+        }
+    }
+    let mut classes = diagonal.clone();
+    let mut last_row = vec![usize::MAX; 26 * 26];
+    let mut contradiction = vec![false; 26 * 26];
+
+    for rotors in &key_space.rotor_orders {
+        let (scramblers, next) = scrambler_table(key_space, rotors)?;
+
+        for start in 0..STATES {
+            let mut state = start;
+            for _ in 0..offset {
+                state = next[state];
+            }
+
+            classes.clone_from(&diagonal);
+            for &(p, c) in &links {
+                state = next[state];
+                for (x, &y) in scramblers[state].iter().enumerate() {
+                    classes.union(p * 26 + x, c * 26 + y as usize);
+                }
+            }
+
+            // a class is contradictory if it gives two partners to the same letter,
+            // the hypotheses of a row are consecutive
+            last_row.fill(usize::MAX);
+            contradiction.fill(false);
+            for node in 0..26 * 26 {
+                let root = classes.find(node);
+                if last_row[root] == node / 26 {
+                    contradiction[root] = true;
+                }
+                last_row[root] = node / 26;
+            }
+
+            for partner in 0..26 {
+                let root = classes.find(test_letter * 26 + partner);
+                if contradiction[root] {
+                    continue;
+                }
+                let steckers = (0..26 * 26)
+                    .filter(|&node| node / 26 <= node % 26 && classes.find(node) == root)
+                    .map(|node| (letter(node / 26), letter(node % 26)))
+                    .collect();
+                stops.push(Stop {
+                    rotors: *rotors,
+                    positions: positions(start),
+                    steckers,
+                });
+            }
+        }
+    }
+
+    Ok(stops)
+}
+
+/// Scrambler of each of the $26^3$ rotor positions, and the position after a key press
+///
+/// A crib is short compared to the number of positions, but every starting
+/// position needs the scramblers of the following ones: computing each
+/// scrambler once is much faster than computing them for each start.
+fn scrambler_table(
+    key_space: &KeySpace,
+    rotors: &[&str; 3],
+) -> Result<(Vec<[u8; 26]>, Vec<usize>), String> {
+    let mut enigma = Enigma::new(
+        key_space.model,
+        key_space.reflector,
+        rotors,
+        key_space.rings,
+        "AAA",
+        "",
+    )?;
+
+    let mut scramblers = vec![[0; 26]; STATES];
+    let mut next = vec![0; STATES];
+    for state in 0..STATES {
+        enigma.set_positions(&positions(state))?;
+        for (y, x) in scramblers[state].iter_mut().zip(enigma.scrambler()) {
+            *y = x as u8;
+        }
+        enigma.step();
+        next[state] = enigma
+            .positions()
+            .bytes()
+            .fold(0, |state, c| state * 26 + (c - b'A') as usize);
+    }
+
+    Ok((scramblers, next))
+}
+
+/// Rotor positions of a state, e.g. `"AAB"` for state 1
+fn positions(state: usize) -> String {
+    [state / 676, state / 26 % 26, state % 26]
+        .into_iter()
+        .map(letter)
+        .collect()
+}
+
+fn letter(x: usize) -> char {
+    (b'A' + x as u8) as char
+}
+
+/// Disjoint sets of nodes, with path halving and union by size
+#[derive(Clone)]
+struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+    }
+}
+
+/// Letter indices of `text`, whitespace ignored
+fn letters(text: &str) -> Result<Vec<usize>, String> {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(letter_index)
+        .collect()
+}
+
+#[test]
+fn test_crib_positions() {
+    let positions = crib_positions("EDPUD NRGYS ZRCXN", "AUFKL").unwrap();
+    assert!(positions.contains(&0));
+    // the U of the crib over the U of EDPUD
+    assert!(!positions.contains(&2));
+    assert!(crib_positions("ABC", "ABCD").unwrap().is_empty());
+}
+
+#[test]
+fn test_bombe() {
+    use super::plugboard::Plugboard;
+
+    let plugboard = "AV BS CG DL FU HZ IN KM OW RX";
+    let mut enigma =
+        Enigma::new(Model::I, "B", &["II", "IV", "V"], "BUL", "QEV", plugboard).unwrap();
+    let cipher = enigma
+        .encrypt("KEINEBESONDERENEREIGNISSEXWETTERVORHERSAGEFUERDIEBISKAYAXREGENXWINDAUSWEST")
+        .unwrap();
+    let crib = "WETTERVORHERSAGEFUERDIEBISKAYA";
+    let offset = 26;
+    assert!(crib_positions(&cipher, crib).unwrap().contains(&offset));
+
+    let key_space = KeySpace {
+        model: Model::I,
+        reflector: "B",
+        rings: "BUL",
+        rotor_orders: vec![["V", "IV", "II"], ["II", "IV", "V"]],
+    };
+    let stops = search(&cipher, crib, offset, &key_space).unwrap();
+    // a 30-letter crib leaves a single stop, at the right setting
+    assert_eq!(stops.len(), 1);
+    let stop = &stops[0];
+    assert_eq!(stop.rotors, ["II", "IV", "V"]);
+    assert_eq!(stop.positions, "QEV");
+    // every deduced connection is on the real plugboard
+    let real = Plugboard::new(plugboard).unwrap();
+    for &(a, b) in &stop.steckers {
+        let (a, b) = (letter_index(a).unwrap(), letter_index(b).unwrap());
+        assert_eq!(real.swap(a), b);
+    }
+    assert_eq!(stop.steckers.iter().filter(|(a, b)| a != b).count(), 10);
+
+    assert_eq!(
+        KeySpace::all_orders(Model::I, "B", "AAA", &["I", "II", "III", "IV", "V"])
+            .rotor_orders
+            .len(),
+        60
+    );
+    assert!(search("ABC", "A", 0, &key_space).is_err());
+}
@@ -0,0 +1,320 @@
+use super::{
+    plugboard::Plugboard,
+    rotor::{Reflector, Rotor},
+};
+use crate::symmetric_encryption::utils::{default_alphabet, substitute, PassThrough};
+
+/// Enigma models, which differ by the rotors and reflectors they accept
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    /// Army and air force: three rotors out of I to V, reflector A, B or C
+    I,
+    /// Navy: three rotors out of I to VIII, reflector B or C
+    M3,
+    /// Navy from 1942: Beta or Gamma, three rotors out of I to VIII, thin reflector B or C
+    M4,
+}
+
+impl Model {
+    fn check(self, reflector: &str, rotors: &[&str]) -> Result<(), String> {
+        const FIVE: [&str; 5] = ["I", "II", "III", "IV", "V"];
+        const EIGHT: [&str; 8] = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII"];
+
+        let (reflectors, greek): (&[&str], &[&str]) = match self {
+            Model::I => (&["A", "B", "C"], &[]),
+            Model::M3 => (&["B", "C"], &[]),
+            Model::M4 => (&["B thin", "C thin"], &["Beta", "Gamma"]),
+        };
+        let wheels: &[&str] = if self == Model::I { &FIVE } else { &EIGHT };
+
+        if !reflectors.contains(&reflector) {
+            return Err(format!("Reflector {reflector} does not fit the {self:?}"));
+        }
+        let greek_rotors = if greek.is_empty() { 0 } else { 1 };
+        if rotors.len() != greek_rotors + 3 {
+            return Err(format!("The {self:?} takes {} rotors", greek_rotors + 3));
+        }
+        let (first, last) = rotors.split_at(greek_rotors);
+        if first.iter().any(|rotor| !greek.contains(rotor))
+            || last.iter().any(|rotor| !wheels.contains(rotor))
+        {
+            return Err(format!("Rotors {rotors:?} do not fit the {self:?}"));
+        }
+        if (1..last.len()).any(|i| last[i..].contains(&last[i - 1])) {
+            return Err("Each rotor can be used only once".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Enigma rotor machine
+///
+/// Each key press first steps the rotors, then sends the letter through the
+/// plugboard, the rotors from right to left, the reflector, the rotors back
+/// from left to right and the plugboard again. The reflector makes every
+/// setting an involution: the same setting decrypts, and no letter is ever
+/// encrypted to itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Enigma {
+    reflector: Reflector,
+    /// Beta or Gamma, between the reflector and the left rotor of the M4
+    greek: Option<Rotor>,
+    /// Left, middle and right rotors
+    rotors: [Rotor; 3],
+    plugboard: Plugboard,
+}
+
+impl Enigma {
+    /// Enigma with `rotors` from left to right, e.g. `&["II", "IV", "V"]`
+    ///
+    /// `rings` and `positions` give a letter per rotor, left to right: ring
+    /// setting 01 is `A`, 02 is `B` and so on. `plugboard` lists the
+    /// swapped pairs, e.g. `"AV BS CG"`.
+    pub fn new(
+        model: Model,
+        reflector: &str,
+        rotors: &[&str],
+        rings: &str,
+        positions: &str,
+        plugboard: &str,
+    ) -> Result<Self, String> {
+        model.check(reflector, rotors)?;
+        let rings: Vec<char> = rings.chars().collect();
+        let positions: Vec<char> = positions.chars().collect();
+        if rings.len() != rotors.len() || positions.len() != rotors.len() {
+            return Err(format!(
+                "Need a ring setting and a position for each of the {} rotors",
+                rotors.len()
+            ));
+        }
+
+        let mut rotors = rotors
+            .iter()
+            .zip(rings)
+            .zip(positions)
+            .map(|((name, ring), position)| Rotor::new(name, ring, position))
+            .collect::<Result<Vec<Rotor>, String>>()?;
+        let greek = (rotors.len() == 4).then(|| rotors.remove(0));
+
+        Ok(Self {
+            reflector: Reflector::new(reflector)?,
+            greek,
+            rotors: rotors.try_into().unwrap(),
+            plugboard: Plugboard::new(plugboard)?,
+        })
+    }
+
+    /// Letters shown in the windows, left to right
+    pub fn positions(&self) -> String {
+        self.greek
+            .iter()
+            .chain(self.rotors.iter())
+            .map(Rotor::position)
+            .collect()
+    }
+
+    /// Turn the rotors to `positions`, one letter per rotor, left to right
+    pub fn set_positions(&mut self, positions: &str) -> Result<(), String> {
+        let positions: Vec<char> = positions.chars().collect();
+        if positions.len() != self.rotors.len() + self.greek.iter().len() {
+            return Err("Need a position for each rotor".to_string());
+        }
+        for (rotor, &position) in self
+            .greek
+            .iter_mut()
+            .chain(self.rotors.iter_mut())
+            .zip(positions.iter())
+        {
+            rotor.set_position(position)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_plugboard(&mut self, plugboard: Plugboard) {
+        self.plugboard = plugboard;
+    }
+
+    /// Step the rotors as a key press does
+    ///
+    /// The right rotor always steps. A rotor at its notch steps its left
+    /// neighbour along with itself, so the middle rotor steps twice in a
+    /// row when it reaches its own notch: the double stepping anomaly,
+    /// e.g. `ADU`, `ADV`, `AEW`, `BFX` with rotors I, II, III.
+    pub fn step(&mut self) {
+        let [left, middle, right] = &mut self.rotors;
+        let middle_at_notch = middle.at_notch();
+        if middle_at_notch {
+            left.step();
+        }
+        if middle_at_notch || right.at_notch() {
+            middle.step();
+        }
+        right.step();
+    }
+
+    /// The permutation of the rotors and reflector at the current position, without the plugboard
+    pub fn scrambler(&self) -> [usize; 26] {
+        std::array::from_fn(|x| self.scramble(x))
+    }
+
+    /// Press the key of letter index `x` and return the index of the lit lamp
+    pub fn press(&mut self, x: usize) -> usize {
+        self.step();
+        let x = self.scramble(self.plugboard.swap(x));
+        self.plugboard.swap(x)
+    }
+
+    /// Encrypt or decrypt `text`, moving the rotors
+    ///
+    /// Letters keep their case, spaces and other characters are rejected.
+    /// The machine is its own inverse: decrypting is encrypting again from
+    /// the same starting positions.
+    pub fn encrypt(&mut self, text: &str) -> Result<String, String> {
+        self.encrypt_with(text, PassThrough::Reject)
+    }
+
+    /// Encryption with the characters other than letters handled according
+    /// to `policy`, they do not move the rotors
+    pub fn encrypt_with(&mut self, text: &str, policy: PassThrough) -> Result<String, String> {
+        substitute(text, default_alphabet(), policy, |x| self.press(x))
+    }
+
+    fn scramble(&self, x: usize) -> usize {
+        let x = self
+            .rotors
+            .iter()
+            .rev()
+            .chain(self.greek.iter())
+            .fold(x, |x, rotor| rotor.forward(x));
+        let x = self.reflector.reflect(x);
+        self.greek
+            .iter()
+            .chain(self.rotors.iter())
+            .fold(x, |x, rotor| rotor.backward(x))
+    }
+}
+
+#[test]
+fn test_enigma() {
+    let mut enigma = Enigma::new(Model::I, "B", &["I", "II", "III"], "AAA", "AAA", "").unwrap();
+    assert_eq!(enigma.encrypt("AAAAA").unwrap(), "BDZGO");
+    assert_eq!(enigma.positions(), "AAF");
+
+    // double stepping
+    enigma.set_positions("ADU").unwrap();
+    let mut positions = vec![];
+    for _ in 0..3 {
+        enigma.step();
+        positions.push(enigma.positions());
+    }
+    assert_eq!(positions, ["ADV", "AEW", "BFX"]);
+
+    // reciprocity, and no letter is encrypted to itself
+    let text = "DieEnigmaIstEineRotorSchluesselmaschine";
+    let mut enigma = Enigma::new(
+        Model::M3,
+        "C",
+        &["VIII", "VI", "III"],
+        "MEW",
+        "ZZY",
+        "AQ BW ET",
+    )
+    .unwrap();
+    let cipher = enigma.encrypt(text).unwrap();
+    assert!(cipher.chars().zip(text.chars()).all(|(c, p)| c != p));
+    enigma.set_positions("ZZY").unwrap();
+    assert_eq!(enigma.encrypt(&cipher).unwrap(), text);
+
+    assert!(enigma.encrypt("NO SPACES").is_err());
+    assert_eq!(
+        enigma
+            .encrypt_with("a b", PassThrough::Preserve)
+            .unwrap()
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn test_operation_barbarossa() {
+    // Enigma I message of 7 July 1941, part 1: the message key BLA is sent
+    // encrypted as EDP... and the text follows
+    let mut enigma = Enigma::new(
+        Model::I,
+        "B",
+        &["II", "IV", "V"],
+        "BUL",
+        "BLA",
+        "AV BS CG DL FU HZ IN KM OW RX",
+    )
+    .unwrap();
+    let cipher = "EDPUD NRGYS ZRCXN UYTPO MRMBO FKTBZ REZKM LXLVE FGUEY SIOZV EQMIK UBPMM YLKLT \
+        TDEIS MDICA GYKUA CTCDO MOHWX MUUIA UBSTS LRNBZ SZWNR FXWFY SSXJZ VIJHI DISHP RKLKA YUPAD \
+        TXQSP INQMA TLPIF SVKDA SCTAC DPBOP VHJK";
+    let plaintext = enigma.encrypt_with(cipher, PassThrough::Strip).unwrap();
+    assert_eq!(
+        plaintext,
+        "AUFKLXABTEILUNGXVONXKURTINOWAXKURTINOWAXNORDWESTLXSEBEZXSEBEZXUAFFLIEGERSTRASZERIQTUNG\
+         XDUBROWKIXDUBROWKIXOPOTSCHKAXOPOTSCHKAXUMXEINSAQTDREINULLXUHRANGETRETENXANGRIFFXINFXRGTX"
+    );
+}
+
+#[test]
+fn test_m4_doenitz() {
+    // M4 message of 1 May 1945 announcing Dönitz as Hitler's successor
+    let mut enigma = Enigma::new(
+        Model::M4,
+        "C thin",
+        &["Beta", "V", "VI", "VIII"],
+        "AAEL",
+        "YOSZ",
+        "AE BF CM DQ HU JN LX PR SZ VW",
+    )
+    .unwrap();
+    let cipher = "LANO TCTO UARB BFPM HPHG CZXT DYGA HGUF XGEW KBLK GJWL QXXT GPJJ AVTO CKZF SLPP \
+        QIHZ FXOE BWII EKFZ LCLO AQJU LJOY HSSM BBGW HZAN VOII PYRB RTDJ QDJJ OQKC XWDN BBTY VXLY \
+        TAPG VEAT XSON PNYN QFUD BBHH VWEP YEYD OHNL XKZD NWRH DUWU JUMW WVII WZXI VIUQ DRHY MNCY \
+        EFUA PNHO TKHK GDNP SAKN UAGH JZSM JBMH VTRE QEDG XHLZ WIFU SKDQ VELN MIMI THBH DBWV HDFY \
+        HJOQ IHOR TDJD BWXE MEAY XGYQ XOHF DMYU XXNO JAZR SGHP LWML RECW WUTL RTTV LBHY OORG LGOW \
+        UXNX HMHY FAAC QEKT HSJW";
+    let plaintext = enigma.encrypt_with(cipher, PassThrough::Strip).unwrap();
+    // the received text has a few garbled letters, e.g. GRZSSADMIRAL
+    assert!(plaintext.starts_with(
+        "KRKRALLEXXFOLGENDESISTSOFORTBEKANNTZUGEBENXXICHHABEFOLGELNBEBEFEHLERHALTENXX"
+    ));
+    assert!(plaintext.contains("SETZTDERFUEHRERSIEYHVRRGRZSSADMIRALYALSSEINENNACHFOLGEREIN"));
+    assert!(plaintext.contains("REICHSLEITEIKKTULPEKKJBORMANNJ"));
+}
+
+#[test]
+fn test_models() {
+    assert!(Enigma::new(Model::I, "B", &["I", "II", "VI"], "AAA", "AAA", "").is_err());
+    assert!(Enigma::new(Model::I, "B thin", &["I", "II", "III"], "AAA", "AAA", "").is_err());
+    assert!(Enigma::new(Model::M3, "A", &["I", "II", "III"], "AAA", "AAA", "").is_err());
+    assert!(Enigma::new(Model::M3, "B", &["I", "II", "II"], "AAA", "AAA", "").is_err());
+    assert!(Enigma::new(Model::M3, "B", &["I", "II", "III"], "AA", "AAA", "").is_err());
+    assert!(Enigma::new(Model::M4, "B thin", &["I", "II", "III"], "AAA", "AAA", "").is_err());
+    assert!(Enigma::new(
+        Model::M4,
+        "B thin",
+        &["I", "Beta", "II", "III"],
+        "AAAA",
+        "AAAA",
+        ""
+    )
+    .is_err());
+
+    // an M4 with Beta at A and the thin reflector B behaves as an M3 with reflector B
+    let text = "VORHERSAGEWETTERBISKAYA";
+    let mut m3 = Enigma::new(Model::M3, "B", &["II", "IV", "I"], "AAV", "JNA", "AT BL").unwrap();
+    let mut m4 = Enigma::new(
+        Model::M4,
+        "B thin",
+        &["Beta", "II", "IV", "I"],
+        "AAAV",
+        "AJNA",
+        "AT BL",
+    )
+    .unwrap();
+    assert_eq!(m3.encrypt(text).unwrap(), m4.encrypt(text).unwrap());
+}
@@ -0,0 +1,5 @@
+pub mod bombe;
+#[allow(clippy::module_inception)]
+pub mod enigma;
+pub mod plugboard;
+pub mod rotor;
@@ -0,0 +1,76 @@
+// Plugboard (Steckerbrett) swapping pairs of letters
+use super::rotor::letter_index;
+
+/// Up to 13 cables, each swapping two letters before and after the rotors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plugboard {
+    wiring: [usize; 26],
+}
+
+impl Plugboard {
+    /// Plugboard from space-separated pairs, e.g. `"AV BS CG DL"`
+    pub fn new(pairs: &str) -> Result<Self, String> {
+        let pairs = pairs
+            .split_whitespace()
+            .map(|pair| {
+                let letters: Vec<char> = pair.chars().collect();
+                match letters[..] {
+                    [a, b] => Ok((letter_index(a)?, letter_index(b)?)),
+                    _ => Err(format!("Invalid plugboard pair {pair}")),
+                }
+            })
+            .collect::<Result<Vec<(usize, usize)>, String>>()?;
+        Self::from_pairs(&pairs)
+    }
+
+    /// Plugboard from pairs of letter indices
+    pub fn from_pairs(pairs: &[(usize, usize)]) -> Result<Self, String> {
+        let mut wiring: [usize; 26] = std::array::from_fn(|x| x);
+        for &(a, b) in pairs {
+            if a >= 26 || b >= 26 || a == b || wiring[a] != a || wiring[b] != b {
+                return Err("Each letter can be plugged at most once".to_string());
+            }
+            wiring[a] = b;
+            wiring[b] = a;
+        }
+        Ok(Self { wiring })
+    }
+
+    pub fn swap(&self, x: usize) -> usize {
+        self.wiring[x]
+    }
+
+    /// The cables as pairs of letters, e.g. `"AV BS"`
+    pub fn pairs(&self) -> String {
+        let letter = |x: usize| (b'A' + x as u8) as char;
+        self.wiring
+            .iter()
+            .enumerate()
+            .filter(|&(a, &b)| a < b)
+            .map(|(a, &b)| format!("{}{}", letter(a), letter(b)))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+impl Default for Plugboard {
+    /// No cable plugged
+    fn default() -> Self {
+        Self::from_pairs(&[]).unwrap()
+    }
+}
+
+#[test]
+fn test_plugboard() {
+    let plugboard = Plugboard::new("av BS cg").unwrap();
+    assert_eq!(plugboard.swap(0), 21);
+    assert_eq!(plugboard.swap(21), 0);
+    assert_eq!(plugboard.swap(25), 25);
+    assert_eq!(plugboard.pairs(), "AV BS CG");
+    assert_eq!(Plugboard::default().pairs(), "");
+
+    assert!(Plugboard::new("AB AC").is_err());
+    assert!(Plugboard::new("AA").is_err());
+    assert!(Plugboard::new("ABC").is_err());
+    assert!(Plugboard::new("A1").is_err());
+}
@@ -0,0 +1,268 @@
+// Rotors and reflectors of the Enigma I, M3 and M4
+
+/// Wiring of a rotor or reflector, as the letters wired to `A` to `Z`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wiring {
+    pub name: &'static str,
+    pub wiring: &'static str,
+    /// Letters shown in the window when the rotor makes its left neighbour step
+    pub notches: &'static str,
+}
+
+/// Rotors I to V of the Enigma I, VI to VIII added by the navy for the M3 and
+/// M4, and the thin Beta and Gamma rotors of the M4, which never step
+pub const ROTORS: [Wiring; 10] = [
+    Wiring {
+        name: "I",
+        wiring: "EKMFLGDQVZNTOWYHXUSPAIBRCJ",
+        notches: "Q",
+    },
+    Wiring {
+        name: "II",
+        wiring: "AJDKSIRUXBLHWTMCQGZNPYFVOE",
+        notches: "E",
+    },
+    Wiring {
+        name: "III",
+        wiring: "BDFHJLCPRTXVZNYEIWGAKMUSQO",
+        notches: "V",
+    },
+    Wiring {
+        name: "IV",
+        wiring: "ESOVPZJAYQUIRHXLNFTGKDCMWB",
+        notches: "J",
+    },
+    Wiring {
+        name: "V",
+        wiring: "VZBRGITYUPSDNHLXAWMJQOFECK",
+        notches: "Z",
+    },
+    Wiring {
+        name: "VI",
+        wiring: "JPGVOUMFYQBENHZRDKASXLICTW",
+        notches: "ZM",
+    },
+    Wiring {
+        name: "VII",
+        wiring: "NZJHGRCXMYSWBOUFAIVLPEKQDT",
+        notches: "ZM",
+    },
+    Wiring {
+        name: "VIII",
+        wiring: "FKQHTLXOCBJSPDZRAMEWNIUYGV",
+        notches: "ZM",
+    },
+    Wiring {
+        name: "Beta",
+        wiring: "LEYJVCNIXWPBQMDRTAKZGFUHOS",
+        notches: "",
+    },
+    Wiring {
+        name: "Gamma",
+        wiring: "FSOKANUERHMBTIYCWLQPZXVGJD",
+        notches: "",
+    },
+];
+
+/// Reflectors (Umkehrwalzen) A, B and C, and the thin B and C of the M4
+pub const REFLECTORS: [Wiring; 5] = [
+    Wiring {
+        name: "A",
+        wiring: "EJMZALYXVBWFCRQUONTSPIKHGD",
+        notches: "",
+    },
+    Wiring {
+        name: "B",
+        wiring: "YRUHQSLDPXNGOKMIEBFZCWVJAT",
+        notches: "",
+    },
+    Wiring {
+        name: "C",
+        wiring: "FVPJIAOYEDRZXWGCTKUQSBNMHL",
+        notches: "",
+    },
+    Wiring {
+        name: "B thin",
+        wiring: "ENKQAUYWJICOPBLMDXZVFTHRGS",
+        notches: "",
+    },
+    Wiring {
+        name: "C thin",
+        wiring: "RDOBJNTKVEHMLFCWZAXGYIPSUQ",
+        notches: "",
+    },
+];
+
+/// Wiring of the rotor named `name`
+pub fn rotor_wiring(name: &str) -> Result<&'static Wiring, String> {
+    ROTORS
+        .iter()
+        .find(|rotor| rotor.name == name)
+        .ok_or(format!("Unknown rotor {name}"))
+}
+
+/// Wiring of the reflector named `name`
+pub fn reflector_wiring(name: &str) -> Result<&'static Wiring, String> {
+    REFLECTORS
+        .iter()
+        .find(|reflector| reflector.name == name)
+        .ok_or(format!("Unknown reflector {name}"))
+}
+
+/// A rotor in the machine, with its ring setting and current position
+///
+/// The wiring turns with the rotor: a letter entering at contact $x$ goes
+/// through the wiring at $x + p - r$, where $p$ is the position shown in the
+/// window and $r$ the ring setting, and leaves shifted back by $p - r$.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rotor {
+    name: &'static str,
+    forward: [usize; 26],
+    backward: [usize; 26],
+    notches: [bool; 26],
+    ring: usize,
+    position: usize,
+}
+
+impl Rotor {
+    /// Rotor `name` with ring setting `ring` and window position `position`, both as letters
+    pub fn new(name: &str, ring: char, position: char) -> Result<Self, String> {
+        let wiring = rotor_wiring(name)?;
+        let forward = letter_table(wiring.wiring);
+        let mut backward = [0; 26];
+        for (x, &y) in forward.iter().enumerate() {
+            backward[y] = x;
+        }
+        let mut notches = [false; 26];
+        for notch in wiring.notches.chars() {
+            notches[letter_index(notch)?] = true;
+        }
+
+        Ok(Self {
+            name: wiring.name,
+            forward,
+            backward,
+            notches,
+            ring: letter_index(ring)?,
+            position: letter_index(position)?,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Letter shown in the window
+    pub fn position(&self) -> char {
+        (b'A' + self.position as u8) as char
+    }
+
+    pub fn set_position(&mut self, position: char) -> Result<(), String> {
+        self.position = letter_index(position)?;
+        Ok(())
+    }
+
+    /// Whether the next step of this rotor also steps its left neighbour
+    pub fn at_notch(&self) -> bool {
+        self.notches[self.position]
+    }
+
+    /// Whether the rotor has notches at all, Beta and Gamma do not
+    pub fn steps(&self) -> bool {
+        self.notches.contains(&true)
+    }
+
+    pub fn step(&mut self) {
+        self.position = (self.position + 1) % 26;
+    }
+
+    /// Contact reached by a letter entering at `x` on the right, going towards the reflector
+    pub fn forward(&self, x: usize) -> usize {
+        let shift = self.position + 26 - self.ring;
+        (self.forward[(x + shift) % 26] + 26 * 2 - shift) % 26
+    }
+
+    /// Contact reached by a letter entering at `x` on the left, coming back from the reflector
+    pub fn backward(&self, x: usize) -> usize {
+        let shift = self.position + 26 - self.ring;
+        (self.backward[(x + shift) % 26] + 26 * 2 - shift) % 26
+    }
+}
+
+/// A reflector, which wires the letters in 13 pairs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reflector {
+    name: &'static str,
+    wiring: [usize; 26],
+}
+
+impl Reflector {
+    pub fn new(name: &str) -> Result<Self, String> {
+        let wiring = reflector_wiring(name)?;
+        Ok(Self {
+            name: wiring.name,
+            wiring: letter_table(wiring.wiring),
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn reflect(&self, x: usize) -> usize {
+        self.wiring[x]
+    }
+}
+
+/// Index of an uppercase or lowercase letter
+pub(crate) fn letter_index(c: char) -> Result<usize, String> {
+    if !c.is_ascii_alphabetic() {
+        return Err(format!("Invalid letter {c:?}"));
+    }
+    Ok((c.to_ascii_uppercase() as u8 - b'A') as usize)
+}
+
+fn letter_table(wiring: &str) -> [usize; 26] {
+    let mut table = [0; 26];
+    for (entry, c) in table.iter_mut().zip(wiring.bytes()) {
+        *entry = (c - b'A') as usize;
+    }
+    table
+}
+
+#[test]
+fn test_wirings() {
+    for wiring in ROTORS.iter().chain(REFLECTORS.iter()) {
+        let mut letters: Vec<u8> = wiring.wiring.bytes().collect();
+        letters.sort_unstable();
+        assert_eq!(
+            letters,
+            (b'A'..=b'Z').collect::<Vec<u8>>(),
+            "{}",
+            wiring.name
+        );
+    }
+
+    // the reflectors are involutions without fixed points
+    for wiring in REFLECTORS {
+        let reflector = Reflector::new(wiring.name).unwrap();
+        for x in 0..26 {
+            assert_ne!(reflector.reflect(x), x);
+            assert_eq!(reflector.reflect(reflector.reflect(x)), x);
+        }
+    }
+
+    let mut rotor = Rotor::new("I", 'B', 'A').unwrap();
+    // with ring setting B, A is wired to K: one contact further than E
+    assert_eq!(rotor.forward(0), 10);
+    for x in 0..26 {
+        assert_eq!(rotor.backward(rotor.forward(x)), x);
+    }
+    rotor.set_position('Q').unwrap();
+    assert!(rotor.at_notch());
+    rotor.step();
+    assert_eq!(rotor.position(), 'R');
+    assert!(!Rotor::new("Beta", 'A', 'A').unwrap().steps());
+    assert!(Rotor::new("IX", 'A', 'A').is_err());
+    assert!(Reflector::new("D").is_err());
+}
@@ -0,0 +1,309 @@
+use crate::common::keystream::KeystreamBuffer;
+use crate::symmetric_encryption::traits::{seek_by_discarding, SeekError, StreamCipher};
+use thiserror::Error;
+
+/// Grain-128a stream cipher, 128-bit key and 96-bit IV, with optional authentication
+///
+/// The registers are held as in [`super::grain_v1::GrainV1`], 128 bits each,
+/// the new bits entering at bit 127. No tap is beyond position 96, so 32
+/// clocks are computed at once.
+///
+/// The first IV bit $IV_0$ selects the mode. With $IV_0 = 0$ the pre-output
+/// $y_i$ is the keystream. With $IV_0 = 1$ the first 64 pre-output bits
+/// initialize the 32-bit accumulator and shift register of the MAC, then the
+/// pre-output bits alternate between keystream $z_i = y_{64+2i}$ and the MAC
+/// shift register $r_{i+32} = y_{65+2i}$.
+#[derive(Debug, Clone)]
+pub struct Grain128a {
+    lfsr: u128,
+    nfsr: u128,
+    /// Number of clocks since the key and IV were loaded
+    count: u64,
+    authenticated: bool,
+    /// Pre-output bits of the last clocks not used yet
+    buffer: KeystreamBuffer,
+    /// MAC accumulator, $a^0$ in the most significant bit
+    accumulator: u32,
+    /// MAC shift register, $r_i$ in the most significant bit
+    register: u32,
+}
+
+impl Grain128a {
+    /// Number of clocks computed at once
+    const STEP: u32 = 32;
+
+    /// Number of clocks of the initialization, the output being fed back
+    pub const INIT_CLOCKS: u64 = 256;
+
+    /// Grain-128a from a 16-byte key and 12-byte IV, in the bit order of the
+    /// specification, ready to produce keystream
+    ///
+    /// Bit $i$ of the key, `key[i / 8] >> (7 - i % 8) & 1`, is loaded into
+    /// $b_i$ and bit $i$ of the IV into $s_i$; $s_{96}, \ldots, s_{126}$ are
+    /// set to 1 and $s_{127}$ to 0. The most significant bit of `iv[0]` is
+    /// $IV_0$: when it is set, the messages are authenticated.
+    pub fn new(key: &[u8; 16], iv: &[u8; 12]) -> Self {
+        let mut padded_iv = [0; 16];
+        padded_iv[..12].copy_from_slice(iv);
+
+        let mut grain = Self {
+            lfsr: u128::from_be_bytes(padded_iv).reverse_bits() | 0x7FFF_FFFF << 96,
+            nfsr: u128::from_be_bytes(*key).reverse_bits(),
+            count: 0,
+            authenticated: iv[0] >> 7 == 1,
+            buffer: KeystreamBuffer::new(),
+            accumulator: 0,
+            register: 0,
+        };
+        while grain.count < Self::INIT_CLOCKS {
+            grain.clock(true);
+        }
+
+        if grain.authenticated {
+            // a^j = y_j and r_j = y_{32+j}
+            grain.accumulator = (grain.clock(false) as u32).reverse_bits();
+            grain.register = (grain.clock(false) as u32).reverse_bits();
+        }
+        grain
+    }
+
+    /// Whether the messages are authenticated, $IV_0 = 1$
+    pub fn is_authenticated(&self) -> bool {
+        self.authenticated
+    }
+
+    /// Encrypt `data` in place, adding it to the MAC when authenticated
+    pub fn encrypt(&mut self, data: &mut [u8]) {
+        self.process(data, |byte, _| byte);
+    }
+
+    /// Decrypt `data` in place, adding it to the MAC when authenticated
+    pub fn decrypt(&mut self, data: &mut [u8]) {
+        self.process(data, |byte, keystream| byte ^ keystream);
+    }
+
+    /// XOR `data` with the next keystream bytes without authenticating it
+    ///
+    /// Each keystream byte holds 8 keystream bits, the first one in the
+    /// most significant bit as in the specification. When authenticated, the
+    /// MAC is computed as if these bytes were zeros.
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        self.process(data, |_, _| 0);
+    }
+
+    /// Number of keystream bits produced so far
+    pub fn position(&self) -> u64 {
+        let pre_output = self.count - Self::INIT_CLOCKS - self.buffer.len() as u64;
+        if self.authenticated {
+            (pre_output - 64) / 2
+        } else {
+            pre_output
+        }
+    }
+
+    /// The 32-bit tag of the message encrypted or decrypted so far
+    ///
+    /// The message is padded with a single 1 bit: the tag is the accumulator
+    /// after adding the shift register once more. The cipher can go on, the
+    /// next tag covering the message extended with the following bytes.
+    pub fn tag(&self) -> Result<[u8; 4], Grain128aError> {
+        if !self.authenticated {
+            return Err(Grain128aError::NotAuthenticated);
+        }
+        Ok((self.accumulator ^ self.register).to_be_bytes())
+    }
+
+    /// Check `tag` against the tag of the message encrypted or decrypted so far
+    pub fn verify(&self, tag: &[u8; 4]) -> Result<(), Grain128aError> {
+        // compare every byte, whatever the first difference
+        let difference = self
+            .tag()?
+            .iter()
+            .zip(tag)
+            .fold(0, |difference, (a, b)| difference | (a ^ b));
+        if difference != 0 {
+            return Err(Grain128aError::InvalidTag);
+        }
+        Ok(())
+    }
+
+    /// XOR `data` with the keystream, `message(byte, keystream)` giving the
+    /// message byte to authenticate
+    fn process(&mut self, data: &mut [u8], message: impl Fn(u8, u8) -> u8) {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        for byte in data.iter_mut() {
+            if !self.authenticated {
+                *byte ^= buffer.next_byte(|| self.step());
+                continue;
+            }
+
+            // 8 pairs (z_i, r_{i+32}), the first one in the top bits
+            let pairs = buffer.next_bits(16, || self.step());
+            let keystream = (0..8).fold(0, |k, j| k << 1 | (pairs >> (15 - 2 * j)) as u8 & 1);
+            let m = message(*byte, keystream);
+            for j in 0..8 {
+                if m >> (7 - j) & 1 == 1 {
+                    self.accumulator ^= self.register;
+                }
+                self.register = self.register << 1 | (pairs >> (14 - 2 * j)) as u32 & 1;
+            }
+            *byte ^= keystream;
+        }
+        self.buffer = buffer;
+    }
+
+    /// The pre-output bits of the next 32 clocks, the first one in the most significant bit
+    fn step(&mut self) -> (u64, u32) {
+        let y = self.clock(false);
+        (y.reverse_bits() >> (64 - Self::STEP), Self::STEP)
+    }
+
+    /// 32 clocks at once, returning the pre-output bits, the first one in bit 0
+    ///
+    /// During the initialization (`init`), the pre-output is XORed into the
+    /// new bits of both registers instead.
+    fn clock(&mut self, init: bool) -> u64 {
+        let mask = (1 << Self::STEP) - 1;
+        let (lfsr, nfsr) = (self.lfsr, self.nfsr);
+        let s = |i: u32| (lfsr >> i) as u64 & mask;
+        let b = |i: u32| (nfsr >> i) as u64 & mask;
+
+        let h = (b(12) & s(8))
+            ^ (s(13) & s(20))
+            ^ (b(95) & s(42))
+            ^ (s(60) & s(79))
+            ^ (b(12) & b(95) & s(94));
+        let y = h ^ s(93) ^ b(2) ^ b(15) ^ b(36) ^ b(45) ^ b(64) ^ b(73) ^ b(89);
+
+        let mut l = s(0) ^ s(7) ^ s(38) ^ s(70) ^ s(81) ^ s(96);
+        let mut f = s(0)
+            ^ b(0)
+            ^ b(26)
+            ^ b(56)
+            ^ b(91)
+            ^ b(96)
+            ^ (b(3) & b(67))
+            ^ (b(11) & b(13))
+            ^ (b(17) & b(18))
+            ^ (b(27) & b(59))
+            ^ (b(40) & b(48))
+            ^ (b(61) & b(65))
+            ^ (b(68) & b(84))
+            ^ (b(88) & b(92) & b(93) & b(95))
+            ^ (b(22) & b(24) & b(25))
+            ^ (b(70) & b(78) & b(82));
+        if init {
+            l ^= y;
+            f ^= y;
+        }
+
+        // shift the registers and feed the new bits at the top
+        let top = 128 - Self::STEP;
+        self.lfsr = (lfsr >> Self::STEP) | (l as u128) << top;
+        self.nfsr = (nfsr >> Self::STEP) | (f as u128) << top;
+
+        self.count += Self::STEP as u64;
+        y
+    }
+}
+
+impl StreamCipher for Grain128a {
+    fn apply_keystream(&mut self, data: &mut [u8]) {
+        Grain128a::apply_keystream(self, data);
+    }
+
+    /// When authenticated, the skipped bytes are added to the MAC as zeros,
+    /// as with [`Grain128a::apply_keystream`]
+    fn seek(&mut self, position: u64) -> Result<(), SeekError> {
+        seek_by_discarding(self, position)
+    }
+
+    /// [`Grain128a::position`] counts bits
+    fn current_pos(&self) -> u64 {
+        self.position() / 8
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum Grain128aError {
+    #[error("Authentication is off: the first IV bit is 0")]
+    NotAuthenticated,
+
+    #[error("Invalid tag")]
+    InvalidTag,
+}
+
+#[cfg(test)]
+fn from_hex<const N: usize>(hex: &str) -> [u8; N] {
+    use crate::common::hex::Hex;
+    Hex::hex_to_bytes(hex.as_bytes())
+        .unwrap()
+        .try_into()
+        .unwrap()
+}
+
+#[test]
+fn test_grain128a() {
+    // pre-output of the test vectors of the specification
+    let mut grain = Grain128a::new(&[0; 16], &[0; 12]);
+    assert!(!grain.is_authenticated());
+    let mut keystream = [0; 16];
+    grain.apply_keystream(&mut keystream);
+    assert_eq!(keystream, from_hex("c0207f221660650b6a952ae26586136f"));
+    assert_eq!(grain.position(), 128);
+
+    let key = from_hex("0123456789abcdef123456789abcdef0");
+    let mut keystream = [0; 16];
+    Grain128a::new(&key, &from_hex("0123456789abcdef12345678")).apply_keystream(&mut keystream);
+    assert_eq!(keystream, from_hex("f88720c13f46e6a43c07eeed89161a4d"));
+
+    // with authentication, the keystream is every other bit of the
+    // pre-output 564b362219bd90e301f259cf52bf5da9..., after the first 64
+    let mut iv = [0; 12];
+    iv[0] = 0x80;
+    let mut grain = Grain128a::new(&[0; 16], &iv);
+    assert!(grain.is_authenticated());
+    let mut keystream = [0; 16];
+    grain.encrypt(&mut keystream);
+    assert_eq!(keystream, from_hex("0d2b1f2ebc83da7e6658ee3150f9ef47"));
+    assert_eq!(grain.position(), 128);
+    assert_eq!(grain.tag(), Ok(from_hex("7e95b482")));
+
+    let mut plain = Grain128a::new(&[0; 16], &[0; 12]);
+    assert_eq!(plain.tag(), Err(Grain128aError::NotAuthenticated));
+    let mut data = *b"no tag";
+    plain.encrypt(&mut data);
+    Grain128a::new(&[0; 16], &[0; 12]).decrypt(&mut data);
+    assert_eq!(&data, b"no tag");
+}
+
+#[test]
+fn test_grain128a_authentication() {
+    let key = from_hex("0123456789abcdef123456789abcdef0");
+    let iv = from_hex("8123456789abcdef12345678");
+
+    // tag of the empty message
+    assert_eq!(Grain128a::new(&key, &iv).tag(), Ok(from_hex("d2d1bda8")));
+
+    let mut message = *b"Grain-128a";
+    let mut sender = Grain128a::new(&key, &iv);
+    sender.encrypt(&mut message[..4]);
+    sender.encrypt(&mut message[4..]);
+    assert_eq!(message, from_hex("e3eff675f946c4a48c3e"));
+    let tag = sender.tag().unwrap();
+    assert_eq!(tag, from_hex("dde27af5"));
+
+    let mut receiver = Grain128a::new(&key, &iv);
+    let mut received = message;
+    receiver.decrypt(&mut received);
+    assert_eq!(&received, b"Grain-128a");
+    assert_eq!(receiver.verify(&tag), Ok(()));
+
+    // flipping ciphertext bits flips the same plaintext bits, and changes the tag
+    message[9] ^= b'a' ^ b'b';
+    let mut receiver = Grain128a::new(&key, &iv);
+    receiver.decrypt(&mut message);
+    assert_eq!(&message, b"Grain-128b");
+    assert_eq!(receiver.verify(&tag), Err(Grain128aError::InvalidTag));
+    assert_eq!(receiver.tag(), Ok(from_hex("fe9b249f")));
+}
@@ -0,0 +1,214 @@
+use crate::common::keystream::KeystreamBuffer;
+use crate::symmetric_encryption::traits::{seek_by_discarding, SeekError, StreamCipher};
+
+/// Grain v1 stream cipher, 80-bit key and 64-bit IV
+///
+/// The 80-bit LFSR $(s_i, \ldots, s_{i+79})$ and NFSR $(b_i, \ldots, b_{i+79})$
+/// are held in one `u128` each, $s_{i+p}$ and $b_{i+p}$ in bit $p$. A clock
+/// shifts both registers right by one and feeds the new bit in at bit 79.
+///
+/// No tap is beyond position 64, so the bits read by the next 16 clocks are
+/// all in the current state: the taps of 16 clocks are 16-bit windows of the
+/// registers, the first clock in the least significant bit, and 16 clocks are
+/// computed at once.
+#[derive(Debug, Clone)]
+pub struct GrainV1 {
+    lfsr: u128,
+    nfsr: u128,
+    /// Number of clocks since the key and IV were loaded
+    count: u64,
+    /// Output bits of the last clocks not used yet
+    buffer: KeystreamBuffer,
+}
+
+impl GrainV1 {
+    const LEN: u32 = 80;
+    /// Number of clocks computed at once
+    const STEP: u32 = 16;
+
+    /// Number of clocks of the initialization, the output being fed back
+    pub const INIT_CLOCKS: u64 = 160;
+
+    /// Grain v1 from a 10-byte key and 8-byte IV, in the byte order of the
+    /// eSTREAM reference implementation, ready to produce keystream
+    ///
+    /// Bit $i$ of the key, `key[i / 8] >> (i % 8) & 1`, is loaded into $b_i$
+    /// and bit $i$ of the IV into $s_i$, $s_{64}, \ldots, s_{79}$ being set to 1.
+    pub fn new(key: &[u8; 10], iv: &[u8; 8]) -> Self {
+        let mut padded_key = [0; 16];
+        padded_key[..10].copy_from_slice(key);
+        let mut padded_iv = [0; 16];
+        padded_iv[..8].copy_from_slice(iv);
+
+        let mut grain = Self {
+            lfsr: u128::from_le_bytes(padded_iv) | 0xFFFF << 64,
+            nfsr: u128::from_le_bytes(padded_key),
+            count: 0,
+            buffer: KeystreamBuffer::new(),
+        };
+        while grain.count < Self::INIT_CLOCKS {
+            grain.clock(true);
+        }
+        grain
+    }
+
+    /// XOR `data` with the next keystream bytes, encrypting or decrypting it in place
+    ///
+    /// Each keystream byte holds 8 output bits, the first one in the least
+    /// significant bit, as in the eSTREAM test vectors.
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        for byte in data.iter_mut() {
+            *byte ^= buffer.next_byte(|| self.step()).reverse_bits();
+        }
+        self.buffer = buffer;
+    }
+
+    /// Number of keystream bits produced so far
+    pub fn position(&self) -> u64 {
+        self.count - Self::INIT_CLOCKS - self.buffer.len() as u64
+    }
+
+    /// The output bits of the next 16 clocks, the first one in the most significant bit
+    fn step(&mut self) -> (u64, u32) {
+        let z = self.clock(false);
+        (z.reverse_bits() >> (64 - Self::STEP), Self::STEP)
+    }
+
+    /// 16 clocks at once, returning the output bits, the first one in bit 0
+    ///
+    /// During the initialization (`init`), the output is XORed into the new
+    /// bits of both registers instead.
+    fn clock(&mut self, init: bool) -> u64 {
+        let mask = (1 << Self::STEP) - 1;
+        let (lfsr, nfsr) = (self.lfsr, self.nfsr);
+        let s = |i: u32| (lfsr >> i) as u64 & mask;
+        let b = |i: u32| (nfsr >> i) as u64 & mask;
+
+        // filter function h(s3, s25, s46, s64, b63)
+        let (x0, x1, x2, x3, x4) = (s(3), s(25), s(46), s(64), b(63));
+        let h = x1
+            ^ x4
+            ^ (x0 & x3)
+            ^ (x2 & x3)
+            ^ (x3 & x4)
+            ^ (x0 & x1 & x2)
+            ^ (x0 & x2 & x3)
+            ^ (x0 & x2 & x4)
+            ^ (x1 & x2 & x4)
+            ^ (x2 & x3 & x4);
+        let z = h ^ b(1) ^ b(2) ^ b(4) ^ b(10) ^ b(31) ^ b(43) ^ b(56);
+
+        let mut l = s(0) ^ s(13) ^ s(23) ^ s(38) ^ s(51) ^ s(62);
+        let mut f = s(0)
+            ^ b(0)
+            ^ b(9)
+            ^ b(14)
+            ^ b(21)
+            ^ b(28)
+            ^ b(33)
+            ^ b(37)
+            ^ b(45)
+            ^ b(52)
+            ^ b(60)
+            ^ b(62)
+            ^ (b(63) & b(60))
+            ^ (b(37) & b(33))
+            ^ (b(15) & b(9))
+            ^ (b(60) & b(52) & b(45))
+            ^ (b(33) & b(28) & b(21))
+            ^ (b(63) & b(45) & b(28) & b(9))
+            ^ (b(60) & b(52) & b(37) & b(33))
+            ^ (b(63) & b(60) & b(21) & b(15))
+            ^ (b(63) & b(60) & b(52) & b(45) & b(37))
+            ^ (b(33) & b(28) & b(21) & b(15) & b(9))
+            ^ (b(52) & b(45) & b(37) & b(33) & b(28) & b(21));
+        if init {
+            l ^= z;
+            f ^= z;
+        }
+
+        // shift the registers and feed the new bits at the top
+        let top = Self::LEN - Self::STEP;
+        self.lfsr = (lfsr >> Self::STEP) | (l as u128) << top;
+        self.nfsr = (nfsr >> Self::STEP) | (f as u128) << top;
+
+        self.count += Self::STEP as u64;
+        z
+    }
+}
+
+impl StreamCipher for GrainV1 {
+    fn apply_keystream(&mut self, data: &mut [u8]) {
+        GrainV1::apply_keystream(self, data);
+    }
+
+    fn seek(&mut self, position: u64) -> Result<(), SeekError> {
+        seek_by_discarding(self, position)
+    }
+
+    /// [`GrainV1::position`] counts bits
+    fn current_pos(&self) -> u64 {
+        self.position() / 8
+    }
+}
+
+#[test]
+fn test_grain_v1() {
+    use crate::common::hex::Hex;
+    // test vectors of the specification
+    let mut keystream = [0; 10];
+    GrainV1::new(&[0; 10], &[0; 8]).apply_keystream(&mut keystream);
+    assert_eq!(
+        format!("{}", Hex::new(&keystream).unwrap()),
+        "DEE931CF1662A72F77D0"
+    );
+
+    let key: [u8; 10] = Hex::hex_to_bytes(b"0123456789abcdef1234")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let iv: [u8; 8] = Hex::hex_to_bytes(b"0123456789abcdef")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let mut grain = GrainV1::new(&key, &iv);
+    let mut keystream = [0; 10];
+    grain.apply_keystream(&mut keystream);
+    assert_eq!(
+        format!("{}", Hex::new(&keystream).unwrap()),
+        "7F362BD3F7ABAE203664"
+    );
+    assert_eq!(grain.position(), 80);
+
+    // successive calls continue the keystream
+    let mut message = *b"hardware-oriented";
+    let mut grain = GrainV1::new(&key, &iv);
+    grain.apply_keystream(&mut message[..3]);
+    grain.apply_keystream(&mut message[3..]);
+    GrainV1::new(&key, &iv).apply_keystream(&mut message);
+    assert_eq!(&message, b"hardware-oriented");
+}
+
+#[test]
+fn test_estream_vectors() {
+    use crate::common::estream;
+
+    let vectors = estream::parse(include_str!("testvectors/grain-v1.80.64.test-vectors")).unwrap();
+    assert_eq!(vectors.len(), 80 + 256 + 256 + 4 + 64 + 4);
+
+    for vector in &vectors {
+        let key = vector.key.as_slice().try_into().unwrap();
+        let iv = vector.iv.as_slice().try_into().unwrap();
+        let mut keystream = vec![0; vector.stream_len()];
+        GrainV1::new(key, iv).apply_keystream(&mut keystream);
+
+        assert_eq!(
+            vector.check(&keystream),
+            Ok(()),
+            "Set {}, vector# {}",
+            vector.set,
+            vector.vector
+        );
+    }
+}
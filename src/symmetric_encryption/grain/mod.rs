@@ -0,0 +1,2 @@
+pub mod grain128a;
+pub mod grain_v1;
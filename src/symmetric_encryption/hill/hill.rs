@@ -0,0 +1,224 @@
+use crate::{
+    math::{
+        linear_algebra::zn::{determinant, inverse, multiply, multiply_vector, transpose, Matrix},
+        modular_arithmetic::gcd::gcd,
+    },
+    symmetric_encryption::utils::{alphabet_size_i32, default_alphabet},
+};
+
+/// Letter appended to complete the last block
+pub const PADDING: char = 'x';
+
+/// Number of plaintext blocks searched for an invertible matrix in [`known_plaintext`]
+const MAX_ATTACK_BLOCKS: usize = 24;
+
+/// Check that `key` is a valid key: square, with $\gcd(\det K, 26) = 1$
+pub fn check_key(key: &[Vec<i32>]) -> Result<(), String> {
+    let det = determinant(key, alphabet_size_i32())?;
+    if gcd(det, alphabet_size_i32()) != 1 {
+        return Err(format!(
+            "Key determinant {det} is not invertible modulo {}",
+            alphabet_size_i32()
+        ));
+    }
+    Ok(())
+}
+
+/// Key matrix filled row by row with the letters of `keyword`, e.g. `"gybnqkurp"` for $n = 3$
+pub fn key_from_keyword(keyword: &str, n: usize) -> Result<Matrix, String> {
+    let letters = letters(keyword)?;
+    if letters.len() != n * n {
+        return Err(format!("A {n}x{n} key needs {} letters", n * n));
+    }
+
+    let key: Matrix = letters.chunks(n).map(|row| row.to_vec()).collect();
+    check_key(&key)?;
+    Ok(key)
+}
+
+/// Hill Cipher Encryption
+///
+/// The text is split into blocks of $n$ letters, each block a column vector
+/// $p$ encrypted as $c = K p \bmod 26$. The last block is padded with
+/// [`PADDING`]. Case is ignored and the output is lowercase.
+pub fn encrypt(text: &str, key: &[Vec<i32>]) -> Result<String, String> {
+    check_key(key)?;
+    let n = key.len();
+
+    let mut letters = letters(text)?;
+    let padding = default_alphabet().index_of(PADDING).unwrap() as i32;
+    letters.resize(letters.len().div_ceil(n) * n, padding);
+
+    Ok(apply(key, &letters))
+}
+
+/// Hill Cipher Decryption: $p = K^{-1} c \bmod 26$
+///
+/// The padding added by [`encrypt`] is not removed, as it cannot be told
+/// apart from the plaintext.
+pub fn decrypt(cipher: &str, key: &[Vec<i32>]) -> Result<String, String> {
+    check_key(key)?;
+    let key_inverse = inverse(key, alphabet_size_i32())?;
+
+    let letters = letters(cipher)?;
+    if !letters.len().is_multiple_of(key.len()) {
+        return Err(format!(
+            "Ciphertext length is not a multiple of the block size {}",
+            key.len()
+        ));
+    }
+
+    Ok(apply(&key_inverse, &letters))
+}
+
+/// Known-plaintext attack: recover the $n \times n$ key from a plaintext and its ciphertext
+///
+/// With $n$ plaintext blocks as the columns of a matrix $P$ and the matching
+/// ciphertext blocks as the columns of $C$, $C = K P$. Whenever $P$ is
+/// invertible modulo 26, $K = C P^{-1}$. Blocks are combined until an
+/// invertible $P$ is found, and the key is then checked against every block.
+pub fn known_plaintext(plaintext: &str, cipher: &str, n: usize) -> Result<Matrix, String> {
+    let (x, y) = (letters(plaintext)?, letters(cipher)?);
+    if n == 0 || x.len() != y.len() || x.len() < n * n {
+        return Err(format!(
+            "Need two texts of the same length with at least {} letters",
+            n * n
+        ));
+    }
+
+    let plain_blocks: Vec<&[i32]> = x.chunks_exact(n).collect();
+    let cipher_blocks: Vec<&[i32]> = y.chunks_exact(n).collect();
+    let blocks = plain_blocks.len().min(MAX_ATTACK_BLOCKS);
+
+    let mut chosen = Vec::with_capacity(n);
+    let key = find_key(&plain_blocks, &cipher_blocks, blocks, n, &mut chosen)
+        .ok_or("No invertible combination of plaintext blocks".to_string())?;
+
+    let consistent = plain_blocks
+        .iter()
+        .zip(cipher_blocks.iter())
+        .all(|(p, c)| multiply_vector(&key, p, alphabet_size_i32()) == *c);
+    if !consistent {
+        return Err("Plaintext and ciphertext are not related by a Hill key".to_string());
+    }
+
+    Ok(key)
+}
+
+/// Try the combinations of `n` blocks among the first `blocks`, return $C P^{-1}$ for the first invertible $P$
+fn find_key(
+    plain_blocks: &[&[i32]],
+    cipher_blocks: &[&[i32]],
+    blocks: usize,
+    n: usize,
+    chosen: &mut Vec<usize>,
+) -> Option<Matrix> {
+    if chosen.len() == n {
+        let columns = |blocks: &[&[i32]]| -> Matrix {
+            // the blocks are the columns
+            let rows: Matrix = chosen.iter().map(|&i| blocks[i].to_vec()).collect();
+            transpose(&rows)
+        };
+        let p_inverse = inverse(&columns(plain_blocks), alphabet_size_i32()).ok()?;
+        return Some(multiply(
+            &columns(cipher_blocks),
+            &p_inverse,
+            alphabet_size_i32(),
+        ));
+    }
+
+    let start = chosen.last().map_or(0, |&i| i + 1);
+    for i in start..blocks {
+        chosen.push(i);
+        if let Some(key) = find_key(plain_blocks, cipher_blocks, blocks, n, chosen) {
+            return Some(key);
+        }
+        chosen.pop();
+    }
+    None
+}
+
+/// Multiply each block of `letters` by `matrix`
+fn apply(matrix: &[Vec<i32>], letters: &[i32]) -> String {
+    letters
+        .chunks(matrix.len())
+        .flat_map(|block| multiply_vector(matrix, block, alphabet_size_i32()))
+        .map(|index| default_alphabet().char_at(index as usize).unwrap())
+        .collect()
+}
+
+/// Indices of the letters of `text`, case-insensitive
+fn letters(text: &str) -> Result<Vec<i32>, String> {
+    text.chars()
+        .map(|c| {
+            default_alphabet()
+                .index_of_folded(c)
+                .map(|(index, _)| index as i32)
+                .ok_or("Invalid character".to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn test_hill() {
+    let key = vec![vec![3, 3], vec![2, 5]];
+    assert_eq!(encrypt("help", &key).unwrap(), "hiat");
+    assert_eq!(decrypt("hiat", &key).unwrap(), "help");
+
+    let key = key_from_keyword("GYBNQKURP", 3).unwrap();
+    assert_eq!(
+        key,
+        vec![vec![6, 24, 1], vec![13, 16, 10], vec![20, 17, 15]]
+    );
+    assert_eq!(encrypt("act", &key).unwrap(), "poh");
+    assert_eq!(encrypt("cat", &key).unwrap(), "fin");
+    assert_eq!(decrypt("POH", &key).unwrap(), "act");
+
+    // padding
+    let cipher = encrypt("attack", &[vec![3, 3], vec![2, 5]]).unwrap();
+    assert_eq!(
+        decrypt(&cipher, &[vec![3, 3], vec![2, 5]]).unwrap(),
+        "attack"
+    );
+    let cipher = encrypt("attackatdawn", &key).unwrap();
+    assert_eq!(decrypt(&cipher, &key).unwrap(), "attackatdawn");
+    let cipher = encrypt("attacks", &key).unwrap();
+    assert_eq!(cipher.len(), 9);
+    assert_eq!(decrypt(&cipher, &key).unwrap(), "attacksxx");
+    assert!(decrypt("abcd", &key).is_err());
+}
+
+#[test]
+fn test_hill_keys() {
+    // det = 2
+    assert!(check_key(&[vec![2, 4], vec![1, 3]]).is_err());
+    // det = 13
+    assert!(check_key(&[vec![13, 0], vec![0, 1]]).is_err());
+    assert!(check_key(&[vec![1, 2, 3], vec![4, 5, 6]]).is_err());
+    assert!(encrypt("abc", &[vec![2, 4], vec![1, 3]]).is_err());
+    assert!(key_from_keyword("abcd", 3).is_err());
+    assert!(encrypt("a b", &[vec![3, 3], vec![2, 5]]).is_err());
+}
+
+#[test]
+fn test_known_plaintext_hill() {
+    let key = key_from_keyword("gybnqkurp", 3).unwrap();
+    let text = "thequickbrownfoxjumpsoverthelazydog";
+    let cipher = encrypt(text, &key).unwrap();
+    assert_eq!(
+        known_plaintext(text, &cipher[..text.len()], 3).unwrap(),
+        key
+    );
+
+    let key = vec![vec![2, 13], vec![13, 2]];
+    let cipher = encrypt("meetmeatnoon", &key).unwrap();
+    assert_eq!(known_plaintext("meetmeatnoon", &cipher, 2).unwrap(), key);
+
+    // "aaaa" gives a singular plaintext matrix
+    assert!(known_plaintext("aaaa", "bbbb", 2).is_err());
+    // two blocks determine a 2x2 key, the third one does not match it
+    let mut cipher = encrypt("helpme", &[vec![3, 3], vec![2, 5]]).unwrap();
+    cipher.replace_range(5.., "a");
+    assert!(known_plaintext("helpme", &cipher, 2).is_err());
+    assert!(known_plaintext("help", "hia", 2).is_err());
+}
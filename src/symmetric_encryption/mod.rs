@@ -1,5 +1,19 @@
+pub mod a5_1;
+pub mod aes;
 pub mod affine;
 pub mod caesar;
 pub mod des;
+pub mod digraphic;
+pub mod enigma;
+pub mod grain;
+pub mod hill;
+pub mod rc4;
+pub mod salsa20;
+pub mod stream;
+pub mod substitution;
+pub mod traits;
+pub mod transposition;
 pub mod trivium;
 pub mod utils;
+pub mod vigenere;
+pub mod xor;
@@ -0,0 +1,140 @@
+use crate::symmetric_encryption::traits::{seek_by_discarding, SeekError, StreamCipher};
+use thiserror::Error;
+
+/// RC4 stream cipher, key of 1 to 256 bytes
+///
+/// The state is a permutation $S$ of the 256 byte values and two indices
+/// $i, j$. The key scheduling algorithm (KSA) mixes the key into the
+/// identity permutation, then each step of the pseudo-random generation
+/// algorithm (PRGA) swaps two entries and outputs a third one.
+#[derive(Debug, Clone)]
+pub struct Rc4 {
+    s: [u8; 256],
+    i: u8,
+    j: u8,
+    /// Number of keystream bytes produced so far
+    count: u64,
+}
+
+impl Rc4 {
+    /// RC4 after the key scheduling:
+    ///
+    /// $j \gets j + S[i] + K[i \bmod \ell]$, swap $S[i]$ and $S[j]$, for $i = 0, \ldots, 255$
+    pub fn new(key: &[u8]) -> Result<Self, Rc4Error> {
+        if key.is_empty() || key.len() > 256 {
+            return Err(Rc4Error::InvalidKeyLength(key.len()));
+        }
+
+        let mut s = [0; 256];
+        for (i, x) in s.iter_mut().enumerate() {
+            *x = i as u8;
+        }
+        let mut j = 0u8;
+        for i in 0..256 {
+            j = j.wrapping_add(s[i]).wrapping_add(key[i % key.len()]);
+            s.swap(i, j as usize);
+        }
+
+        Ok(Self {
+            s,
+            i: 0,
+            j: 0,
+            count: 0,
+        })
+    }
+
+    /// RC4 skipping the first `drop` keystream bytes, RC4-drop[`drop`]
+    ///
+    /// The first bytes are the most biased, and the key scheduling does not
+    /// hide the key well: dropping a few hundred bytes defeats the attacks of
+    /// [`crate::cryptanalysis::rc4`].
+    pub fn with_drop(key: &[u8], drop: usize) -> Result<Self, Rc4Error> {
+        let mut rc4 = Self::new(key)?;
+        for _ in 0..drop {
+            rc4.next_byte();
+        }
+        Ok(rc4)
+    }
+
+    /// XOR `data` with the next keystream bytes, encrypting or decrypting it in place
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            *byte ^= self.next_byte();
+        }
+    }
+
+    /// Number of keystream bytes produced so far, the dropped ones included
+    pub fn position(&self) -> u64 {
+        self.count
+    }
+
+    /// One step of the PRGA:
+    ///
+    /// $i \gets i + 1$, $j \gets j + S[i]$, swap $S[i]$ and $S[j]$, output $S[S[i] + S[j]]$
+    fn next_byte(&mut self) -> u8 {
+        self.i = self.i.wrapping_add(1);
+        self.j = self.j.wrapping_add(self.s[self.i as usize]);
+        self.s.swap(self.i as usize, self.j as usize);
+        self.count += 1;
+        let t = self.s[self.i as usize].wrapping_add(self.s[self.j as usize]);
+        self.s[t as usize]
+    }
+}
+
+impl StreamCipher for Rc4 {
+    fn apply_keystream(&mut self, data: &mut [u8]) {
+        Rc4::apply_keystream(self, data);
+    }
+
+    fn seek(&mut self, position: u64) -> Result<(), SeekError> {
+        seek_by_discarding(self, position)
+    }
+
+    fn current_pos(&self) -> u64 {
+        self.position()
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum Rc4Error {
+    #[error("Invalid key length: expected 1 to 256 bytes, got {0} bytes")]
+    InvalidKeyLength(usize),
+}
+
+#[test]
+fn test_rc4() {
+    use crate::common::hex::Hex;
+
+    for (key, plain_text, cipher_text) in [
+        ("Key", "Plaintext", "BBF316E8D940AF0AD3"),
+        ("Wiki", "pedia", "1021BF0420"),
+        ("Secret", "Attack at dawn", "45A01F645FC35B383552544B9BF5"),
+    ] {
+        let mut data = plain_text.as_bytes().to_vec();
+        Rc4::new(key.as_bytes()).unwrap().apply_keystream(&mut data);
+        assert_eq!(data, Hex::hex_to_bytes(cipher_text.as_bytes()).unwrap());
+    }
+
+    // RFC 6229, 40-bit key, offset 0
+    let mut rc4 = Rc4::new(&[1, 2, 3, 4, 5]).unwrap();
+    let mut keystream = [0; 16];
+    rc4.apply_keystream(&mut keystream[..7]);
+    rc4.apply_keystream(&mut keystream[7..]);
+    assert_eq!(
+        keystream.to_vec(),
+        Hex::hex_to_bytes(b"B2396305F03DC027CCC3524A0A1118A8").unwrap()
+    );
+    assert_eq!(rc4.position(), 16);
+
+    let mut dropped = [0; 8];
+    Rc4::with_drop(&[1, 2, 3, 4, 5], 8)
+        .unwrap()
+        .apply_keystream(&mut dropped);
+    assert_eq!(dropped, keystream[8..]);
+
+    assert_eq!(Rc4::new(&[]).unwrap_err(), Rc4Error::InvalidKeyLength(0));
+    assert_eq!(
+        Rc4::new(&[0; 257]).unwrap_err(),
+        Rc4Error::InvalidKeyLength(257)
+    );
+}
@@ -0,0 +1,214 @@
+use super::counter::{words, CounterMode};
+use crate::rng::chacha::{chacha_block, chacha_rounds, CONSTANTS};
+use crate::symmetric_encryption::traits::{SeekError, StreamCipher};
+
+/// Input matrix of ChaCha for `key` and the 4 words of the last row, the
+/// counter and the nonce
+fn chacha_input(key: &[u8; 32], last_row: [u32; 4]) -> [u32; 16] {
+    let mut input = [0; 16];
+    input[..4].copy_from_slice(&CONSTANTS);
+    input[4..12].copy_from_slice(&words::<8>(key));
+    input[12..].copy_from_slice(&last_row);
+    input
+}
+
+/// HChaCha20, a subkey derived from `key` and a 128-bit `nonce`
+///
+/// The nonce fills the last row, and the subkey is made of the first and
+/// last rows after the 20 rounds, without the final addition.
+pub fn hchacha20(key: &[u8; 32], nonce: &[u8; 16]) -> [u8; 32] {
+    let mut state = chacha_input(key, words(nonce));
+    chacha_rounds(&mut state, 20);
+
+    let mut subkey = [0; 32];
+    for (bytes, word) in subkey
+        .chunks_mut(4)
+        .zip(state[..4].iter().chain(&state[12..]))
+    {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    subkey
+}
+
+/// ChaCha20 stream cipher, 256-bit key
+///
+/// The keystream is the ChaCha block function of [`crate::rng::chacha`] in
+/// counter mode, the last row of the input holding the counter and the
+/// nonce:
+///
+/// - RFC 8439: a 32-bit counter and a 96-bit nonce, 256 GiB per nonce;
+/// - original: a 64-bit counter and a 64-bit nonce;
+/// - XChaCha20: a 192-bit nonce, through a subkey.
+#[derive(Debug, Clone)]
+pub struct ChaCha20 {
+    stream: CounterMode,
+}
+
+impl ChaCha20 {
+    /// ChaCha20 with a 96-bit nonce and a 32-bit counter, as in RFC 8439
+    ///
+    /// The counter starts at 0: RFC 8439 encrypts from block 1, block 0
+    /// giving the Poly1305 key, so encryption there starts with `seek(64)`.
+    pub fn new(key: &[u8; 32], nonce: &[u8; 12]) -> Self {
+        let nonce: [u32; 3] = words(nonce);
+        let input = chacha_input(key, [0, nonce[0], nonce[1], nonce[2]]);
+        Self {
+            stream: CounterMode::new(input, 12, 1, |input| chacha_block(input, 20)),
+        }
+    }
+
+    /// ChaCha20 with a 64-bit nonce and a 64-bit counter, as in the original design
+    pub fn original(key: &[u8; 32], nonce: &[u8; 8]) -> Self {
+        let nonce: [u32; 2] = words(nonce);
+        let input = chacha_input(key, [0, 0, nonce[0], nonce[1]]);
+        Self {
+            stream: CounterMode::new(input, 12, 2, |input| chacha_block(input, 20)),
+        }
+    }
+
+    /// XChaCha20, with a 192-bit nonce
+    ///
+    /// The first 128 bits of the nonce derive a subkey with [`hchacha20`],
+    /// used with a 96-bit nonce made of 4 zero bytes and the last 64 bits.
+    pub fn xchacha20(key: &[u8; 32], nonce: &[u8; 24]) -> Self {
+        let subkey = hchacha20(key, nonce[..16].try_into().unwrap());
+        let mut short_nonce = [0; 12];
+        short_nonce[4..].copy_from_slice(&nonce[16..]);
+        Self::new(&subkey, &short_nonce)
+    }
+
+    /// XOR `data` with the next keystream bytes, encrypting or decrypting it in place
+    ///
+    /// # Panics
+    /// When the 32-bit counter would wrap, after 256 GiB of keystream.
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        self.stream.apply_keystream(data);
+    }
+
+    /// Move to the keystream byte at `position`, block `position / 64`
+    pub fn seek(&mut self, position: u64) -> Result<(), SeekError> {
+        self.stream.seek(position)
+    }
+
+    /// Number of keystream bytes used so far, or the position set by [`ChaCha20::seek`]
+    pub fn position(&self) -> u64 {
+        self.stream.position()
+    }
+}
+
+impl StreamCipher for ChaCha20 {
+    fn apply_keystream(&mut self, data: &mut [u8]) {
+        ChaCha20::apply_keystream(self, data);
+    }
+
+    fn seek(&mut self, position: u64) -> Result<(), SeekError> {
+        ChaCha20::seek(self, position)
+    }
+
+    fn current_pos(&self) -> u64 {
+        self.position()
+    }
+}
+
+#[test]
+fn test_chacha20() {
+    use crate::common::hex::Hex;
+
+    // RFC 8439 section 2.4.2, from block 1
+    let key: [u8; 32] = core::array::from_fn(|i| i as u8);
+    let nonce = Hex::hex_to_bytes(b"000000000000004a00000000").unwrap();
+    let mut chacha = ChaCha20::new(&key, &nonce.try_into().unwrap());
+    chacha.seek(64).unwrap();
+    let mut data = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.".to_vec();
+    chacha.apply_keystream(&mut data);
+    assert_eq!(
+        data,
+        Hex::hex_to_bytes(
+            b"6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0b\
+              f91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d8\
+              07ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab7793736\
+              5af90bbf74a35be6b40b8eedf2785e42874d"
+        )
+        .unwrap()
+    );
+    assert_eq!(chacha.position(), 64 + 114);
+
+    // the original layout with a zero nonce is the ChaCha20 generator
+    let mut keystream = [0; 100];
+    ChaCha20::original(&key, &[0; 8]).apply_keystream(&mut keystream);
+    let mut rng = crate::rng::chacha::ChaChaRng::new(&key, 20).unwrap();
+    let mut expected = [0; 100];
+    crate::rng::traits::Rng::fill_bytes(&mut rng, &mut expected);
+    assert_eq!(keystream, expected);
+}
+
+#[test]
+fn test_chacha20_seek() {
+    let key = [7; 32];
+    let mut keystream = vec![0; 300];
+    ChaCha20::new(&key, &[1; 12]).apply_keystream(&mut keystream);
+
+    // any byte can be reached, within or across blocks
+    let mut chacha = ChaCha20::new(&key, &[1; 12]);
+    for start in [250, 3, 64, 127] {
+        chacha.seek(start).unwrap();
+        let mut part = vec![0; 300 - start as usize];
+        chacha.apply_keystream(&mut part);
+        assert_eq!(part, keystream[start as usize..]);
+    }
+
+    // the 32-bit counter ends after 2^32 blocks, the 64-bit one does not
+    let end = 64 << 32;
+    assert_eq!(chacha.seek(end), Ok(()));
+    assert_eq!(chacha.seek(end + 1), Err(SeekError::PastEnd(end + 1)));
+    let mut original = ChaCha20::original(&key, &[1; 8]);
+    assert_eq!(original.seek(end + 1), Ok(()));
+    original.apply_keystream(&mut [0; 64]);
+    assert_eq!(original.position(), end + 65);
+
+    // the 64-bit counter is cut at the largest position
+    assert_eq!(original.seek(u64::MAX), Ok(()));
+    original.apply_keystream(&mut []);
+    assert_eq!(original.position(), u64::MAX);
+    assert_eq!(original.stream.keystream_len(), u64::MAX);
+}
+
+#[test]
+fn test_xchacha20() {
+    use crate::common::hex::Hex;
+
+    // draft-irtf-cfrg-xchacha section 2.2.1
+    let key: [u8; 32] = core::array::from_fn(|i| i as u8);
+    let nonce = Hex::hex_to_bytes(b"000000090000004a0000000031415927").unwrap();
+    assert_eq!(
+        hchacha20(&key, &nonce.try_into().unwrap()).to_vec(),
+        Hex::hex_to_bytes(b"82413b4227b27bfed30e42508a877d73a0f9e4d58a74a853c12ec41326d3ecdc")
+            .unwrap()
+    );
+
+    // draft-irtf-cfrg-xchacha appendix A.3.2
+    let key: [u8; 32] = core::array::from_fn(|i| 0x80 + i as u8);
+    let nonce = Hex::hex_to_bytes(b"404142434445464748494a4b4c4d4e4f5051525354555658").unwrap();
+    let mut data = b"The dhole (pronounced \"dole\") is also known as the Asiatic wild dog, \
+        red dog, and whistling dog. It is about the size of a German shepherd but looks more \
+        like a long-legged fox. This highly elusive and skilled jumper is classified with \
+        wolves, coyotes, jackals, and foxes in the taxonomic family Canidae."
+        .to_vec();
+    ChaCha20::xchacha20(&key, &nonce.try_into().unwrap()).apply_keystream(&mut data);
+    assert_eq!(
+        data,
+        Hex::hex_to_bytes(
+            b"4559abba4e48c16102e8bb2c05e6947f50a786de162f9b0b7e592a9b53d0d4e9\
+              8d8d6410d540a1a6375b26d80dace4fab52384c731acbf16a5923c0c48d3575d\
+              4d0d2c673b666faa731061277701093a6bf7a158a8864292a41c48e3a9b4c0da\
+              ece0f8d98d0d7e05b37a307bbb66333164ec9e1b24ea0d6c3ffddcec4f68e744\
+              3056193a03c810e11344ca06d8ed8a2bfb1e8d48cfa6bc0eb4e2464b74814240\
+              7c9f431aee769960e15ba8b96890466ef2457599852385c661f752ce20f9da0c\
+              09ab6b19df74e76a95967446f8d0fd415e7bee2a12a114c20eb5292ae7a349ae\
+              577820d5520a1f3fb62a17ce6a7e68fa7c79111d8860920bc048ef43fe84486c\
+              cb87c25f0ae045f0cce1e7989a9aa220a28bdd4827e751a24a6d5c62d790a663\
+              93b93111c1a55dd7421a10184974c7c5"
+        )
+        .unwrap()
+    );
+}
@@ -0,0 +1,113 @@
+// Keystream of a 64-byte block function in counter mode, shared by Salsa20 and ChaCha20
+use crate::symmetric_encryption::traits::SeekError;
+
+/// Block function of 16 words, the input holding the key, nonce and counter
+pub type BlockFunction = fn(&[u32; 16]) -> [u32; 16];
+
+/// Keystream made of the blocks of a block function for the counter values
+/// $0, 1, 2, \ldots$, each block serialized as 16 little-endian words
+///
+/// The counter is one word of the input (32 bits) or two consecutive words,
+/// low word first (64 bits). Any block can be computed directly from its
+/// counter, so the keystream can be entered at any byte with
+/// [`CounterMode::seek`].
+#[derive(Debug, Clone)]
+pub struct CounterMode {
+    input: [u32; 16],
+    /// Index of the (low) counter word in the input
+    counter_word: usize,
+    /// Number of counter words, 1 or 2
+    counter_words: usize,
+    block_function: BlockFunction,
+    /// Position of the next keystream byte
+    position: u64,
+    /// Counter of the block held in `block`
+    block_counter: Option<u64>,
+    block: [u8; 64],
+}
+
+impl CounterMode {
+    pub fn new(
+        input: [u32; 16],
+        counter_word: usize,
+        counter_words: usize,
+        block_function: BlockFunction,
+    ) -> Self {
+        Self {
+            input,
+            counter_word,
+            counter_words,
+            block_function,
+            position: 0,
+            block_counter: None,
+            block: [0; 64],
+        }
+    }
+
+    /// Number of keystream bytes that can be reached, 64 bytes per counter value
+    ///
+    /// A 64-bit counter gives $2^{70}$ bytes, but the position is a `u64`:
+    /// the keystream is cut at $2^{64} - 1$ bytes so that it cannot overflow.
+    pub fn keystream_len(&self) -> u64 {
+        (64u128 << (32 * self.counter_words)).min(u64::MAX as u128) as u64
+    }
+
+    /// Position of the next keystream byte
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Move to the keystream byte at `position`, in block `position / 64`
+    pub fn seek(&mut self, position: u64) -> Result<(), SeekError> {
+        if position > self.keystream_len() {
+            return Err(SeekError::PastEnd(position));
+        }
+        self.position = position;
+        Ok(())
+    }
+
+    /// XOR `data` with the keystream from the current position
+    ///
+    /// # Panics
+    /// When `data` goes past the end of the keystream: with a 32-bit counter
+    /// the keystream has 256 GiB, and reusing it would leak the XOR of the
+    /// messages.
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        assert!(
+            data.len() as u64 <= self.keystream_len() - self.position,
+            "the keystream ends after {} bytes",
+            self.keystream_len()
+        );
+
+        for byte in data.iter_mut() {
+            let counter = self.position / 64;
+            if self.block_counter != Some(counter) {
+                self.refill(counter);
+            }
+            *byte ^= self.block[(self.position % 64) as usize];
+            self.position += 1;
+        }
+    }
+
+    fn refill(&mut self, counter: u64) {
+        self.input[self.counter_word] = counter as u32;
+        if self.counter_words == 2 {
+            self.input[self.counter_word + 1] = (counter >> 32) as u32;
+        }
+
+        let block = (self.block_function)(&self.input);
+        for (bytes, word) in self.block.chunks_mut(4).zip(block) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        self.block_counter = Some(counter);
+    }
+}
+
+/// The little-endian words of `bytes`, whose length is a multiple of 4
+pub fn words<const N: usize>(bytes: &[u8]) -> [u32; N] {
+    let mut words = [0; N];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks(4)) {
+        *word = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    words
+}
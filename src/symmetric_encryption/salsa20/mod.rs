@@ -0,0 +1,4 @@
+pub mod chacha20;
+pub mod counter;
+#[allow(clippy::module_inception)]
+pub mod salsa20;
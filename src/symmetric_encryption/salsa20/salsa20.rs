@@ -0,0 +1,218 @@
+use super::counter::{words, CounterMode};
+use crate::rng::chacha::CONSTANTS;
+use crate::symmetric_encryption::traits::{SeekError, StreamCipher};
+
+/// Salsa20 quarter round on the words `a`, `b`, `c` and `d` of the state
+///
+/// ```text
+/// b ^= (a + d) <<< 7;
+/// c ^= (b + a) <<< 9;
+/// d ^= (c + b) <<< 13;
+/// a ^= (d + c) <<< 18;
+/// ```
+pub fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[b] ^= state[a].wrapping_add(state[d]).rotate_left(7);
+    state[c] ^= state[b].wrapping_add(state[a]).rotate_left(9);
+    state[d] ^= state[c].wrapping_add(state[b]).rotate_left(13);
+    state[a] ^= state[d].wrapping_add(state[c]).rotate_left(18);
+}
+
+/// The `rounds` rounds of Salsa20 on `state`, without the final addition
+///
+/// Each double round applies the quarter round to the 4 columns, each one
+/// starting on the diagonal, then to the 4 rows.
+pub fn salsa20_rounds(state: &mut [u32; 16], rounds: usize) {
+    for _ in 0..rounds / 2 {
+        // column round
+        quarter_round(state, 0, 4, 8, 12);
+        quarter_round(state, 5, 9, 13, 1);
+        quarter_round(state, 10, 14, 2, 6);
+        quarter_round(state, 15, 3, 7, 11);
+        // row round
+        quarter_round(state, 0, 1, 2, 3);
+        quarter_round(state, 5, 6, 7, 4);
+        quarter_round(state, 10, 11, 8, 9);
+        quarter_round(state, 15, 12, 13, 14);
+    }
+}
+
+/// Salsa20 block function
+///
+/// The 4x4 input matrix is
+///
+/// ```text
+/// cccccccc  kkkkkkkk  kkkkkkkk  kkkkkkkk
+/// kkkkkkkk  cccccccc  nnnnnnnn  nnnnnnnn
+/// bbbbbbbb  bbbbbbbb  cccccccc  kkkkkkkk
+/// kkkkkkkk  kkkkkkkk  kkkkkkkk  cccccccc
+/// ```
+///
+/// with c = constant, k = key, n = nonce, b = block counter: the constants
+/// are on the diagonal. The input is added to the state after the rounds.
+pub fn salsa20_block(input: &[u32; 16], rounds: usize) -> [u32; 16] {
+    let mut state = *input;
+    salsa20_rounds(&mut state, rounds);
+
+    for (word, input_word) in state.iter_mut().zip(input.iter()) {
+        *word = word.wrapping_add(*input_word);
+    }
+
+    state
+}
+
+/// Input matrix of Salsa20 for `key` and the 4 words of `middle`, the nonce
+/// and the counter
+fn salsa20_input(key: &[u8; 32], middle: [u32; 4]) -> [u32; 16] {
+    let key: [u32; 8] = words(key);
+    [
+        CONSTANTS[0],
+        key[0],
+        key[1],
+        key[2],
+        key[3],
+        CONSTANTS[1],
+        middle[0],
+        middle[1],
+        middle[2],
+        middle[3],
+        CONSTANTS[2],
+        key[4],
+        key[5],
+        key[6],
+        key[7],
+        CONSTANTS[3],
+    ]
+}
+
+/// HSalsa20, a subkey derived from `key` and a 128-bit `nonce`
+///
+/// The nonce fills the 4 words of the nonce and the counter, and the subkey
+/// is made of the 8 words at the positions of the constants and of the
+/// input, 0, 5, 10, 15 and 6 to 9, after the 20 rounds, without the final
+/// addition: the words added would be known to an attacker.
+pub fn hsalsa20(key: &[u8; 32], nonce: &[u8; 16]) -> [u8; 32] {
+    let mut state = salsa20_input(key, words(nonce));
+    salsa20_rounds(&mut state, 20);
+
+    let mut subkey = [0; 32];
+    for (bytes, i) in subkey.chunks_mut(4).zip([0, 5, 10, 15, 6, 7, 8, 9]) {
+        bytes.copy_from_slice(&state[i].to_le_bytes());
+    }
+    subkey
+}
+
+/// Salsa20/20 stream cipher, 256-bit key
+///
+/// The keystream is the Salsa20 block function in counter mode: a 64-bit
+/// nonce and a 64-bit block counter, starting at 0. XSalsa20 extends the
+/// nonce to 192 bits.
+#[derive(Debug, Clone)]
+pub struct Salsa20 {
+    stream: CounterMode,
+}
+
+impl Salsa20 {
+    /// Salsa20 with a 64-bit nonce, as in the original specification
+    pub fn new(key: &[u8; 32], nonce: &[u8; 8]) -> Self {
+        let nonce: [u32; 2] = words(nonce);
+        let input = salsa20_input(key, [nonce[0], nonce[1], 0, 0]);
+        Self {
+            stream: CounterMode::new(input, 8, 2, |input| salsa20_block(input, 20)),
+        }
+    }
+
+    /// XSalsa20, with a 192-bit nonce
+    ///
+    /// The first 128 bits of the nonce derive a subkey with [`hsalsa20`],
+    /// used with the last 64 bits: nonces can be chosen at random without
+    /// fear of collisions.
+    pub fn xsalsa20(key: &[u8; 32], nonce: &[u8; 24]) -> Self {
+        let subkey = hsalsa20(key, nonce[..16].try_into().unwrap());
+        Self::new(&subkey, nonce[16..].try_into().unwrap())
+    }
+
+    /// XOR `data` with the next keystream bytes, encrypting or decrypting it in place
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        self.stream.apply_keystream(data);
+    }
+
+    /// Move to the keystream byte at `position`
+    pub fn seek(&mut self, position: u64) -> Result<(), SeekError> {
+        self.stream.seek(position)
+    }
+
+    /// Number of keystream bytes used so far, or the position set by [`Salsa20::seek`]
+    pub fn position(&self) -> u64 {
+        self.stream.position()
+    }
+}
+
+impl StreamCipher for Salsa20 {
+    fn apply_keystream(&mut self, data: &mut [u8]) {
+        Salsa20::apply_keystream(self, data);
+    }
+
+    fn seek(&mut self, position: u64) -> Result<(), SeekError> {
+        Salsa20::seek(self, position)
+    }
+
+    fn current_pos(&self) -> u64 {
+        self.position()
+    }
+}
+
+#[test]
+fn test_salsa20() {
+    use crate::common::hex::Hex;
+
+    // eSTREAM, 256-bit key, set 1, vector 0
+    let mut key = [0; 32];
+    key[0] = 0x80;
+    let mut salsa = Salsa20::new(&key, &[0; 8]);
+    let mut keystream = [0; 64];
+    salsa.apply_keystream(&mut keystream);
+    assert_eq!(
+        keystream.to_vec(),
+        Hex::hex_to_bytes(
+            b"E3BE8FDD8BECA2E3EA8EF9475B29A6E7003951E1097A5C38D23B7A5FAD9F6844\
+              B22C97559E2723C7CBBD3FE4FC8D9A0744652A83E72A9C461876AF4D7EF1A117"
+        )
+        .unwrap()
+    );
+
+    // stream[192..207], reached by seeking into the fourth block
+    salsa.seek(192).unwrap();
+    let mut keystream = [0; 16];
+    salsa.apply_keystream(&mut keystream);
+    assert_eq!(
+        keystream.to_vec(),
+        Hex::hex_to_bytes(b"57BE81F47B17D9AE7C4FF15429A73E10").unwrap()
+    );
+    assert_eq!(salsa.position(), 208);
+}
+
+#[test]
+fn test_xsalsa20() {
+    use crate::common::hex::Hex;
+
+    // NaCl: the first subkey of the crypto_box example
+    let key =
+        Hex::hex_to_bytes(b"4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742")
+            .unwrap();
+    assert_eq!(
+        hsalsa20(&key.try_into().unwrap(), &[0; 16]).to_vec(),
+        Hex::hex_to_bytes(b"1b27556473e985d462cd51197a9a46c76009549eac6474f206c4ee0844f68389")
+            .unwrap()
+    );
+
+    let mut message = *b"Hello world!";
+    Salsa20::xsalsa20(
+        b"this is 32-byte key for xsalsa20",
+        b"24-byte nonce for xsalsa",
+    )
+    .apply_keystream(&mut message);
+    assert_eq!(
+        message.to_vec(),
+        Hex::hex_to_bytes(b"002d4513843fc240c401e541").unwrap()
+    );
+}
@@ -0,0 +1,80 @@
+// std::io adapters encrypting or decrypting the bytes going through them with a stream cipher
+use super::traits::StreamCipher;
+use std::io::{self, Read, Write};
+
+/// Reader XORing the bytes read from `inner` with the keystream
+///
+/// Reading from a ciphertext decrypts it, reading from a plaintext encrypts it.
+#[derive(Debug)]
+pub struct StreamReader<R, C> {
+    inner: R,
+    cipher: C,
+}
+
+impl<R: Read, C: StreamCipher> StreamReader<R, C> {
+    pub fn new(inner: R, cipher: C) -> Self {
+        Self { inner, cipher }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// The underlying reader and the cipher, positioned after the bytes read
+    pub fn into_inner(self) -> (R, C) {
+        (self.inner, self.cipher)
+    }
+}
+
+impl<R: Read, C: StreamCipher> Read for StreamReader<R, C> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.cipher.apply_keystream(&mut buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Writer XORing the bytes written with the keystream before passing them to `inner`
+///
+/// Each call writes the whole buffer to `inner`, the keystream having been
+/// used for all of it: after an error, the cipher is no longer in step with
+/// the data written.
+#[derive(Debug)]
+pub struct StreamWriter<W, C> {
+    inner: W,
+    cipher: C,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write, C: StreamCipher> StreamWriter<W, C> {
+    pub fn new(inner: W, cipher: C) -> Self {
+        Self {
+            inner,
+            cipher,
+            buffer: Vec::new(),
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// The underlying writer and the cipher, positioned after the bytes written
+    pub fn into_inner(self) -> (W, C) {
+        (self.inner, self.cipher)
+    }
+}
+
+impl<W: Write, C: StreamCipher> Write for StreamWriter<W, C> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.clear();
+        self.buffer.extend_from_slice(buf);
+        self.cipher.apply_keystream(&mut self.buffer);
+        self.inner.write_all(&self.buffer)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{
+    math::modular_arithmetic::mod_inverse::mod_inverse,
+    symmetric_encryption::utils::{default_alphabet, substitute, Alphabet, PassThrough},
+};
+
+/// Encryption over the lowercase alphabet, `key` is the cipher alphabet:
+/// the $i$-th letter of the alphabet is replaced by the $i$-th letter of the key
+pub fn encrypt(text: &str, key: &str) -> Result<String, String> {
+    encrypt_with(text, key, default_alphabet(), PassThrough::Reject)
+}
+
+pub fn decrypt(cipher: &str, key: &str) -> Result<String, String> {
+    decrypt_with(cipher, key, default_alphabet(), PassThrough::Reject)
+}
+
+/// Monoalphabetic substitution with an arbitrary permutation of the alphabet
+///
+/// Uppercase characters missing from the alphabet are substituted as their
+/// lowercase form and converted back, the other characters outside the
+/// alphabet are handled according to `policy`.
+pub fn encrypt_with(
+    text: &str,
+    key: &str,
+    alphabet: &Alphabet,
+    policy: PassThrough,
+) -> Result<String, String> {
+    let permutation = check_key(key, alphabet)?;
+    substitute(text, alphabet, policy, |x| permutation[x])
+}
+
+pub fn decrypt_with(
+    cipher: &str,
+    key: &str,
+    alphabet: &Alphabet,
+    policy: PassThrough,
+) -> Result<String, String> {
+    let inverse = invert(&check_key(key, alphabet)?);
+    substitute(cipher, alphabet, policy, |y| inverse[y])
+}
+
+/// Atbash: the alphabet reversed, $y = n - 1 - x$, its own inverse
+pub fn atbash(text: &str) -> Result<String, String> {
+    encrypt(text, &atbash_key())
+}
+
+/// `"zyxwvutsrqponmlkjihgfedcba"`
+pub fn atbash_key() -> String {
+    default_alphabet().chars().iter().rev().collect()
+}
+
+/// Cipher alphabet derived from a keyword: its distinct letters, then the
+/// rest of the alphabet in order
+///
+/// `"zebras"` gives `"zebrascdfghijklmnopqtuvwxy"`.
+pub fn key_from_keyword(keyword: &str) -> Result<String, String> {
+    let alphabet = default_alphabet();
+    let mut used = vec![false; alphabet.len()];
+    let mut key = String::with_capacity(alphabet.len());
+
+    let keyword_letters = keyword
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            alphabet
+                .index_of_folded(c)
+                .map(|(index, _)| index)
+                .ok_or("Invalid key".to_string())
+        })
+        .collect::<Result<Vec<usize>, String>>()?;
+
+    for index in keyword_letters.into_iter().chain(0..alphabet.len()) {
+        if !used[index] {
+            used[index] = true;
+            key.push(alphabet.char_at(index).unwrap());
+        }
+    }
+
+    Ok(key)
+}
+
+/// The Caesar cipher as a substitution key: $x \mapsto x + k$
+pub fn caesar_key(shift: i32) -> String {
+    affine_key(1, shift).unwrap()
+}
+
+/// The affine cipher as a substitution key: $x \mapsto ax + b$
+pub fn affine_key(a: i32, b: i32) -> Result<String, String> {
+    let alphabet = default_alphabet();
+    let n = alphabet.len() as i32;
+    // reduced first, a * x + b could overflow
+    let (a, b) = (a.rem_euclid(n), b.rem_euclid(n));
+    // a must be invertible for the map to be a permutation
+    mod_inverse(a, n)?;
+
+    Ok((0..n)
+        .map(|x| alphabet.char_at(((a * x + b) % n) as usize).unwrap())
+        .collect())
+}
+
+/// Indices of the key letters, which must be a permutation of the alphabet
+pub fn check_key(key: &str, alphabet: &Alphabet) -> Result<Vec<usize>, String> {
+    let mut seen = vec![false; alphabet.len()];
+    let permutation = key
+        .chars()
+        .map(|c| {
+            let (index, _) = alphabet.index_of_folded(c).ok_or("Invalid key")?;
+            if std::mem::replace(&mut seen[index], true) {
+                return Err("Invalid key");
+            }
+            Ok(index)
+        })
+        .collect::<Result<Vec<usize>, &str>>()?;
+
+    if permutation.len() != alphabet.len() {
+        return Err(format!(
+            "The key must be a permutation of the {} letters of the alphabet",
+            alphabet.len()
+        ));
+    }
+
+    Ok(permutation)
+}
+
+/// Inverse permutation
+pub fn invert(permutation: &[usize]) -> Vec<usize> {
+    let mut inverse = vec![0; permutation.len()];
+    for (x, &y) in permutation.iter().enumerate() {
+        inverse[y] = x;
+    }
+    inverse
+}
+
+#[test]
+fn test_substitution() {
+    let key = key_from_keyword("zebras").unwrap();
+    assert_eq!(key, "zebrascdfghijklmnopqtuvwxy");
+
+    let alphabet = default_alphabet();
+    let text = "Flee at once. We are discovered!";
+    let cipher = encrypt_with(text, &key, alphabet, PassThrough::Preserve).unwrap();
+    assert_eq!(cipher, "Siaa zq lkba. Va zoa rfpbluaoar!");
+    assert_eq!(
+        decrypt_with(&cipher, &key, alphabet, PassThrough::Preserve).unwrap(),
+        text
+    );
+
+    assert_eq!(
+        check_key("abc", alphabet).unwrap_err(),
+        "The key must be a permutation of the 26 letters of the alphabet"
+    );
+    assert!(encrypt("abc", "aacdefghijklmnopqrstuvwxyz").is_err());
+    assert!(key_from_keyword("k3y").is_err());
+}
+
+#[test]
+fn test_special_cases() {
+    use crate::symmetric_encryption::{affine::affine, caesar::caesar};
+
+    assert_eq!(atbash("wizard").unwrap(), "draziw");
+    assert_eq!(
+        atbash(&atbash("attackatdawn").unwrap()).unwrap(),
+        "attackatdawn"
+    );
+    assert_eq!(atbash_key(), affine_key(-1, -1).unwrap());
+
+    let text = "thequickbrownfoxjumpsoverthelazydog";
+    assert_eq!(
+        encrypt(text, &caesar_key(3)).unwrap(),
+        caesar::encrypt(text, 3).unwrap()
+    );
+    assert_eq!(
+        encrypt(text, &affine_key(5, 8).unwrap()).unwrap(),
+        affine::encrypt(text, 5, 8).unwrap()
+    );
+    assert!(affine_key(13, 1).is_err());
+    assert_eq!(affine_key(i32::MAX, i32::MIN), affine_key(23, 2));
+}
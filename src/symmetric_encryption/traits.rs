@@ -0,0 +1,165 @@
+use std::fmt::Debug;
+use thiserror::Error;
+
+/// Common interface of the classical ciphers
+///
+/// A cipher value carries its configuration (alphabet, handling of the
+/// characters outside it) and the key is passed to each call, so generic
+/// code such as [`crate::cryptanalysis::exhaustive::brute_force`] can try
+/// every key of any cipher whose key space is small enough.
+pub trait ClassicalCipher {
+    /// Key of the cipher, e.g. a shift for Caesar or $(a, b)$ for affine
+    type Key: Clone + Debug + PartialEq;
+
+    /// Check that `key` can be used, e.g. that $a$ is invertible for affine
+    fn check_key(&self, key: &Self::Key) -> Result<(), ClassicalCipherError>;
+
+    fn encrypt(&self, text: &str, key: &Self::Key) -> Result<String, ClassicalCipherError>;
+
+    fn decrypt(&self, cipher: &str, key: &Self::Key) -> Result<String, ClassicalCipherError>;
+
+    /// Every valid key, each distinct encryption appearing once
+    fn keys(&self) -> impl Iterator<Item = Self::Key> + '_;
+
+    /// Number of valid keys, counted from [`ClassicalCipher::keys`] by default
+    fn key_space_size(&self) -> usize {
+        self.keys().count()
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ClassicalCipherError {
+    #[error("a = {a} is not invertible modulo {n}")]
+    NonInvertibleKey { a: i32, n: usize },
+
+    #[error("Invalid character")]
+    InvalidCharacter,
+}
+
+/// Common interface of the stream ciphers
+///
+/// A stream cipher value is positioned in its keystream: each call to
+/// [`StreamCipher::apply_keystream`] uses the bytes following those of the
+/// previous call, so encryption and decryption are the same operation and
+/// data can be processed in pieces of any size.
+pub trait StreamCipher {
+    /// XOR `data` with the next keystream bytes, encrypting or decrypting it in place
+    fn apply_keystream(&mut self, data: &mut [u8]);
+
+    /// Move to the keystream byte at `position`
+    ///
+    /// Ciphers in counter mode compute any block directly. The others generate
+    /// their keystream sequentially: they skip forward by discarding the bytes
+    /// in between, in time linear in the distance, and cannot go back.
+    fn seek(&mut self, position: u64) -> Result<(), SeekError>;
+
+    /// Number of keystream bytes used so far, or the position set by [`StreamCipher::seek`]
+    fn current_pos(&self) -> u64;
+}
+
+/// [`StreamCipher::seek`] for the ciphers generating their keystream
+/// sequentially: the keystream up to `position` is generated and discarded
+pub fn seek_by_discarding<C: StreamCipher + ?Sized>(
+    cipher: &mut C,
+    position: u64,
+) -> Result<(), SeekError> {
+    let current = cipher.current_pos();
+    if position < current {
+        return Err(SeekError::Backward { current, position });
+    }
+
+    let mut discarded = [0; 64];
+    let mut remaining = position - current;
+    while remaining > 0 {
+        let n = remaining.min(discarded.len() as u64) as usize;
+        cipher.apply_keystream(&mut discarded[..n]);
+        remaining -= n as u64;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum SeekError {
+    #[error("Cannot seek back from position {current} to {position}: the keystream is sequential")]
+    Backward { current: u64, position: u64 },
+
+    #[error("Position {0} is past the end of the keystream")]
+    PastEnd(u64),
+}
+
+#[test]
+fn test_stream_ciphers() {
+    use crate::symmetric_encryption::{
+        a5_1::a5_1::A51,
+        grain::{grain128a::Grain128a, grain_v1::GrainV1},
+        rc4::rc4::Rc4,
+        salsa20::{chacha20::ChaCha20, salsa20::Salsa20},
+        trivium::trivium::Trivium,
+    };
+
+    // every cipher, and whether it can seek backward
+    type NewCipher = Box<dyn Fn() -> Box<dyn StreamCipher>>;
+    let ciphers: Vec<(NewCipher, bool)> = vec![
+        (
+            Box::new(|| Box::new(Trivium::from_bytes(&[1; 10], &[2; 10]))),
+            false,
+        ),
+        (
+            Box::new(|| Box::new(GrainV1::new(&[1; 10], &[2; 8]))),
+            false,
+        ),
+        (
+            Box::new(|| Box::new(Grain128a::new(&[1; 16], &[2; 12]))),
+            false,
+        ),
+        (Box::new(|| Box::new(A51::new(&[1; 8], 2).unwrap())), false),
+        (Box::new(|| Box::new(Rc4::new(&[1; 16]).unwrap())), false),
+        (Box::new(|| Box::new(Salsa20::new(&[1; 32], &[2; 8]))), true),
+        (
+            Box::new(|| Box::new(ChaCha20::new(&[1; 32], &[2; 12]))),
+            true,
+        ),
+        (
+            Box::new(|| Box::new(ChaCha20::xchacha20(&[1; 32], &[2; 24]))),
+            true,
+        ),
+    ];
+
+    for (new_cipher, random_access) in ciphers {
+        let mut keystream = [0; 300];
+        new_cipher().apply_keystream(&mut keystream);
+
+        // the same keystream in pieces
+        let mut cipher = new_cipher();
+        let mut pieces = [0; 300];
+        for range in [0..1, 1..64, 64..300] {
+            cipher.apply_keystream(&mut pieces[range]);
+        }
+        assert_eq!(pieces, keystream);
+        assert_eq!(cipher.current_pos(), 300);
+
+        // seeking forward
+        let mut cipher = new_cipher();
+        cipher.seek(130).unwrap();
+        assert_eq!(cipher.current_pos(), 130);
+        let mut rest = [0; 170];
+        cipher.apply_keystream(&mut rest);
+        assert_eq!(rest, keystream[130..]);
+
+        // and backward
+        if random_access {
+            cipher.seek(10).unwrap();
+            let mut part = [0; 10];
+            cipher.apply_keystream(&mut part);
+            assert_eq!(part, keystream[10..20]);
+        } else {
+            assert_eq!(
+                cipher.seek(10),
+                Err(SeekError::Backward {
+                    current: 300,
+                    position: 10
+                })
+            );
+        }
+    }
+}
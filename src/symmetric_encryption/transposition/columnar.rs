@@ -0,0 +1,108 @@
+use super::transposition::{gather, scatter};
+
+/// Columnar Transposition Encryption
+///
+/// The text is written row by row under the key, then the columns are read
+/// top to bottom in the alphabetical order of the key letters (equal
+/// letters left to right). The last row may be incomplete: its missing
+/// cells are simply skipped, no padding is added.
+pub fn encrypt(text: &str, key: &str) -> Result<String, String> {
+    encrypt_with_order(text, &column_order(key)?)
+}
+
+/// Columnar Transposition Decryption
+pub fn decrypt(cipher: &str, key: &str) -> Result<String, String> {
+    decrypt_with_order(cipher, &column_order(key)?)
+}
+
+/// Double columnar transposition: encryption with `first_key`, then `second_key`
+pub fn double_encrypt(text: &str, first_key: &str, second_key: &str) -> Result<String, String> {
+    encrypt(&encrypt(text, first_key)?, second_key)
+}
+
+pub fn double_decrypt(cipher: &str, first_key: &str, second_key: &str) -> Result<String, String> {
+    decrypt(&decrypt(cipher, second_key)?, first_key)
+}
+
+/// Encryption with an explicit order: column `i` is read in position `order[i]`
+pub fn encrypt_with_order(text: &str, order: &[usize]) -> Result<String, String> {
+    let text: Vec<char> = text.chars().collect();
+    Ok(gather(&text, &positions(text.len(), order)?))
+}
+
+pub fn decrypt_with_order(cipher: &str, order: &[usize]) -> Result<String, String> {
+    let cipher: Vec<char> = cipher.chars().collect();
+    Ok(scatter(&cipher, &positions(cipher.len(), order)?))
+}
+
+/// Rank of each key letter in alphabetical order, ties broken left to right
+///
+/// `"zebras"` gives `[5, 2, 1, 3, 0, 4]`.
+pub fn column_order(key: &str) -> Result<Vec<usize>, String> {
+    let key: Vec<char> = key.chars().map(|c| c.to_ascii_lowercase()).collect();
+    if key.is_empty() {
+        return Err("Empty key".to_string());
+    }
+
+    let mut columns: Vec<usize> = (0..key.len()).collect();
+    columns.sort_by_key(|&i| key[i]);
+
+    let mut order = vec![0; key.len()];
+    for (rank, column) in columns.into_iter().enumerate() {
+        order[column] = rank;
+    }
+    Ok(order)
+}
+
+/// Positions of the text in the order they are read, column by column
+fn positions(len: usize, order: &[usize]) -> Result<Vec<usize>, String> {
+    let width = order.len();
+    let mut sorted = order.to_vec();
+    sorted.sort_unstable();
+    if width == 0 || sorted.iter().enumerate().any(|(i, &rank)| i != rank) {
+        return Err("The column order must be a permutation".to_string());
+    }
+
+    let mut columns: Vec<usize> = (0..width).collect();
+    columns.sort_by_key(|&column| order[column]);
+
+    Ok(columns
+        .into_iter()
+        .flat_map(|column| (column..len).step_by(width))
+        .collect())
+}
+
+#[test]
+fn test_columnar() {
+    assert_eq!(column_order("zebras").unwrap(), [5, 2, 1, 3, 0, 4]);
+    assert_eq!(column_order("aba").unwrap(), [0, 2, 1]);
+
+    // irregular: the last row is incomplete
+    let text = "wearediscoveredfleeatonce";
+    let cipher = "evlnacdtesearofodeecwiree";
+    assert_eq!(encrypt(text, "zebras").unwrap(), cipher);
+    assert_eq!(decrypt(cipher, "zebras").unwrap(), text);
+
+    // regular: padded to complete the last row
+    let padded = format!("{text}qkjeu");
+    let cipher = "evlneacdtkeseaqrofojdeecuwiree";
+    assert_eq!(encrypt(&padded, "zebras").unwrap(), cipher);
+    assert_eq!(decrypt(cipher, "zebras").unwrap(), padded);
+
+    assert!(encrypt(text, "").is_err());
+    assert!(encrypt_with_order(text, &[0, 0, 1]).is_err());
+}
+
+#[test]
+fn test_double_columnar() {
+    let text = "Attack the north wall at dawn.";
+    let cipher = double_encrypt(text, "german", "cipher").unwrap();
+    assert_ne!(cipher, encrypt(text, "german").unwrap());
+    assert_eq!(double_decrypt(&cipher, "german", "cipher").unwrap(), text);
+
+    // same key length, different column orders
+    for (k1, k2) in [("ab", "ba"), ("secret", "k"), ("x", "longerkeyword")] {
+        let cipher = double_encrypt(text, k1, k2).unwrap();
+        assert_eq!(double_decrypt(&cipher, k1, k2).unwrap(), text);
+    }
+}
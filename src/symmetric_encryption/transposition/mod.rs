@@ -0,0 +1,5 @@
+pub mod columnar;
+pub mod rail_fence;
+pub mod route;
+#[allow(clippy::module_inception)]
+pub mod transposition;
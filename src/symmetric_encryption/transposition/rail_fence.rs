@@ -0,0 +1,56 @@
+use super::transposition::{gather, scatter};
+
+/// Rail Fence Encryption
+///
+/// The text is written in a zigzag over `rails` rows, going down then up,
+/// and read off row by row:
+///
+/// ```text
+/// w . . . e . . . c . . . r . . . u . . . o . . .
+/// . e . r . d . s . o . e . e . r . n . t . n . e
+/// . . a . . . i . . . v . . . d . . . a . . . c .
+/// ```
+pub fn encrypt(text: &str, rails: usize) -> Result<String, String> {
+    let text: Vec<char> = text.chars().collect();
+    Ok(gather(&text, &order(text.len(), rails)?))
+}
+
+/// Rail Fence Decryption
+pub fn decrypt(cipher: &str, rails: usize) -> Result<String, String> {
+    let cipher: Vec<char> = cipher.chars().collect();
+    Ok(scatter(&cipher, &order(cipher.len(), rails)?))
+}
+
+/// Positions of the text in the order they are read, rail by rail
+pub fn order(len: usize, rails: usize) -> Result<Vec<usize>, String> {
+    if rails == 0 {
+        return Err("The number of rails must be positive".to_string());
+    }
+
+    // the zigzag repeats every 2 * (rails - 1) characters
+    let period = (2 * (rails - 1)).max(1);
+    let rail = |i: usize| {
+        let phase = i % period;
+        phase.min(period - phase)
+    };
+
+    let mut positions: Vec<usize> = (0..len).collect();
+    positions.sort_by_key(|&i| rail(i));
+    Ok(positions)
+}
+
+#[test]
+fn test_rail_fence() {
+    let text = "wearediscoveredrunatonce";
+    let cipher = "wecruoerdsoeerntneaivdac";
+    assert_eq!(encrypt(text, 3).unwrap(), cipher);
+    assert_eq!(decrypt(cipher, 3).unwrap(), text);
+
+    let text = "Meet me at the old bridge!";
+    for rails in 1..=text.len() + 1 {
+        let cipher = encrypt(text, rails).unwrap();
+        assert_eq!(decrypt(&cipher, rails).unwrap(), text);
+    }
+    assert_eq!(encrypt(text, 1).unwrap(), text);
+    assert!(encrypt(text, 0).is_err());
+}
@@ -0,0 +1,121 @@
+use super::transposition::{gather, scatter};
+
+/// Letter completing the last row of the grid
+pub const FILLER: char = 'x';
+
+/// Path along which the grid is read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    /// Down each column, left to right
+    Columns,
+    /// Down the first column, up the second one, and so on
+    Snake,
+    /// Clockwise spiral inwards, from the top-left corner
+    Spiral,
+    /// Counterclockwise spiral inwards, from the top-right corner
+    CounterSpiral,
+}
+
+/// Route Cipher Encryption
+///
+/// The text is written row by row in a grid of `columns` columns, the last
+/// row completed with [`FILLER`], and read along `route`.
+pub fn encrypt(text: &str, columns: usize, route: Route) -> Result<String, String> {
+    let mut text: Vec<char> = text.chars().collect();
+    if columns == 0 {
+        return Err("The number of columns must be positive".to_string());
+    }
+    text.resize(text.len().div_ceil(columns) * columns, FILLER);
+
+    Ok(gather(&text, &path(text.len() / columns, columns, route)))
+}
+
+/// Route Cipher Decryption, the filler is not removed
+pub fn decrypt(cipher: &str, columns: usize, route: Route) -> Result<String, String> {
+    let cipher: Vec<char> = cipher.chars().collect();
+    if columns == 0 || !cipher.len().is_multiple_of(columns) {
+        return Err("The ciphertext does not fill a grid of that width".to_string());
+    }
+
+    Ok(scatter(
+        &cipher,
+        &path(cipher.len() / columns, columns, route),
+    ))
+}
+
+/// Positions (row-major indices) of a `rows` x `columns` grid in route order
+pub fn path(rows: usize, columns: usize, route: Route) -> Vec<usize> {
+    let cell = |row: usize, column: usize| row * columns + column;
+
+    match route {
+        Route::Columns => (0..columns)
+            .flat_map(|c| (0..rows).map(move |r| cell(r, c)))
+            .collect(),
+        Route::Snake => (0..columns)
+            .flat_map(|c| {
+                let down = c % 2 == 0;
+                (0..rows).map(move |r| cell(if down { r } else { rows - 1 - r }, c))
+            })
+            .collect(),
+        Route::Spiral => spiral(rows, columns).map(|(r, c)| cell(r, c)).collect(),
+        // the clockwise spiral of the mirrored grid
+        Route::CounterSpiral => spiral(rows, columns)
+            .map(|(r, c)| cell(r, columns - 1 - c))
+            .collect(),
+    }
+}
+
+/// Cells of the grid along a clockwise spiral from the top-left corner
+fn spiral(rows: usize, columns: usize) -> impl Iterator<Item = (usize, usize)> {
+    let mut cells = Vec::with_capacity(rows * columns);
+    let (mut top, mut bottom, mut left, mut right) = (0, rows, 0, columns);
+
+    while top < bottom && left < right {
+        cells.extend((left..right).map(|c| (top, c)));
+        cells.extend((top + 1..bottom).map(|r| (r, right - 1)));
+        if bottom - top > 1 {
+            cells.extend((left..right - 1).rev().map(|c| (bottom - 1, c)));
+        }
+        if right - left > 1 {
+            cells.extend((top + 1..bottom - 1).rev().map(|r| (r, left)));
+        }
+        top += 1;
+        bottom -= 1;
+        left += 1;
+        right -= 1;
+    }
+
+    cells.into_iter()
+}
+
+#[test]
+fn test_route() {
+    // a b c d
+    // e f g h
+    // i j k l
+    let text = "abcdefghijkl";
+    assert_eq!(encrypt(text, 4, Route::Columns).unwrap(), "aeibfjcgkdhl");
+    assert_eq!(encrypt(text, 4, Route::Snake).unwrap(), "aeijfbcgklhd");
+    assert_eq!(encrypt(text, 4, Route::Spiral).unwrap(), "abcdhlkjiefg");
+    assert_eq!(
+        encrypt(text, 4, Route::CounterSpiral).unwrap(),
+        "dcbaeijklhgf"
+    );
+
+    for route in [
+        Route::Columns,
+        Route::Snake,
+        Route::Spiral,
+        Route::CounterSpiral,
+    ] {
+        for columns in 1..8 {
+            let cipher = encrypt("wearediscovered", columns, route).unwrap();
+            let decrypted = decrypt(&cipher, columns, route).unwrap();
+            assert!(decrypted.starts_with("wearediscovered"));
+            assert!(decrypted[15..].chars().all(|c| c == FILLER));
+        }
+    }
+
+    assert!(encrypt(text, 0, Route::Spiral).is_err());
+    assert!(decrypt("abcde", 2, Route::Spiral).is_err());
+}
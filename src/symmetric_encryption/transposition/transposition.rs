@@ -0,0 +1,49 @@
+// Shared machinery of the transposition ciphers
+
+/// A transposition is a permutation of positions: `cipher[i] = text[order[i]]`
+pub fn gather(text: &[char], order: &[usize]) -> String {
+    order.iter().map(|&i| text[i]).collect()
+}
+
+/// Undo [`gather`]: `text[order[i]] = cipher[i]`
+pub fn scatter(cipher: &[char], order: &[usize]) -> String {
+    let mut text = vec!['\0'; cipher.len()];
+    for (&c, &i) in cipher.iter().zip(order) {
+        text[i] = c;
+    }
+    text.into_iter().collect()
+}
+
+/// Rearrange `items` into the next permutation in lexicographic order
+///
+/// Returns false, leaving `items` sorted, after the last permutation.
+pub fn next_permutation(items: &mut [usize]) -> bool {
+    let Some(i) = (1..items.len()).rev().find(|&i| items[i - 1] < items[i]) else {
+        items.reverse();
+        return false;
+    };
+    let j = (i..items.len())
+        .rev()
+        .find(|&j| items[j] > items[i - 1])
+        .unwrap();
+    items.swap(i - 1, j);
+    items[i..].reverse();
+    true
+}
+
+#[test]
+fn test_permutations() {
+    let text: Vec<char> = "abcd".chars().collect();
+    let order = [2, 0, 3, 1];
+    let cipher = gather(&text, &order);
+    assert_eq!(cipher, "cadb");
+    assert_eq!(scatter(&cipher.chars().collect::<Vec<_>>(), &order), "abcd");
+
+    let mut items = [0, 1, 2];
+    let mut count = 1;
+    while next_permutation(&mut items) {
+        count += 1;
+    }
+    assert_eq!(count, 6);
+    assert_eq!(items, [0, 1, 2]);
+}
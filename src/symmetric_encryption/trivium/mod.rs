@@ -1 +1,3 @@
+pub mod stream;
+#[allow(clippy::module_inception)]
 pub mod trivium;
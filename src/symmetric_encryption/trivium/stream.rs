@@ -0,0 +1,44 @@
+// std::io adapters for Trivium, see [`crate::symmetric_encryption::stream`]
+use super::trivium::Trivium;
+use crate::symmetric_encryption::stream::{StreamReader, StreamWriter};
+
+/// Reader XORing the bytes read with the Trivium keystream
+pub type TriviumReader<R> = StreamReader<R, Trivium>;
+
+/// Writer XORing the bytes written with the Trivium keystream
+pub type TriviumWriter<W> = StreamWriter<W, Trivium>;
+
+#[test]
+fn test_trivium_io() {
+    use std::io::{Read, Write};
+
+    let key = *b"stream key";
+    let iv = [7; 10];
+    let message: Vec<u8> = (0..1000u32).map(|i| (i * 31 % 251) as u8).collect();
+
+    let mut expected = message.clone();
+    Trivium::from_bytes(&key, &iv).apply_keystream(&mut expected);
+
+    // written in pieces of different sizes
+    let mut writer = TriviumWriter::new(Vec::new(), Trivium::from_bytes(&key, &iv));
+    for chunk in message.chunks(37) {
+        writer.write_all(chunk).unwrap();
+    }
+    writer.flush().unwrap();
+    let (cipher, trivium) = writer.into_inner();
+    assert_eq!(cipher, expected);
+    assert_eq!(trivium.position(), 8000);
+
+    // read back through a small buffer
+    let mut reader = TriviumReader::new(&cipher[..], Trivium::from_bytes(&key, &iv));
+    let mut decrypted = vec![];
+    let mut buf = [0; 13];
+    loop {
+        let n = reader.read(&mut buf).unwrap();
+        if n == 0 {
+            break;
+        }
+        decrypted.extend_from_slice(&buf[..n]);
+    }
+    assert_eq!(decrypted, message);
+}
@@ -1,38 +1,74 @@
 use crate::common::bits::{Bits, BitsError};
+use crate::common::keystream::KeystreamBuffer;
+use crate::symmetric_encryption::traits::{SeekError, StreamCipher};
 use thiserror::Error;
 
-#[derive(Debug)]
-pub struct Trivium<'a> {
-    state: Bits<'a>,
-    key_stream: Bits<'a>,
-    count: u16,
+/// Trivium stream cipher, 80-bit key and 80-bit IV
+///
+/// The 288-bit state $(s_1, \ldots, s_{288})$ is held in three registers,
+/// one `u128` each: bit $p$ of `a` is $s_{p+1}$ ($p < 93$), of `b` is
+/// $s_{p+94}$ ($p < 84$) and of `c` is $s_{p+178}$ ($p < 111$). A clock
+/// shifts every register left by one and feeds the new bit in at bit 0.
+///
+/// No tap is closer than 66 positions to the start of its register, so the
+/// bits read by the next 64 clocks are all in the current state: the taps of
+/// 64 clocks are 64-bit windows of the registers, and 64 clocks are computed
+/// at once with a few word operations.
+#[derive(Debug, Clone)]
+pub struct Trivium {
+    a: u128,
+    b: u128,
+    c: u128,
+    /// Number of clocks since the key and IV were loaded
+    count: u64,
+    /// Number of initialization clocks, without output
+    rounds: u64,
+    /// Output bits of the last clocks not used yet
+    buffer: KeystreamBuffer,
 }
 
-impl<'a> Trivium<'a> {
-    // Register A indices
-    const BIT66: usize = 65;
-    const BIT69: usize = 68;
-    const BIT91: usize = 90;
-    const BIT92: usize = 91;
-    const BIT93: usize = 92;
-    const BIT94: usize = 93;
-
-    // Register B indices
-    const BIT162: usize = 161;
-    const BIT171: usize = 170;
-    const BIT175: usize = 174;
-    const BIT176: usize = 175;
-    const BIT177: usize = 176;
-    const BIT178: usize = 177;
-
-    // Register C indices
-    const BIT243: usize = 242;
-    const BIT264: usize = 263;
-    const BIT286: usize = 285;
-    const BIT287: usize = 286;
-    const BIT288: usize = 287;
-
+impl Trivium {
+    const A_LEN: u32 = 93;
+    const B_LEN: u32 = 84;
+    const C_LEN: u32 = 111;
+
+    // Taps, as positions within their register: s66, s69, s91, s92, s93
+    const A66: u32 = 66;
+    const A69: u32 = 69;
+    const A91: u32 = 91;
+    const A92: u32 = 92;
+    const A93: u32 = 93;
+
+    // s162, s171, s175, s176, s177
+    const B69: u32 = 69;
+    const B78: u32 = 78;
+    const B82: u32 = 82;
+    const B83: u32 = 83;
+    const B84: u32 = 84;
+
+    // s243, s264, s286, s287, s288
+    const C66: u32 = 66;
+    const C87: u32 = 87;
+    const C109: u32 = 109;
+    const C110: u32 = 110;
+    const C111: u32 = 111;
+
+    /// Number of clocks of the initialization, 4 full rotations of the state
+    pub const WARM_UP: u64 = 4 * 288;
+
+    /// Trivium from the 80 bits of the key and of the IV, one bit (0 or 1) per byte
+    ///
+    /// The state is warmed up: the cipher is ready to produce keystream.
     pub fn new(key: &[u8], iv: &[u8]) -> Result<Self, TriviumError> {
+        Self::with_rounds(key, iv, Self::WARM_UP)
+    }
+
+    /// Trivium with `rounds` initialization clocks instead of [`Trivium::WARM_UP`]
+    ///
+    /// With fewer rounds the keystream bits are polynomials of low degree in
+    /// the key and IV bits, which the cube attack exploits: see
+    /// [`crate::cryptanalysis::cube`].
+    pub fn with_rounds(key: &[u8], iv: &[u8], rounds: u64) -> Result<Self, TriviumError> {
         // Validate key length
         if key.len() != 80 {
             return Err(TriviumError::InvalidKeyLength(key.len()));
@@ -42,96 +78,156 @@ impl<'a> Trivium<'a> {
         if iv.len() != 80 {
             return Err(TriviumError::InvalidIVLength(iv.len()));
         }
-        let mut ra = vec![0; 93]; // bit 1 -> 93 (93 bits)
-        let mut rb = vec![0; 84]; // bit 94 -> 177 (84 bits)
-        let mut rc = vec![0; 111]; // bit 178 -> 288 (111 bits)
-
-        // load the key to the first 80 bits (bit 1 ->  80)
-        for (i, byte) in key.iter().enumerate() {
-            ra[i] = *byte;
+        let (key, iv) = (Bits::new(key)?, Bits::new(iv)?);
+
+        // the key in s1 -> s80, the IV in s94 -> s173
+        let load = |bits: &[u8]| {
+            bits.iter()
+                .enumerate()
+                .fold(0u128, |register, (p, &bit)| register | (bit as u128) << p)
+        };
+        // s286, s287 and s288 set to 1
+        let c = 0b111 << (Self::C_LEN - 3);
+
+        let mut trivium = Self {
+            a: load(key.as_slice()),
+            b: load(iv.as_slice()),
+            c,
+            count: 0,
+            rounds,
+            buffer: KeystreamBuffer::new(),
+        };
+        while trivium.count < rounds {
+            let n = (rounds - trivium.count).min(64);
+            trivium.clock(n as u32);
         }
+        Ok(trivium)
+    }
+
+    /// Trivium from a 10-byte key and IV, in the byte order of the eSTREAM
+    /// test vectors, ready to produce keystream
+    ///
+    /// Bit $i$ of the key, `key[i / 8] >> (i % 8) & 1`, is $K_{i+1}$, and
+    /// the register is loaded from $K_{80}$: $s_1 = K_{80}, \ldots, s_{80} = K_1$.
+    /// The IV is loaded the same way into $s_{94}, \ldots, s_{173}$.
+    pub fn from_bytes(key: &[u8; 10], iv: &[u8; 10]) -> Self {
+        let bits = |bytes: &[u8; 10]| -> Vec<u8> {
+            (0..80).rev().map(|i| bytes[i / 8] >> (i % 8) & 1).collect()
+        };
+        Self::new(&bits(key), &bits(iv)).unwrap()
+    }
 
-        // load the iv into the next 80 bits (bit 94 -> 173)
-        for (i, byte) in iv.iter().enumerate() {
-            rb[i] = *byte;
+    /// XOR `data` with the next keystream bytes, encrypting or decrypting it in place
+    ///
+    /// Each keystream byte holds 8 output bits, the first one in the least
+    /// significant bit, as in the eSTREAM test vectors. Successive calls
+    /// continue the keystream where the previous one stopped.
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            // the first output bit is the most significant one of `next_bits`
+            *byte ^= (self.next_bits(8) as u8).reverse_bits();
         }
+    }
 
-        // set the last 3 bits of rc to 1
-        let rc_len = rc.len();
-        rc[rc_len - 1] = 1;
-        rc[rc_len - 2] = 1;
-        rc[rc_len - 3] = 1;
+    /// The next `len` keystream bits, the last one first
+    ///
+    /// Messages are given in the same order, see [`crate::common::hex::Hex::to_bits_lsb`].
+    pub fn key_stream(&mut self, len: usize) -> Bits<'static> {
+        let mut bits: Vec<u8> = (0..len).map(|_| self.next_bits(1) as u8).collect();
+        bits.reverse();
+        Bits::from(bits)
+    }
 
-        let state = [ra, rb, rc].concat();
+    /// Number of keystream bits produced so far
+    pub fn position(&self) -> u64 {
+        self.count - self.rounds - self.buffer.len() as u64
+    }
 
-        Ok(Self {
-            state: state.into(),
-            key_stream: Bits::new(&[])?,
-            count: 0,
-        })
+    /// XOR the bits of `plain_text` with the next keystream bits
+    pub fn encrypt(&mut self, plain_text: &[u8]) -> Result<Vec<u8>, TriviumError> {
+        let plain_text_bits = Bits::new(plain_text)?;
+
+        Ok(plain_text_bits.xor(&self.key_stream(plain_text.len()))?)
     }
 
-    pub fn update_state(&mut self, count: u16) {
+    pub fn decrypt(&mut self, cipher_text: &[u8]) -> Result<Vec<u8>, TriviumError> {
+        let cipher_text_bits = Bits::new(cipher_text)?;
+
+        Ok(cipher_text_bits.xor(&self.key_stream(cipher_text.len()))?)
+    }
+
+    /// The next `n` output bits, $1 \le n \le 64$, the first one in bit `n - 1`
+    fn next_bits(&mut self, n: u32) -> u64 {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        let bits = buffer.next_bits(n, || (self.clock(64), 64));
+        self.buffer = buffer;
+        bits
+    }
+
+    /// `n` clocks at once, $1 \le n \le 64$
+    ///
+    /// Returns the `n` output bits, the first one in bit `n - 1`. The tap
+    /// $s_i$ over the next `n` clocks is the window of `n` bits ending at
+    /// $s_{i-n+1}$, with the first clock in the most significant bit, and the
+    /// new bits are shifted in the same order.
+    fn clock(&mut self, n: u32) -> u64 {
+        let mask = u64::MAX >> (64 - n);
+        let tap = |register: u128, i: u32| (register >> (i - n)) as u64 & mask;
+        let (a, b, c) = (self.a, self.b, self.c);
+
         // intermediate bits
-        let mut t1 = self.state.get_bit(Self::BIT66) ^ self.state.get_bit(Self::BIT93);
-        let mut t2 = self.state.get_bit(Self::BIT162) ^ self.state.get_bit(Self::BIT177);
-        let mut t3 = self.state.get_bit(Self::BIT243) ^ self.state.get_bit(Self::BIT288);
+        let mut t1 = tap(a, Self::A66) ^ tap(a, Self::A93);
+        let mut t2 = tap(b, Self::B69) ^ tap(b, Self::B84);
+        let mut t3 = tap(c, Self::C66) ^ tap(c, Self::C111);
         // output bits
-        if count > 1151 {
-            let z = t1 ^ t2 ^ t3;
-            // because the key stream is in lsb, we need to insert the bit at the beginning
-            self.key_stream.insert(0, z);
-        }
-
-        t1 = t1
-            ^ (self.state.get_bit(Self::BIT91) & self.state.get_bit(Self::BIT92))
-            ^ self.state.get_bit(Self::BIT171);
-        t2 = t2
-            ^ (self.state.get_bit(Self::BIT175) & self.state.get_bit(Self::BIT176))
-            ^ self.state.get_bit(Self::BIT264);
-        t3 = t3
-            ^ (self.state.get_bit(Self::BIT286) & self.state.get_bit(Self::BIT287))
-            ^ self.state.get_bit(Self::BIT69);
+        let z = t1 ^ t2 ^ t3;
 
-        // right shift 1 of the state
-        self.state.shift_right(1);
+        t1 ^= (tap(a, Self::A91) & tap(a, Self::A92)) ^ tap(b, Self::B78);
+        t2 ^= (tap(b, Self::B82) & tap(b, Self::B83)) ^ tap(c, Self::C87);
+        t3 ^= (tap(c, Self::C109) & tap(c, Self::C110)) ^ tap(a, Self::A69);
 
-        // feed the new values to the state
-        self.state.update_bit(0, t3);
-        self.state.update_bit(Self::BIT94, t1);
-        self.state.update_bit(Self::BIT178, t2);
+        // shift the registers and feed the new values
+        let register_mask = |len: u32| u128::MAX >> (128 - len);
+        self.a = ((a << n) | t3 as u128) & register_mask(Self::A_LEN);
+        self.b = ((b << n) | t1 as u128) & register_mask(Self::B_LEN);
+        self.c = ((c << n) | t2 as u128) & register_mask(Self::C_LEN);
 
-        self.count += 1;
+        self.count += n as u64;
+        z
     }
+}
 
-    pub fn warm_up(&mut self) {
-        for _ in 0..4 * 288 {
-            self.update_state(self.count);
-        }
+impl StreamCipher for Trivium {
+    fn apply_keystream(&mut self, data: &mut [u8]) {
+        Trivium::apply_keystream(self, data);
     }
 
-    pub fn build_key_stream(&mut self, plain_text_length: usize) {
-        for _ in 0..plain_text_length {
-            self.update_state(self.count);
+    /// Skip to keystream bit `8 * position`, which may end a byte partly
+    /// used by [`Trivium::key_stream`]
+    fn seek(&mut self, position: u64) -> Result<(), SeekError> {
+        let current = self.position();
+        let target = position
+            .checked_mul(8)
+            .ok_or(SeekError::PastEnd(position))?;
+        if target < current {
+            return Err(SeekError::Backward {
+                current: self.current_pos(),
+                position,
+            });
         }
-    }
 
-    pub fn encrypt(&mut self, plain_text: &[u8]) -> Result<Vec<u8>, TriviumError> {
-        if self.count < 1152 {
-            return Err(TriviumError::NotWarmedUp);
+        let mut remaining = target - current;
+        while remaining > 0 {
+            let n = remaining.min(64);
+            self.next_bits(n as u32);
+            remaining -= n;
         }
-        let plain_text_bits = Bits::new(plain_text)?;
-
-        Ok(plain_text_bits.xor(&self.key_stream)?)
+        Ok(())
     }
 
-    pub fn decrypt(&mut self, cipher_text: &[u8]) -> Result<Vec<u8>, TriviumError> {
-        if self.count < 1152 {
-            return Err(TriviumError::NotWarmedUp);
-        }
-        let cipher_text_bits = Bits::new(cipher_text)?;
-
-        Ok(cipher_text_bits.xor(&self.key_stream)?)
+    /// [`Trivium::position`] counts bits, a partly used byte counting as used
+    fn current_pos(&self) -> u64 {
+        self.position().div_ceil(8)
     }
 }
 
@@ -145,9 +241,6 @@ pub enum TriviumError {
 
     #[error("Invalid IV length: expected 10 bytes, got {0} bytes")]
     InvalidIVLength(usize),
-
-    #[error("Trivium is not warmed up")]
-    NotWarmedUp,
 }
 
 #[test]
@@ -164,21 +257,26 @@ fn test_trivium() {
     assert_eq!(key_bits.len(), 80);
     assert_eq!(iv_bits.len(), 80);
 
+    // the constructor warms the state up
     let mut trivium = Trivium::new(&key_bits, &iv_bits).unwrap();
-    trivium.warm_up();
     assert_eq!(trivium.count, 1152);
+    assert_eq!(trivium.position(), 0);
 
     let plain_text_bits = plain_text_hex.to_bits_lsb();
-    trivium.build_key_stream(plain_text_bits.len());
 
     // test vector key stream: 38EB86FF73
     // if we want to get the test vector value, we need to reverse the key stream using reverse_mut
     // because the key stream is in lsb-first order
     assert_eq!(
-        format!("{}", Hex::new(&trivium.key_stream.as_vec_bytes()).unwrap()),
+        format!(
+            "{}",
+            Hex::new(&trivium.key_stream(plain_text_bits.len()).as_vec_bytes()).unwrap()
+        ),
         "73FF86EB38"
     );
+    assert_eq!(trivium.position(), 40);
 
+    let mut trivium = Trivium::new(&key_bits, &iv_bits).unwrap();
     let cipher: Vec<u8> = trivium.encrypt(&plain_text_bits).unwrap();
     let cipher_bits = Bits::new(&cipher).unwrap();
     // test vector cipher text: 508EEA931C
@@ -187,10 +285,46 @@ fn test_trivium() {
         "1C93EA8E50"
     );
 
+    let mut trivium = Trivium::new(&key_bits, &iv_bits).unwrap();
     let plain_text_decrypted = trivium.decrypt(&cipher).unwrap();
     assert_eq!(plain_text_decrypted, plain_text_bits);
 }
 
+#[test]
+fn test_trivium_stream() {
+    use crate::common::hex::Hex;
+
+    let key_bits = Hex::new("80000000000000000000").unwrap().to_bits_lsb();
+    let iv_bits = Hex::new("00000000000000000000").unwrap().to_bits_lsb();
+
+    // two messages use successive parts of the keystream, as one message would
+    let mut trivium = Trivium::new(&key_bits, &iv_bits).unwrap();
+    let first = trivium
+        .encrypt(&Hex::new("he").unwrap().to_bits_lsb())
+        .unwrap();
+    let second = trivium
+        .encrypt(&Hex::new("llo").unwrap().to_bits_lsb())
+        .unwrap();
+    assert_ne!(first[..], second[..16]);
+    let both = Trivium::new(&key_bits, &iv_bits)
+        .unwrap()
+        .encrypt(&Hex::new("hello").unwrap().to_bits_lsb())
+        .unwrap();
+    assert_eq!([second, first].concat(), both);
+
+    // the bit and byte calls share the keystream
+    let mut trivium = Trivium::new(&key_bits, &iv_bits).unwrap();
+    let mut data = *b"hello";
+    trivium.apply_keystream(&mut data[..1]);
+    let bits = trivium
+        .encrypt(&Hex::new("e").unwrap().to_bits_lsb())
+        .unwrap();
+    data[1] = Bits::new(&bits).unwrap().as_vec_bytes()[0];
+    trivium.apply_keystream(&mut data[2..]);
+    assert_eq!(format!("{}", Hex::new(&data).unwrap()), "508EEA931C");
+    assert_eq!(trivium.position(), 40);
+}
+
 #[test]
 fn test_trivium_errors() {
     use crate::common::hex::Hex;
@@ -219,15 +353,133 @@ fn test_trivium_errors() {
 
     let mut trivium = Trivium::new(&key_bits, &iv_bits).unwrap();
     let err = trivium.encrypt(&[0, 1, 2]).unwrap_err();
-    assert_eq!(err, TriviumError::NotWarmedUp);
-
-    let err = trivium.decrypt(&[0, 1, 2]).unwrap_err();
-    assert_eq!(err, TriviumError::NotWarmedUp);
-
-    trivium.warm_up();
-    let err = trivium.encrypt(&[0, 1, 2]).unwrap_err();
     assert_eq!(err, TriviumError::BitsError(BitsError::InvalidBit(2)));
 
     let err = trivium.decrypt(&[0, 1, 2]).unwrap_err();
     assert_eq!(err, TriviumError::BitsError(BitsError::InvalidBit(2)));
+    // an invalid message does not use any keystream
+    assert_eq!(trivium.position(), 0);
+}
+
+#[test]
+fn test_packed_state() {
+    // bit by bit reference: the state as 288 bits, shifted one clock at a time
+    fn reference(key: &[u8], iv: &[u8], rounds: usize, len: usize) -> Vec<u8> {
+        let mut s = [0u8; 288];
+        s[..80].copy_from_slice(key);
+        s[93..173].copy_from_slice(iv);
+        s[285..].fill(1);
+
+        let mut z = vec![];
+        for i in 0..rounds + len {
+            let mut t1 = s[65] ^ s[92];
+            let mut t2 = s[161] ^ s[176];
+            let mut t3 = s[242] ^ s[287];
+            if i >= rounds {
+                z.push(t1 ^ t2 ^ t3);
+            }
+            t1 ^= (s[90] & s[91]) ^ s[170];
+            t2 ^= (s[174] & s[175]) ^ s[263];
+            t3 ^= (s[285] & s[286]) ^ s[68];
+            s.rotate_right(1);
+            s[0] = t3;
+            s[93] = t1;
+            s[177] = t2;
+        }
+        z
+    }
+
+    use crate::rng::{splitmix64::SplitMix64, traits::Rng};
+    let mut rng = SplitMix64::new(288);
+    for len in [1, 63, 64, 65, 1000] {
+        let key: Vec<u8> = (0..80).map(|_| rng.next_below(2) as u8).collect();
+        let iv: Vec<u8> = (0..80).map(|_| rng.next_below(2) as u8).collect();
+
+        let mut trivium = Trivium::new(&key, &iv).unwrap();
+        let key_stream = trivium.key_stream(len).reverse();
+        assert_eq!(key_stream, reference(&key, &iv, 1152, len));
+
+        // requests of any size give the same bits
+        let mut single = Trivium::new(&key, &iv).unwrap();
+        let mut bits = vec![];
+        let mut sizes = [1, 7, 30, 64, 3].iter().cycle();
+        while bits.len() < len {
+            let n = (*sizes.next().unwrap()).min(len - bits.len());
+            bits.extend(single.key_stream(n).reverse());
+        }
+        assert_eq!(bits, key_stream);
+    }
+
+    // reduced initialization
+    for rounds in [0, 1, 576, 672, 799] {
+        let key: Vec<u8> = (0..80).map(|_| rng.next_below(2) as u8).collect();
+        let iv: Vec<u8> = (0..80).map(|_| rng.next_below(2) as u8).collect();
+        let mut trivium = Trivium::with_rounds(&key, &iv, rounds).unwrap();
+        assert_eq!(
+            trivium.key_stream(100).reverse(),
+            reference(&key, &iv, rounds as usize, 100)
+        );
+        assert_eq!(trivium.position(), 100);
+    }
+}
+
+#[test]
+fn test_byte_api() {
+    use crate::common::hex::Hex;
+
+    let mut key = [0; 10];
+    key[0] = 0x80;
+    let mut trivium = Trivium::from_bytes(&key, &[0; 10]);
+    let mut data = *b"hello";
+    trivium.apply_keystream(&mut data);
+    assert_eq!(format!("{}", Hex::new(&data).unwrap()), "508EEA931C");
+
+    // the same keystream in pieces of any size, and decryption
+    let mut trivium = Trivium::from_bytes(&key, &[0; 10]);
+    let mut data = *b"hello";
+    for chunk in data.chunks_mut(2) {
+        trivium.apply_keystream(chunk);
+    }
+    assert_eq!(format!("{}", Hex::new(&data).unwrap()), "508EEA931C");
+    Trivium::from_bytes(&key, &[0; 10]).apply_keystream(&mut data);
+    assert_eq!(&data, b"hello");
+
+    // the byte API is the bit API with the bits of each byte reversed
+    let key = *b"Trivium 80";
+    let iv = [0x5a, 0xc3, 0x00, 0xff, 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc];
+    let mut keystream = [0; 100];
+    Trivium::from_bytes(&key, &iv).apply_keystream(&mut keystream);
+
+    let mut trivium = Trivium::new(
+        &Hex::new(&key).unwrap().to_bits_lsb(),
+        &Hex::new(&iv).unwrap().to_bits_lsb(),
+    )
+    .unwrap();
+    let mut expected = trivium.key_stream(800).as_vec_bytes();
+    expected.reverse();
+    assert_eq!(keystream.to_vec(), expected);
+}
+
+#[test]
+fn test_estream_vectors() {
+    use crate::common::estream;
+
+    let vectors = estream::parse(include_str!("testvectors/trivium-80.80.test-vectors")).unwrap();
+    // sets 1 and 5 flip each key and IV bit, sets 4 and 6 cover 128 KiB of keystream
+    assert_eq!(vectors.len(), 80 + 256 + 256 + 4 + 80 + 4);
+
+    for vector in &vectors {
+        let key = vector.key.as_slice().try_into().unwrap();
+        let iv = vector.iv.as_slice().try_into().unwrap();
+        let mut keystream = vec![0; vector.stream_len()];
+        Trivium::from_bytes(key, iv).apply_keystream(&mut keystream);
+
+        assert_eq!(
+            vector.check(&keystream),
+            Ok(()),
+            "Set {}, vector# {}",
+            vector.set,
+            vector.vector
+        );
+    }
 }
@@ -1,11 +1,194 @@
+use std::{collections::HashMap, sync::OnceLock};
+
 pub const ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz";
 
+/// Ordered set of characters the classical ciphers work on
+///
+/// Each character is identified with its index, so a cipher over an
+/// alphabet of $n$ characters computes in $\mathbb{Z}_n$. Both directions are
+/// $O(1)$: index to character is a vector access, character to index goes
+/// through a table for ASCII and a hash map for the other characters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alphabet {
+    chars: Vec<char>,
+    ascii: [Option<usize>; 128],
+    others: HashMap<char, usize>,
+}
+
+impl Alphabet {
+    /// Alphabet made of the characters of `chars`, in order
+    pub fn new(chars: &str) -> Result<Self, String> {
+        let mut alphabet = Self {
+            chars: Vec::new(),
+            ascii: [None; 128],
+            others: HashMap::new(),
+        };
+
+        for (index, c) in chars.chars().enumerate() {
+            if alphabet.contains(c) {
+                return Err(format!("Duplicate character {c:?} in alphabet"));
+            }
+            if c.is_ascii() {
+                alphabet.ascii[c as usize] = Some(index);
+            } else {
+                alphabet.others.insert(c, index);
+            }
+            alphabet.chars.push(c);
+        }
+
+        if alphabet.chars.len() < 2 {
+            return Err("An alphabet needs at least 2 characters".to_string());
+        }
+
+        Ok(alphabet)
+    }
+
+    /// `a` to `z`
+    pub fn lowercase() -> Self {
+        Self::new(ALPHABET).unwrap()
+    }
+
+    /// `a` to `z` then `A` to `Z`
+    pub fn mixed_case() -> Self {
+        Self::new("abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ").unwrap()
+    }
+
+    /// `a` to `z`, `A` to `Z` then `0` to `9`
+    pub fn alphanumeric() -> Self {
+        Self::new("abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789").unwrap()
+    }
+
+    /// The 95 printable ASCII characters, from space to `~`
+    pub fn printable_ascii() -> Self {
+        Self::new(&(' '..='~').collect::<String>()).unwrap()
+    }
+
+    /// The 33 lowercase letters of the Russian alphabet
+    pub fn cyrillic() -> Self {
+        Self::new("абвгдеёжзийклмнопрстуфхцчшщъыьэюя").unwrap()
+    }
+
+    /// The 24 lowercase letters of the Greek alphabet (without the final sigma)
+    pub fn greek() -> Self {
+        Self::new("αβγδεζηθικλμνξοπρστυφχψω").unwrap()
+    }
+
+    pub fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        self.index_of(c).is_some()
+    }
+
+    pub fn index_of(&self, c: char) -> Option<usize> {
+        if c.is_ascii() {
+            self.ascii[c as usize]
+        } else {
+            self.others.get(&c).copied()
+        }
+    }
+
+    pub fn char_at(&self, index: usize) -> Option<char> {
+        self.chars.get(index).copied()
+    }
+
+    /// Index of `c`, or of its lowercase form if only that one is in the alphabet
+    ///
+    /// Returns whether the case was folded, so the cipher can restore it.
+    pub fn index_of_folded(&self, c: char) -> Option<(usize, bool)> {
+        if let Some(index) = self.index_of(c) {
+            return Some((index, false));
+        }
+
+        let mut lowercase = c.to_lowercase();
+        match (lowercase.next(), lowercase.next()) {
+            (Some(lower), None) if lower != c => self.index_of(lower).map(|index| (index, true)),
+            _ => None,
+        }
+    }
+
+    /// Character at `index`, converted back to uppercase if `folded`
+    pub fn char_at_folded(&self, index: usize, folded: bool) -> Option<char> {
+        let c = self.char_at(index)?;
+        if !folded {
+            return Some(c);
+        }
+
+        let mut uppercase = c.to_uppercase();
+        match (uppercase.next(), uppercase.next()) {
+            (Some(upper), None) => Some(upper),
+            _ => Some(c),
+        }
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Self::lowercase()
+    }
+}
+
+/// What a cipher does with characters outside its alphabet
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PassThrough {
+    /// Copy them unchanged to the output
+    Preserve,
+    /// Drop them from the output
+    Strip,
+    /// Fail with an "Invalid character" error
+    #[default]
+    Reject,
+}
+
+/// Apply a monoalphabetic substitution, given on indices, to `text`
+///
+/// Uppercase characters missing from the alphabet are substituted as their
+/// lowercase form and converted back, the other characters outside the
+/// alphabet are handled according to `policy`.
+pub fn substitute(
+    text: &str,
+    alphabet: &Alphabet,
+    policy: PassThrough,
+    mut substitution: impl FnMut(usize) -> usize,
+) -> Result<String, String> {
+    text.chars().try_fold(String::new(), |mut acc, c| {
+        match alphabet.index_of_folded(c) {
+            Some((index, folded)) => {
+                let new_char = alphabet.char_at_folded(substitution(index), folded);
+                acc.push(new_char.ok_or("Invalid character".to_string())?);
+            }
+            None => match policy {
+                PassThrough::Preserve => acc.push(c),
+                PassThrough::Strip => {}
+                PassThrough::Reject => return Err("Invalid character".to_string()),
+            },
+        }
+
+        Ok(acc)
+    })
+}
+
+/// The lowercase alphabet behind [`ALPHABET`], built once
+pub(crate) fn default_alphabet() -> &'static Alphabet {
+    static LOWERCASE: OnceLock<Alphabet> = OnceLock::new();
+    LOWERCASE.get_or_init(Alphabet::lowercase)
+}
+
 pub fn alphabet_size_i32() -> i32 {
     ALPHABET.len() as i32
 }
 
 pub fn get_char_index(c: char) -> Result<i32, String> {
-    let Some(index) = ALPHABET.find(c) else {
+    let Some(index) = default_alphabet().index_of(c) else {
         return Err("Invalid character".to_string());
     };
 
@@ -13,8 +196,55 @@ pub fn get_char_index(c: char) -> Result<i32, String> {
 }
 
 pub fn get_char_at_index(index: i32) -> Result<char, String> {
-    ALPHABET
-        .chars()
-        .nth(index as usize)
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| default_alphabet().char_at(index))
         .ok_or("Invalid character".to_string())
 }
+
+#[test]
+fn test_alphabet() {
+    let alphabet = Alphabet::lowercase();
+    assert_eq!(alphabet.len(), 26);
+    assert_eq!(alphabet.index_of('a'), Some(0));
+    assert_eq!(alphabet.index_of('z'), Some(25));
+    assert_eq!(alphabet.index_of('A'), None);
+    assert_eq!(alphabet.index_of_folded('A'), Some((0, true)));
+    assert_eq!(alphabet.char_at_folded(1, true), Some('B'));
+    assert_eq!(alphabet.char_at(26), None);
+
+    assert_eq!(Alphabet::mixed_case().index_of('A'), Some(26));
+    assert_eq!(
+        Alphabet::mixed_case().index_of_folded('A'),
+        Some((26, false))
+    );
+    assert_eq!(Alphabet::alphanumeric().index_of('9'), Some(61));
+    assert_eq!(Alphabet::printable_ascii().len(), 95);
+    assert_eq!(Alphabet::printable_ascii().index_of('~'), Some(94));
+
+    let cyrillic = Alphabet::cyrillic();
+    assert_eq!(cyrillic.len(), 33);
+    assert_eq!(cyrillic.index_of('ё'), Some(6));
+    assert_eq!(cyrillic.index_of_folded('Я'), Some((32, true)));
+    assert_eq!(Alphabet::greek().index_of('ω'), Some(23));
+
+    let text = "Hello, World!";
+    let identity = |index| index;
+    assert_eq!(
+        substitute(text, &alphabet, PassThrough::Preserve, identity).unwrap(),
+        text
+    );
+    assert_eq!(
+        substitute(text, &alphabet, PassThrough::Strip, identity).unwrap(),
+        "HelloWorld"
+    );
+    assert!(substitute(text, &alphabet, PassThrough::Reject, identity).is_err());
+
+    assert!(Alphabet::new("abca").is_err());
+    assert!(Alphabet::new("a").is_err());
+
+    assert_eq!(get_char_index('c'), Ok(2));
+    assert_eq!(get_char_index('!'), Err("Invalid character".to_string()));
+    assert_eq!(get_char_at_index(25), Ok('z'));
+    assert_eq!(get_char_at_index(-1), Err("Invalid character".to_string()));
+}
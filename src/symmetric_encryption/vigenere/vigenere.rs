@@ -0,0 +1,289 @@
+use crate::symmetric_encryption::utils::{default_alphabet, substitute, Alphabet, PassThrough};
+
+/// How a key letter $k$ combines with a text letter, modulo the alphabet size $n$
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tableau {
+    /// $y = x + k$, $x = y - k$
+    #[default]
+    Vigenere,
+    /// $y = k - x$, $x = k - y$: encryption and decryption are the same
+    Beaufort,
+    /// $y = x - k$, $x = y + k$: Vigenère with encryption and decryption swapped
+    VariantBeaufort,
+}
+
+impl Tableau {
+    pub fn encrypt_index(&self, x: usize, k: usize, n: usize) -> usize {
+        match self {
+            Tableau::Vigenere => (x + k) % n,
+            Tableau::Beaufort => (n + k - x) % n,
+            Tableau::VariantBeaufort => (n + x - k) % n,
+        }
+    }
+
+    pub fn decrypt_index(&self, y: usize, k: usize, n: usize) -> usize {
+        match self {
+            Tableau::Vigenere => (n + y - k) % n,
+            Tableau::Beaufort => (n + k - y) % n,
+            Tableau::VariantBeaufort => (y + k) % n,
+        }
+    }
+}
+
+/// Vigenère encryption over the lowercase alphabet
+pub fn encrypt(text: &str, key: &str) -> Result<String, String> {
+    encrypt_with(
+        text,
+        key,
+        Tableau::Vigenere,
+        default_alphabet(),
+        PassThrough::Reject,
+    )
+}
+
+/// Vigenère decryption over the lowercase alphabet
+pub fn decrypt(cipher: &str, key: &str) -> Result<String, String> {
+    decrypt_with(
+        cipher,
+        key,
+        Tableau::Vigenere,
+        default_alphabet(),
+        PassThrough::Reject,
+    )
+}
+
+/// Periodic polyalphabetic encryption: the $i$-th letter is combined with
+/// the key letter $k_{i \bmod m}$
+///
+/// Characters outside the alphabet do not consume key letters.
+pub fn encrypt_with(
+    text: &str,
+    key: &str,
+    tableau: Tableau,
+    alphabet: &Alphabet,
+    policy: PassThrough,
+) -> Result<String, String> {
+    let key = key_indices(key, alphabet)?;
+    let mut position = 0;
+
+    substitute(text, alphabet, policy, |x| {
+        let k = key[position % key.len()];
+        position += 1;
+        tableau.encrypt_index(x, k, alphabet.len())
+    })
+}
+
+pub fn decrypt_with(
+    cipher: &str,
+    key: &str,
+    tableau: Tableau,
+    alphabet: &Alphabet,
+    policy: PassThrough,
+) -> Result<String, String> {
+    let key = key_indices(key, alphabet)?;
+    let mut position = 0;
+
+    substitute(cipher, alphabet, policy, |y| {
+        let k = key[position % key.len()];
+        position += 1;
+        tableau.decrypt_index(y, k, alphabet.len())
+    })
+}
+
+/// Autokey encryption over the lowercase alphabet
+pub fn autokey_encrypt(text: &str, key: &str) -> Result<String, String> {
+    autokey_encrypt_with(
+        text,
+        key,
+        Tableau::Vigenere,
+        default_alphabet(),
+        PassThrough::Reject,
+    )
+}
+
+/// Autokey decryption over the lowercase alphabet
+pub fn autokey_decrypt(cipher: &str, key: &str) -> Result<String, String> {
+    autokey_decrypt_with(
+        cipher,
+        key,
+        Tableau::Vigenere,
+        default_alphabet(),
+        PassThrough::Reject,
+    )
+}
+
+/// Autokey encryption: the key is the primer followed by the plaintext itself
+///
+/// $k = k_0 k_1 \dots k_{m-1} x_0 x_1 \dots$, so the key never repeats and
+/// Kasiski examination does not apply.
+pub fn autokey_encrypt_with(
+    text: &str,
+    key: &str,
+    tableau: Tableau,
+    alphabet: &Alphabet,
+    policy: PassThrough,
+) -> Result<String, String> {
+    let mut keystream = key_indices(key, alphabet)?;
+    let mut position = 0;
+
+    substitute(text, alphabet, policy, |x| {
+        keystream.push(x);
+        let k = keystream[position];
+        position += 1;
+        tableau.encrypt_index(x, k, alphabet.len())
+    })
+}
+
+/// Autokey decryption, each recovered letter extends the keystream
+pub fn autokey_decrypt_with(
+    cipher: &str,
+    key: &str,
+    tableau: Tableau,
+    alphabet: &Alphabet,
+    policy: PassThrough,
+) -> Result<String, String> {
+    let mut keystream = key_indices(key, alphabet)?;
+    let mut position = 0;
+
+    substitute(cipher, alphabet, policy, |y| {
+        let x = tableau.decrypt_index(y, keystream[position], alphabet.len());
+        keystream.push(x);
+        position += 1;
+        x
+    })
+}
+
+/// Indices of the key letters, case-insensitive
+fn key_indices(key: &str, alphabet: &Alphabet) -> Result<Vec<usize>, String> {
+    if key.is_empty() {
+        return Err("Empty key".to_string());
+    }
+
+    key.chars()
+        .map(|c| {
+            alphabet
+                .index_of_folded(c)
+                .map(|(index, _)| index)
+                .ok_or("Invalid key".to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn test_vigenere() {
+    assert_eq!(encrypt("attackatdawn", "lemon").unwrap(), "lxfopvefrnhr");
+    assert_eq!(decrypt("lxfopvefrnhr", "LEMON").unwrap(), "attackatdawn");
+
+    // case and punctuation carry over, only letters advance the key
+    let alphabet = default_alphabet();
+    let cipher = encrypt_with(
+        "Attack at dawn!",
+        "lemon",
+        Tableau::Vigenere,
+        alphabet,
+        PassThrough::Preserve,
+    )
+    .unwrap();
+    assert_eq!(cipher, "Lxfopv ef rnhr!");
+
+    assert_eq!(encrypt("abc", "").unwrap_err(), "Empty key");
+    assert_eq!(encrypt("abc", "k3y").unwrap_err(), "Invalid key");
+
+    let cyrillic = Alphabet::cyrillic();
+    let cipher = encrypt_with(
+        "шифр",
+        "ключ",
+        Tableau::Vigenere,
+        &cyrillic,
+        PassThrough::Reject,
+    )
+    .unwrap();
+    assert_eq!(
+        decrypt_with(
+            &cipher,
+            "ключ",
+            Tableau::Vigenere,
+            &cyrillic,
+            PassThrough::Reject
+        )
+        .unwrap(),
+        "шифр"
+    );
+}
+
+#[test]
+fn test_beaufort() {
+    let alphabet = default_alphabet();
+    let text = "defendtheeastwallofthecastle";
+    let cipher = "ckmpvcpvwpiwujogiuapvwriwuuk";
+
+    let encrypted = encrypt_with(
+        text,
+        "fortification",
+        Tableau::Beaufort,
+        alphabet,
+        PassThrough::Reject,
+    )
+    .unwrap();
+    assert_eq!(encrypted, cipher);
+
+    // reciprocal: encrypting the ciphertext again gives the plaintext back
+    let decrypted = encrypt_with(
+        cipher,
+        "fortification",
+        Tableau::Beaufort,
+        alphabet,
+        PassThrough::Reject,
+    )
+    .unwrap();
+    assert_eq!(decrypted, text);
+
+    // variant Beaufort encryption is Vigenère decryption
+    let variant = encrypt_with(
+        text,
+        "fortification",
+        Tableau::VariantBeaufort,
+        alphabet,
+        PassThrough::Reject,
+    )
+    .unwrap();
+    assert_eq!(variant, decrypt(text, "fortification").unwrap());
+    assert_eq!(
+        decrypt_with(
+            &variant,
+            "fortification",
+            Tableau::VariantBeaufort,
+            alphabet,
+            PassThrough::Reject
+        )
+        .unwrap(),
+        text
+    );
+}
+
+#[test]
+fn test_autokey() {
+    assert_eq!(
+        autokey_encrypt("attackatdawn", "queenly").unwrap(),
+        "qnxepvytwtwp"
+    );
+    assert_eq!(
+        autokey_decrypt("qnxepvytwtwp", "queenly").unwrap(),
+        "attackatdawn"
+    );
+
+    for tableau in [
+        Tableau::Vigenere,
+        Tableau::Beaufort,
+        Tableau::VariantBeaufort,
+    ] {
+        let alphabet = default_alphabet();
+        let text = "Meet me near the old bridge at midnight.";
+        let cipher =
+            autokey_encrypt_with(text, "kilo", tableau, alphabet, PassThrough::Preserve).unwrap();
+        let decrypted =
+            autokey_decrypt_with(&cipher, "kilo", tableau, alphabet, PassThrough::Preserve)
+                .unwrap();
+        assert_eq!(decrypted, text);
+    }
+}
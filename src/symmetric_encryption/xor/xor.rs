@@ -0,0 +1,112 @@
+use crate::common::bits::{Bits, BitsError};
+use thiserror::Error;
+
+/// One-time pad on bytes: $c_i = m_i \oplus k_i$
+///
+/// The key must be at least as long as the message, only its first
+/// `message.len()` bytes are used. Decryption is the same operation.
+pub fn one_time_pad(message: &[u8], key: &[u8]) -> Result<Vec<u8>, XorError> {
+    if key.len() < message.len() {
+        return Err(XorError::KeyTooShort {
+            key: key.len(),
+            message: message.len(),
+        });
+    }
+    Ok(xor(message, &key[..message.len()]))
+}
+
+/// One-time pad on bits, e.g. a message XORed with the keystream of a
+/// stream cipher such as Trivium
+pub fn one_time_pad_bits(message: &Bits, key: &Bits) -> Result<Vec<u8>, XorError> {
+    Ok(message.xor(key)?)
+}
+
+/// Repeating-key XOR: $c_i = m_i \oplus k_{i \bmod n}$
+///
+/// A key shorter than the message is reused, which turns the one-time pad
+/// into a Vigenère cipher on bytes: see `cryptanalysis::xor`.
+pub fn repeating_key(message: &[u8], key: &[u8]) -> Result<Vec<u8>, XorError> {
+    if key.is_empty() {
+        return Err(XorError::EmptyKey);
+    }
+    Ok(message
+        .iter()
+        .zip(key.iter().cycle())
+        .map(|(m, k)| m ^ k)
+        .collect())
+}
+
+/// XOR of every byte with the same key byte, the Caesar cipher of bytes
+pub fn single_byte(message: &[u8], key: u8) -> Vec<u8> {
+    message.iter().map(|m| m ^ key).collect()
+}
+
+/// Byte-wise XOR of two slices, truncated to the shorter one
+pub fn xor(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b).map(|(x, y)| x ^ y).collect()
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum XorError {
+    #[error("Bits error: {0}")]
+    BitsError(#[from] BitsError),
+
+    #[error("Key too short: {key} bytes for a message of {message} bytes")]
+    KeyTooShort { key: usize, message: usize },
+
+    #[error("Empty key")]
+    EmptyKey,
+}
+
+#[test]
+fn test_xor() {
+    use crate::common::hex::Hex;
+
+    let message = b"Burning 'em, if you ain't quick and nimble\nI go crazy when I hear a cymbal";
+    let cipher = repeating_key(message, b"ICE").unwrap();
+    // Cryptopals set 1, challenge 5
+    let expected = Hex::hex_to_bytes(
+        b"0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63343c2a26226324272765272\
+          a282b2f20430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f",
+    )
+    .unwrap();
+    assert_eq!(cipher, expected);
+    assert_eq!(repeating_key(&cipher, b"ICE").unwrap(), message);
+    assert_eq!(repeating_key(message, b""), Err(XorError::EmptyKey));
+
+    assert_eq!(single_byte(b"abc", 0x20), b"ABC");
+    assert_eq!(xor(b"abc", &[0x20, 0x20]), b"AB");
+}
+
+#[test]
+fn test_one_time_pad() {
+    use crate::common::hex::Hex;
+
+    let key = [0x5a, 0x13, 0xc4, 0x7e, 0x01, 0xff];
+    let cipher = one_time_pad(b"attack", &key).unwrap();
+    assert_eq!(one_time_pad(&cipher, &key).unwrap(), b"attack");
+    assert_eq!(
+        one_time_pad(b"attack at dawn", &key),
+        Err(XorError::KeyTooShort {
+            key: 6,
+            message: 14
+        })
+    );
+
+    // the bit-level pad agrees with the byte-level one
+    let message = Hex::new("attack").unwrap().to_bits_msb();
+    let key_bits = Hex::new(&key).unwrap().to_bits_msb();
+    let cipher_bits = one_time_pad_bits(
+        &Bits::new(&message).unwrap(),
+        &Bits::new(&key_bits).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(Bits::new(&cipher_bits).unwrap().as_vec_bytes(), cipher);
+    assert_eq!(
+        one_time_pad_bits(
+            &Bits::new(&message).unwrap(),
+            &Bits::new(&key_bits[..8]).unwrap()
+        ),
+        Err(XorError::BitsError(BitsError::LengthMismatch))
+    );
+}